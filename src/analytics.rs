@@ -0,0 +1,275 @@
+//! Модуль аналитики по наборам транзакций.
+//!
+//! Содержит агрегирующие функции, которые работают поверх уже разобранных
+//! транзакций и не зависят от формата, из которого они были прочитаны.
+
+use std::collections::HashMap;
+
+use crate::types::{Transaction, TxStatus, TxType};
+
+/// Количество транзакций в каждом статусе и их доли от общего числа.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatusRates {
+    /// Общее количество транзакций.
+    pub total: usize,
+    /// Количество транзакций в статусе [`TxStatus::Success`].
+    pub success: usize,
+    /// Количество транзакций в статусе [`TxStatus::Failure`].
+    pub failure: usize,
+    /// Количество транзакций в статусе [`TxStatus::Pending`].
+    pub pending: usize,
+}
+
+impl StatusRates {
+    fn add(&mut self, status: TxStatus) {
+        self.total += 1;
+        match status {
+            TxStatus::Success => self.success += 1,
+            TxStatus::Failure => self.failure += 1,
+            TxStatus::Pending => self.pending += 1,
+        }
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 / self.total as f64
+        }
+    }
+
+    /// Доля успешных транзакций (`0.0` для пустого набора).
+    pub fn success_rate(&self) -> f64 {
+        self.rate(self.success)
+    }
+
+    /// Доля транзакций, завершившихся ошибкой (`0.0` для пустого набора).
+    pub fn failure_rate(&self) -> f64 {
+        self.rate(self.failure)
+    }
+
+    /// Доля транзакций в ожидании обработки (`0.0` для пустого набора).
+    pub fn pending_rate(&self) -> f64 {
+        self.rate(self.pending)
+    }
+}
+
+/// Считает распределение транзакций по статусам.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::analytics::status_rates;
+///
+/// let rates = status_rates(&[]);
+/// assert_eq!(rates.total, 0);
+/// assert_eq!(rates.failure_rate(), 0.0);
+/// ```
+pub fn status_rates(transactions: &[Transaction]) -> StatusRates {
+    let mut rates = StatusRates::default();
+    for tx in transactions {
+        rates.add(tx.status);
+    }
+    rates
+}
+
+/// Считает распределение транзакций по статусам отдельно для каждого типа транзакции.
+///
+/// В результат попадают только типы, встретившиеся в наборе.
+pub fn status_rates_by_type(transactions: &[Transaction]) -> HashMap<TxType, StatusRates> {
+    let mut result = HashMap::<TxType, StatusRates>::new();
+    for tx in transactions {
+        result.entry(tx.r#type).or_default().add(tx.status);
+    }
+    result
+}
+
+/// Суммирует поле `amount` всех транзакций.
+///
+/// Сумма считается в `u128`, поэтому не переполняется даже на наборах,
+/// сумма которых не помещается в `u64`.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::analytics::total_amount;
+/// use ypbank_parser::types::TransactionBuilder;
+///
+/// let tx = TransactionBuilder::new().amount(u64::MAX).build();
+/// assert_eq!(total_amount(&[tx.clone(), tx]), 2 * u128::from(u64::MAX));
+/// ```
+pub fn total_amount(transactions: &[Transaction]) -> u128 {
+    transactions.iter().map(|tx| u128::from(tx.amount)).sum()
+}
+
+/// Суммирует поле `amount` отдельно для каждого типа транзакции (см. [`total_amount`]).
+///
+/// В результат попадают только типы, встретившиеся в наборе.
+pub fn amount_by_type(transactions: &[Transaction]) -> HashMap<TxType, u128> {
+    let mut result = HashMap::<TxType, u128>::new();
+    for tx in transactions {
+        *result.entry(tx.r#type).or_default() += u128::from(tx.amount);
+    }
+    result
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// 64-битный FNV-1a: результат не зависит от запуска, платформы и версии std.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_be_bytes());
+    }
+}
+
+/// Считает стабильный 64-битный отпечаток набора транзакций.
+///
+/// Поля каждой транзакции хешируются по порядку (`id`, тип, отправитель, получатель,
+/// сумма, время, статус, описание) алгоритмом FNV-1a; тип и статус учитываются своими
+/// числовыми кодами, описание — длиной и байтами UTF-8. Значение воспроизводимо между
+/// запусками и машинами, поэтому совпадение отпечатков позволяет быстро убедиться,
+/// что две выгрузки одинаковы. Порядок транзакций в наборе влияет на результат.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::analytics::fingerprint;
+/// use ypbank_parser::types::TransactionBuilder;
+///
+/// let txs = [TransactionBuilder::new().id(1).amount(100).build()];
+/// assert_eq!(fingerprint(&txs), fingerprint(&txs.clone()));
+/// assert_ne!(fingerprint(&txs), fingerprint(&[]));
+/// ```
+pub fn fingerprint(transactions: &[Transaction]) -> u64 {
+    let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+    hasher.write_u64(transactions.len() as u64);
+    for tx in transactions {
+        hasher.write_u64(tx.id);
+        hasher.write(&[u8::from(tx.r#type)]);
+        hasher.write_u64(tx.from_user);
+        hasher.write_u64(tx.to_user);
+        hasher.write_u64(tx.amount);
+        hasher.write_u64(tx.timestamp);
+        hasher.write(&[u8::from(tx.status)]);
+        hasher.write_u64(tx.description.len() as u64);
+        hasher.write(tx.description.as_bytes());
+    }
+    hasher.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(r#type: TxType, status: TxStatus) -> Transaction {
+        Transaction {
+            id: 1,
+            r#type,
+            from_user: 501,
+            to_user: 502,
+            amount: 100,
+            timestamp: 1672531200000,
+            status,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_status_rates() {
+        let txs = vec![
+            tx(TxType::Deposit, TxStatus::Success),
+            tx(TxType::Deposit, TxStatus::Success),
+            tx(TxType::Withdrawal, TxStatus::Failure),
+            tx(TxType::Withdrawal, TxStatus::Pending),
+        ];
+
+        let rates = status_rates(&txs);
+
+        assert_eq!(rates.total, 4);
+        assert_eq!(rates.success, 2);
+        assert_eq!(rates.success_rate(), 0.5);
+        assert_eq!(rates.failure_rate(), 0.25);
+        assert_eq!(rates.pending_rate(), 0.25);
+
+        let by_type = status_rates_by_type(&txs);
+
+        assert_eq!(by_type.len(), 2);
+        assert_eq!(by_type[&TxType::Deposit].success_rate(), 1.0);
+        assert_eq!(by_type[&TxType::Withdrawal].failure_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_amount_sums_exceed_u64() {
+        let mut near_max = tx(TxType::Deposit, TxStatus::Success);
+        near_max.amount = u64::MAX - 1;
+        let mut fee = tx(TxType::Fee, TxStatus::Success);
+        fee.amount = 10;
+        let txs = vec![near_max.clone(), near_max, fee];
+
+        let expected = 2 * u128::from(u64::MAX - 1);
+        assert_eq!(total_amount(&txs), expected + 10);
+
+        let by_type = amount_by_type(&txs);
+        assert_eq!(by_type.len(), 2);
+        assert_eq!(by_type[&TxType::Deposit], expected);
+        assert_eq!(by_type[&TxType::Fee], 10);
+        assert_eq!(total_amount(&[]), 0);
+    }
+
+    #[test]
+    fn test_status_rates_empty() {
+        let rates = status_rates(&[]);
+
+        assert_eq!(rates, StatusRates::default());
+        assert_eq!(rates.success_rate(), 0.0);
+        assert_eq!(rates.failure_rate(), 0.0);
+        assert_eq!(rates.pending_rate(), 0.0);
+        assert!(status_rates_by_type(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut txs = vec![
+            tx(TxType::Deposit, TxStatus::Success),
+            tx(TxType::Transfer, TxStatus::Pending),
+        ];
+        txs[1].description = "Payment".to_string();
+        let expected = fingerprint(&txs);
+
+        assert_eq!(fingerprint(&txs.clone()), expected);
+        assert_ne!(fingerprint(&txs[..1]), expected);
+
+        let mut swapped = txs.clone();
+        swapped[0].from_user = txs[0].to_user;
+        swapped[0].to_user = txs[0].from_user;
+        assert_ne!(fingerprint(&swapped), expected);
+
+        let reordered = vec![txs[1].clone(), txs[0].clone()];
+        assert_ne!(fingerprint(&reordered), expected);
+
+        let mut status = txs.clone();
+        status[1].status = TxStatus::Success;
+        assert_ne!(fingerprint(&status), expected);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Известные значения FNV-1a защищают от случайной смены алгоритма или порядка полей.
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        hasher.write(b"a");
+        assert_eq!(hasher.0, 0xaf63_dc4c_8601_ec8c);
+
+        assert_eq!(fingerprint(&[]), fingerprint(&[]));
+        let txs = [tx(TxType::Deposit, TxStatus::Success)];
+        assert_eq!(fingerprint(&txs), 0x400c_1cfc_e9fc_59e2);
+    }
+}
@@ -0,0 +1,75 @@
+//! Генераторы для property-based тестов: реализации [`proptest::arbitrary::Arbitrary`]
+//! для [`Transaction`], [`TxType`] и [`TxStatus`]. Доступны с фичей `proptest`.
+
+use proptest::prelude::*;
+
+use crate::types::{Transaction, TxStatus, TxType};
+
+impl Arbitrary for TxType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    // Упрощение идёт к первому варианту, `Deposit`.
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::sample::select(TxType::all()).boxed()
+    }
+}
+
+impl Arbitrary for TxStatus {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::sample::select(TxStatus::all()).boxed()
+    }
+}
+
+// Символ описания. Чаще всего — печатные ASCII, среди которых кавычки, запятые, `\`, `:`,
+// `=`, `<` и `&`, проверяющие экранирование; реже — переводы строк и табуляция,
+// кириллица, символы вне BMP и произвольные символы Unicode. Исключены только символы,
+// которые XML 1.0 не допускает даже в виде ссылки (U+0001 и другие управляющие, кроме
+// `\t`, `\n` и `\r`, а также U+FFFE и U+FFFF): такое описание формат XML не записывает.
+fn description_char() -> impl Strategy<Value = char> {
+    prop_oneof![
+        8 => proptest::char::range(' ', '~'),
+        1 => prop_oneof![Just('\n'), Just('\r'), Just('\t')],
+        1 => proptest::char::range('а', 'я'),
+        1 => prop_oneof![Just('é'), Just('€'), Just('💳')],
+        1 => any::<char>().prop_filter("allowed in XML 1.0", |c| {
+            !matches!(c, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}')
+        }),
+    ]
+}
+
+impl Arbitrary for Transaction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<u64>(),
+            any::<TxType>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<TxStatus>(),
+            proptest::collection::vec(description_char(), 0..40),
+        )
+            .prop_map(
+                |(id, r#type, from_user, to_user, amount, timestamp, status, description)| {
+                    Transaction {
+                        id,
+                        r#type,
+                        from_user,
+                        to_user,
+                        amount,
+                        timestamp,
+                        status,
+                        description: description.into_iter().collect(),
+                    }
+                },
+            )
+            .boxed()
+    }
+}
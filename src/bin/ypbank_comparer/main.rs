@@ -1,28 +1,15 @@
 use clap::Parser;
 use core::fmt;
-use std::{fs, io, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
 use ypbank_parser::{
-    error,
-    types::{self, Transaction},
+    analytics, diff, error,
+    types::{self, Transaction, TxField},
 };
 
-#[derive(clap::ValueEnum, Clone, Debug)]
-enum KnownFileFormat {
-    Bin,
-    Csv,
-    Text,
-}
-
-impl KnownFileFormat {
-    fn as_supported(&self) -> types::SupportedFileFormat {
-        match self {
-            KnownFileFormat::Bin => types::SupportedFileFormat::Bin,
-            KnownFileFormat::Csv => types::SupportedFileFormat::Csv,
-            KnownFileFormat::Text => types::SupportedFileFormat::Text,
-        }
-    }
-}
-
 #[derive(Debug)]
 enum Error {
     Parse(String),
@@ -31,6 +18,10 @@ enum Error {
     IO(String),
 }
 
+// Код завершения, если наборы транзакций различаются. Коды 1–4 заняты ошибками
+// (см. `Error::code`), 0 означает, что наборы идентичны.
+const DIFFERENT_EXIT_CODE: i32 = 5;
+
 impl Error {
     fn code(&self) -> i32 {
         match self {
@@ -56,8 +47,25 @@ impl fmt::Display for Error {
 impl From<error::ParseError> for Error {
     fn from(value: error::ParseError) -> Self {
         match value {
-            error::ParseError::IOError(str) => Error::Parse(str),
+            error::ParseError::IOError(str) => Error::IO(str),
             error::ParseError::InvalidFormat(err) => Error::Parse(err.to_string()),
+            error::ParseError::ChecksumMismatch { expected, actual } => Error::Parse(format!(
+                "checksum mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            )),
+            error::ParseError::TruncatedRecord { recovered } => Error::Parse(format!(
+                "truncated record after {} complete records",
+                recovered
+            )),
+            error::ParseError::RecordTooLarge { size } => {
+                Error::Parse(format!("record too large: {} bytes", size))
+            }
+            error::ParseError::DuplicateId { id } => {
+                Error::Parse(format!("duplicate transaction id: {}", id))
+            }
+            error::ParseError::ImplausibleTimestamp { value } => {
+                Error::Parse(format!("implausible timestamp: {}", value))
+            }
         }
     }
 }
@@ -67,6 +75,14 @@ impl From<error::DumpError> for Error {
         match value {
             error::DumpError::InternalError => Error::Dump("internal dump error".to_string()),
             error::DumpError::OutputError => Error::Dump("dump error".to_string()),
+            error::DumpError::Io { message, .. } => Error::Dump(format!("dump error: {}", message)),
+            error::DumpError::DescriptionTooLong { len } => {
+                Error::Dump(format!("description too long: {} bytes", len))
+            }
+            error::DumpError::InvalidColumns(msg) => {
+                Error::Dump(format!("invalid columns: {}", msg))
+            }
+            err @ error::DumpError::InvalidXmlChar { .. } => Error::Dump(err.to_string()),
         }
     }
 }
@@ -77,23 +93,180 @@ impl From<io::Error> for Error {
     }
 }
 
+// Значение поля в сообщении о расхождении; описание выводится в кавычках.
+fn field_value(field: TxField, tx: &Transaction) -> String {
+    match field {
+        TxField::Id => tx.id.to_string(),
+        TxField::Type => tx.r#type.to_string(),
+        TxField::FromUser => tx.from_user.to_string(),
+        TxField::ToUser => tx.to_user.to_string(),
+        TxField::Amount => tx.amount.to_string(),
+        TxField::Timestamp => tx.timestamp.to_string(),
+        TxField::Status => tx.status.to_string(),
+        TxField::Description => format!("{:?}", tx.description),
+    }
+}
+
+#[derive(Debug, Default)]
+struct CompareOptions {
+    ignore_fields: Vec<TxField>,
+    trim_description: bool,
+}
+
+impl CompareOptions {
+    // Проверяет, отличается ли поле у двух транзакций. Игнорируемые поля не отличаются.
+    fn differs(&self, field: TxField, l: &Transaction, r: &Transaction) -> bool {
+        if self.ignore_fields.contains(&field) {
+            return false;
+        }
+        match field {
+            TxField::Description if self.trim_description => {
+                l.description.trim() != r.description.trim()
+            }
+            _ => !field.eq_in(l, r),
+        }
+    }
+
+    // Сравнивает транзакции без учёта игнорируемых полей.
+    fn eq(&self, l: &Transaction, r: &Transaction) -> bool {
+        if !self.trim_description {
+            return l.eq_ignoring(r, &self.ignore_fields);
+        }
+        TxField::ALL.iter().all(|field| !self.differs(*field, l, r))
+    }
+
+    // Перечисляет отличающиеся поля в виде `amount: 500 != 600`.
+    fn field_diff(&self, l: &Transaction, r: &Transaction) -> Vec<String> {
+        TxField::ALL
+            .iter()
+            .filter(|field| self.differs(**field, l, r))
+            .map(|field| {
+                format!(
+                    "{}: {} != {}",
+                    field,
+                    field_value(*field, l),
+                    field_value(*field, r)
+                )
+            })
+            .collect()
+    }
+}
+
 #[derive(Parser, Debug)]
 struct Args {
-    /// Input file path
-    #[arg(long, required = true)]
-    file1: PathBuf,
+    /// Input file path (`-` for stdin)
+    #[arg(long, required_unless_present = "list_formats")]
+    file1: Option<PathBuf>,
+
+    /// Input file type: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "or protobuf")]
+    #[arg(long, required_unless_present = "list_formats")]
+    format1: Option<types::SupportedFileFormat>,
+
+    /// Input file path (`-` for stdin)
+    #[arg(long, required_unless_present = "list_formats")]
+    file2: Option<PathBuf>,
+
+    /// Output file type: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "or protobuf")]
+    #[arg(long, required_unless_present = "list_formats")]
+    format2: Option<types::SupportedFileFormat>,
+
+    /// Поля, не учитываемые при сравнении, через запятую (например, description,timestamp):
+    /// id, type, from_user, to_user, amount, timestamp, status, description
+    #[arg(long, value_delimiter = ',')]
+    ignore_fields: Vec<TxField>,
+
+    /// Не учитывать пробелы в начале и конце описания при сравнении
+    #[arg(long)]
+    trim_description: bool,
 
-    /// Input file type: text/csv/bin
-    #[arg(long, required = true)]
-    format1: KnownFileFormat,
+    /// Перед сравнением обрезать описание, снять внешние кавычки и схлопнуть пробелы внутри
+    #[arg(long)]
+    normalize: bool,
 
-    /// Input file path
-    #[arg(long, required = true)]
-    file2: PathBuf,
+    /// Вывести 64-битные отпечатки обоих наборов транзакций (см. analytics::fingerprint)
+    #[arg(long)]
+    fingerprint: bool,
 
-    /// Output file type: text/csv/bin
-    #[arg(long, required = true)]
-    format2: KnownFileFormat,
+    /// Записать отличающиеся транзакции из file2 в файл PATH в формате FORMAT: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "или protobuf")]
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "PATH"])]
+    dump_diffs: Option<Vec<String>>,
+
+    /// Вместо сравнения объединить оба файла (без полных дубликатов, по возрастанию id)
+    /// и вывести результат в stdout
+    #[arg(long, requires = "output_format", conflicts_with = "dump_diffs")]
+    merge: bool,
+
+    /// Формат вывода для --merge: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "или protobuf")]
+    #[arg(long, requires = "merge")]
+    output_format: Option<types::SupportedFileFormat>,
+
+    /// Сравнить только количество транзакций в файлах, без сравнения содержимого.
+    /// Файлы BIN при этом проверяются по заголовкам записей без разбора транзакций
+    #[arg(long, conflicts_with_all = ["merge", "dump_diffs", "fingerprint"])]
+    count_only: bool,
+
+    /// Вывести список поддерживаемых форматов и выйти
+    #[arg(long, exclusive = true)]
+    list_formats: bool,
+}
+
+// Список поддерживаемых форматов с кратким описанием, по одному на строку.
+fn format_list() -> String {
+    types::SupportedFileFormat::ALL
+        .iter()
+        .map(|format| format!("{:<10}{}\n", format.to_string(), format.description()))
+        .collect()
+}
+
+// Открывает входной файл; путь `-` означает стандартный ввод.
+fn open_input(path: &Path) -> io::Result<Box<dyn io::Read>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(fs::File::open(path)?))
+}
+
+// Ошибка разбора файла с номером `n`: ошибки ввода-вывода сохраняют свой код завершения.
+fn read_error(n: usize, err: error::ParseError) -> Error {
+    match Error::from(err) {
+        Error::IO(msg) => Error::IO(format!("ошибка чтения файла {}: {}", n, msg)),
+        err => Error::Parse(format!(
+            "ошибка при разборе транзакций файла {}: {}",
+            n, err
+        )),
+    }
+}
+
+// Считает транзакции в потоке. BIN проверяется структурно (`verify_bin`),
+// без разбора тел записей; остальные форматы разбираются целиком.
+fn count_transactions(
+    reader: &mut impl io::Read,
+    format: types::SupportedFileFormat,
+) -> Result<usize, error::ParseError> {
+    match format {
+        types::SupportedFileFormat::Bin => ypbank_parser::verify_bin(reader),
+        _ => Ok(ypbank_parser::parse(reader, format)?.len()),
+    }
+}
+
+// Приводит транзакцию к каноническому виду для сравнения данных, выгруженных разными системами.
+// У описания убираются пробелы по краям и одна пара внешних кавычек (`"..."` или `'...'`),
+// подряд идущие пробельные символы заменяются одним пробелом. Остальные поля уже
+// приведены к единому виду при разборе и не меняются.
+fn normalize(tx: &Transaction) -> Transaction {
+    let trimmed = tx.description.trim();
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|&q| trimmed.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(trimmed);
+    Transaction {
+        description: unquoted.split_whitespace().collect::<Vec<_>>().join(" "),
+        ..tx.clone()
+    }
 }
 
 // Сравнивает набор транзакций.
@@ -103,71 +276,457 @@ struct Args {
 fn compare<'a>(
     lhs: &'a [Transaction],
     rhs: &'a [Transaction],
+    options: &CompareOptions,
 ) -> Option<(usize, Option<&'a Transaction>, Option<&'a Transaction>)> {
-    for i in 0..std::cmp::max(lhs.len(), rhs.len()) {
-        let l = lhs.get(i);
-        let r = rhs.get(i);
-        if l.is_none() || r.is_none() || l.unwrap() != r.unwrap() {
-            return Some((i, l, r));
+    diff::diff_by(lhs, rhs, |l, r| options.eq(l, r))
+        .first()
+        .map(|d| (d.index(), d.lhs(), d.rhs()))
+}
+
+// Записывает во writer транзакции правого набора, отличающиеся от левого.
+// Возвращает количество записанных транзакций.
+fn dump_diffs(
+    writer: &mut impl io::Write,
+    format: types::SupportedFileFormat,
+    lhs: &[Transaction],
+    rhs: &[Transaction],
+    options: &CompareOptions,
+) -> Result<usize, Error> {
+    let changed: Vec<Transaction> = diff::diff_by(lhs, rhs, |l, r| options.eq(l, r))
+        .iter()
+        .filter_map(|d| d.rhs())
+        .cloned()
+        .collect();
+    let written = ypbank_parser::dump(writer, format, &changed)?;
+    Ok(written)
+}
+
+// Объединяет два набора: удаляет полностью совпадающие транзакции (оставляя первое вхождение)
+// и сортирует по id. Транзакции с одинаковым id, но разными полями сохраняются,
+// а их id возвращаются вторым элементом для предупреждения.
+fn merge(lhs: &[Transaction], rhs: &[Transaction]) -> (Vec<Transaction>, Vec<u64>) {
+    let mut seen = HashSet::with_capacity(lhs.len() + rhs.len());
+    let mut merged: Vec<Transaction> = lhs
+        .iter()
+        .chain(rhs)
+        .filter(|tx| seen.insert(*tx))
+        .cloned()
+        .collect();
+    merged.sort_by_key(|tx| tx.id);
+
+    let mut conflicts: Vec<u64> = merged
+        .windows(2)
+        .filter(|pair| pair[0].id == pair[1].id)
+        .map(|pair| pair[0].id)
+        .collect();
+    conflicts.dedup();
+    (merged, conflicts)
+}
+
+// Разбирает аргументы командной строки. Ошибки clap завершают процесс с кодом
+// ошибки использования 3, а не со стандартным для clap кодом 2, занятым ошибками записи.
+fn parse_args() -> Args {
+    Args::try_parse().unwrap_or_else(|err| {
+        if err.use_stderr() {
+            let _ = err.print();
+            std::process::exit(3);
         }
+        err.exit()
+    })
+}
+
+// Проверяет пару FORMAT PATH из --dump-diffs.
+fn parse_dump_target(dump_args: &[String]) -> Result<(types::SupportedFileFormat, PathBuf), Error> {
+    let format = dump_args[0]
+        .parse::<types::SupportedFileFormat>()
+        .map_err(|e| Error::Usage(format!("неизвестный формат {}: {}", dump_args[0], e)))?;
+    if dump_args[1].is_empty() {
+        return Err(Error::Usage("пустой путь в --dump-diffs".to_string()));
     }
-    None
+    Ok((format, PathBuf::from(&dump_args[1])))
 }
 
-fn run() -> Result<(), Error> {
-    let args = Args::parse();
+// Возвращает `true`, если сравнение нашло отличия между наборами.
+fn run() -> Result<bool, Error> {
+    let args = parse_args();
 
-    let file1 = fs::File::open(&args.file1);
-    let Ok(mut f1) = file1 else {
-        return Err(Error::Usage(format!(
-            "невозможно открыть файл {}: {}",
-            args.file1.display(),
-            file1.unwrap_err()
-        )));
+    if args.list_formats {
+        print!("{}", format_list());
+        return Ok(false);
+    }
+    let (Some(path1), Some(format1), Some(path2), Some(format2)) =
+        (&args.file1, args.format1, &args.file2, args.format2)
+    else {
+        return Err(Error::Usage(
+            "не заданы --file1, --format1, --file2 и --format2".to_string(),
+        ));
     };
 
-    let file2 = fs::File::open(&args.file2);
-    let Ok(mut f2) = file2 else {
-        return Err(Error::Usage(format!(
+    if path1 == Path::new("-") && path2 == Path::new("-") {
+        return Err(Error::Usage(
+            "из stdin можно читать только один из файлов".to_string(),
+        ));
+    }
+
+    let dump_target = args
+        .dump_diffs
+        .as_deref()
+        .map(parse_dump_target)
+        .transpose()?;
+
+    let mut f1 = open_input(path1).map_err(|err| {
+        Error::IO(format!(
+            "невозможно открыть файл {}: {}",
+            path1.display(),
+            err
+        ))
+    })?;
+
+    let mut f2 = open_input(path2).map_err(|err| {
+        Error::IO(format!(
             "невозможно открыть файл {}: {}",
-            args.file2.display(),
-            file2.unwrap_err()
-        )));
+            path2.display(),
+            err
+        ))
+    })?;
+
+    if args.count_only {
+        let count1 = count_transactions(&mut f1, format1).map_err(|err| read_error(1, err))?;
+        let count2 = count_transactions(&mut f2, format2).map_err(|err| read_error(2, err))?;
+        if count1 == count2 {
+            println!("Количество транзакций совпадает: {}", count1);
+        } else {
+            println!(
+                "Количество транзакций не совпадает: {} в file1, {} в file2",
+                count1, count2
+            );
+        }
+        return Ok(count1 != count2);
+    }
+
+    // Файл для --dump-diffs создаётся до чтения входных данных, чтобы ошибка его
+    // создания не появлялась после уже напечатанного результата сравнения.
+    let dump_target = match dump_target {
+        Some((format, path)) => {
+            let out = fs::File::create(&path).map_err(|err| {
+                Error::IO(format!(
+                    "невозможно создать файл {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+            Some((format, path, out))
+        }
+        None => None,
     };
 
-    let transactions1 = ypbank_parser::parse(&mut f1, args.format1.as_supported());
-    let Ok(tx1_unwraped) = transactions1 else {
-        return Err(Error::Usage(format!(
-            "ошибка при разборе транзакций файла 1: {:?}",
-            transactions1.unwrap_err()
-        )));
+    let tx1_unwraped = ypbank_parser::parse(&mut f1, format1).map_err(|err| read_error(1, err))?;
+    let tx2_unwraped = ypbank_parser::parse(&mut f2, format2).map_err(|err| read_error(2, err))?;
+    let (tx1_unwraped, tx2_unwraped) = if args.normalize {
+        (
+            tx1_unwraped.iter().map(normalize).collect(),
+            tx2_unwraped.iter().map(normalize).collect(),
+        )
+    } else {
+        (tx1_unwraped, tx2_unwraped)
     };
-    let transactions2 = ypbank_parser::parse(&mut f2, args.format2.as_supported());
-    let Ok(tx2_unwraped) = transactions2 else {
-        return Err(Error::Usage(format!(
-            "ошибка при разборе транзакций файла 2: {:?}",
-            transactions2.unwrap_err()
-        )));
+
+    if args.merge {
+        let (merged, conflicts) = merge(&tx1_unwraped, &tx2_unwraped);
+        for id in conflicts {
+            eprintln!(
+                "Предупреждение: транзакции с id {} различаются, сохранены обе",
+                id
+            );
+        }
+        if let Some(format) = args.output_format {
+            ypbank_parser::dump(&mut io::stdout(), format, &merged)?;
+        }
+        return Ok(false);
+    }
+
+    let options = CompareOptions {
+        ignore_fields: args.ignore_fields,
+        trim_description: args.trim_description,
     };
 
-    let result = compare(&tx1_unwraped, &tx2_unwraped);
+    if args.fingerprint {
+        println!(
+            "Отпечаток file1: {:016x}",
+            analytics::fingerprint(&tx1_unwraped)
+        );
+        println!(
+            "Отпечаток file2: {:016x}",
+            analytics::fingerprint(&tx2_unwraped)
+        );
+    }
+
+    let result = compare(&tx1_unwraped, &tx2_unwraped, &options);
     if let Some(r) = &result {
         println!("Наборы транзакций не иднетичны!");
         println!("Несовпали транзакции на позииции {}", r.0 + 1);
 
-        println!("LHS:\n{:#?}\n\nRHS:\n{:#?}", r.1, r.2);
+        match (r.1, r.2) {
+            (Some(l), Some(r)) => println!("{}", options.field_diff(l, r).join(", ")),
+            (Some(l), None) => println!("Транзакция отсутствует во втором файле:\n{}", l),
+            (None, Some(r)) => println!("Транзакция отсутствует в первом файле:\n{}", r),
+            (None, None) => {}
+        }
     } else {
         println!("Наборы транзакций идентичны!")
     }
-    Ok(())
+
+    if let Some((format, path, mut out)) = dump_target {
+        let count = dump_diffs(&mut out, format, &tx1_unwraped, &tx2_unwraped, &options)?;
+        println!(
+            "Записано отличающихся транзакций: {} в {}",
+            count,
+            path.display()
+        );
+    }
+    Ok(result.is_some())
 }
 
 fn main() {
     match run() {
-        Ok(_) => {}
+        Ok(false) => {}
+        Ok(true) => std::process::exit(DIFFERENT_EXIT_CODE),
         Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(e.code());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ypbank_parser::types::{TxStatus, TxType};
+
+    fn tx(id: u64, amount: u64) -> Transaction {
+        Transaction {
+            id,
+            r#type: TxType::Transfer,
+            from_user: 501,
+            to_user: 502,
+            amount,
+            timestamp: 1672534800000,
+            status: TxStatus::Success,
+            description: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_dump_diffs_contains_only_changed() {
+        let lhs = vec![tx(1, 100), tx(2, 200), tx(3, 300)];
+        let rhs = vec![tx(1, 100), tx(2, 250), tx(3, 300), tx(4, 400)];
+
+        let mut buffer = Vec::new();
+        let count = dump_diffs(
+            &mut buffer,
+            types::SupportedFileFormat::Csv,
+            &lhs,
+            &rhs,
+            &CompareOptions::default(),
+        )
+        .unwrap();
+
+        let dumped =
+            ypbank_parser::parse(&mut buffer.as_slice(), types::SupportedFileFormat::Csv).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(dumped, vec![rhs[1].clone(), rhs[3].clone()]);
+    }
+
+    fn parse_text(description: &str) -> Vec<Transaction> {
+        let data = format!(
+            "TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\nAMOUNT: 50000\n\
+             TIMESTAMP: 1672531200000\nSTATUS: SUCCESS\nDESCRIPTION: \"{}\"\n",
+            description
+        );
+        ypbank_parser::parse(&mut data.as_bytes(), types::SupportedFileFormat::Text).unwrap()
+    }
+
+    #[test]
+    fn test_trim_description() {
+        let lhs = parse_text("Initial funding");
+        let rhs = parse_text("  Initial funding ");
+
+        let strict = compare(&lhs, &rhs, &CompareOptions::default());
+        assert_eq!(strict.map(|r| r.0), Some(0));
+
+        let trimmed = CompareOptions {
+            trim_description: true,
+            ..Default::default()
+        };
+        assert!(compare(&lhs, &rhs, &trimmed).is_none());
+    }
+
+    #[test]
+    fn test_ignore_fields() {
+        let lhs = vec![tx(1, 100), tx(2, 200)];
+        let mut rhs = vec![tx(1, 100), tx(2, 200)];
+        rhs[1].timestamp += 999;
+        rhs[1].description = "changed".to_string();
+
+        let options = CompareOptions {
+            ignore_fields: vec![TxField::Timestamp],
+            ..Default::default()
+        };
+        assert_eq!(compare(&lhs, &rhs, &options).map(|r| r.0), Some(1));
+
+        let options = CompareOptions {
+            ignore_fields: vec![TxField::Timestamp, TxField::Description],
+            ..Default::default()
+        };
+        assert!(compare(&lhs, &rhs, &options).is_none());
+    }
+
+    fn merged_ids(lhs: &[Transaction], rhs: &[Transaction]) -> (Vec<u64>, Vec<u64>) {
+        let (merged, conflicts) = merge(lhs, rhs);
+
+        let mut buffer = Vec::new();
+        ypbank_parser::dump(&mut buffer, types::SupportedFileFormat::Csv, &merged).unwrap();
+        let emitted =
+            ypbank_parser::parse(&mut buffer.as_slice(), types::SupportedFileFormat::Csv).unwrap();
+        (emitted.iter().map(|tx| tx.id).collect(), conflicts)
+    }
+
+    #[test]
+    fn test_merge_clean() {
+        let lhs = vec![tx(3, 300), tx(1, 100)];
+        let rhs = vec![tx(4, 400), tx(2, 200)];
+
+        assert_eq!(merged_ids(&lhs, &rhs), (vec![1, 2, 3, 4], vec![]));
+    }
+
+    #[test]
+    fn test_merge_drops_duplicates() {
+        let lhs = vec![tx(1, 100), tx(2, 200)];
+        let rhs = vec![tx(2, 200), tx(3, 300), tx(1, 100)];
+
+        assert_eq!(merged_ids(&lhs, &rhs), (vec![1, 2, 3], vec![]));
+    }
+
+    #[test]
+    fn test_merge_reports_id_conflicts() {
+        let lhs = vec![tx(1, 100), tx(2, 200)];
+        let rhs = vec![tx(2, 250), tx(3, 300)];
+
+        let (ids, conflicts) = merged_ids(&lhs, &rhs);
+
+        assert_eq!(ids, vec![1, 2, 2, 3]);
+        assert_eq!(conflicts, vec![2]);
+    }
+
+    #[test]
+    fn test_field_diff_lists_changed_fields() {
+        let lhs = tx(1, 500);
+        let mut rhs = tx(1, 600);
+        rhs.status = TxStatus::Pending;
+
+        let options = CompareOptions::default();
+        assert_eq!(
+            options.field_diff(&lhs, &rhs),
+            vec!["amount: 500 != 600", "status: SUCCESS != PENDING"]
+        );
+        assert!(options.field_diff(&lhs, &lhs).is_empty());
+
+        let options = CompareOptions {
+            ignore_fields: vec![TxField::Status],
+            ..Default::default()
+        };
+        assert_eq!(options.field_diff(&lhs, &rhs), vec!["amount: 500 != 600"]);
+    }
+
+    #[test]
+    fn test_field_diff_quotes_description() {
+        let lhs = tx(1, 100);
+        let mut rhs = tx(1, 100);
+        rhs.description = " test".to_string();
+
+        assert_eq!(
+            CompareOptions::default().field_diff(&lhs, &rhs),
+            vec![r#"description: "test" != " test""#]
+        );
+        let trimmed = CompareOptions {
+            trim_description: true,
+            ..Default::default()
+        };
+        assert!(trimmed.field_diff(&lhs, &rhs).is_empty());
+    }
+
+    #[test]
+    fn test_list_formats() {
+        let args = Args::try_parse_from(["ypbank_comparer", "--list-formats"]).unwrap();
+        assert!(args.list_formats);
+        assert!(Args::try_parse_from(["ypbank_comparer", "--list-formats", "--merge"]).is_err());
+
+        let list = format_list();
+        for name in ["csv", "bin", "text"] {
+            assert!(list.lines().any(|line| line.starts_with(name)));
+        }
+        assert_eq!(list.lines().count(), types::SupportedFileFormat::ALL.len());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut messy = tx(1, 100);
+        messy.description = "  \"Payment \t for\n  services\"  ".to_string();
+        let mut quoted = tx(1, 100);
+        quoted.description = "'Payment for services'".to_string();
+
+        assert_eq!(normalize(&messy).description, "Payment for services");
+        assert_eq!(normalize(&messy), normalize(&quoted));
+        assert_eq!(normalize(&normalize(&messy)), normalize(&messy));
+        assert_eq!(normalize(&tx(2, 200)), tx(2, 200));
+    }
+
+    #[test]
+    fn test_normalize_equal_files() {
+        let csv = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1001,DEPOSIT,0,501,0050000,1672531200000,SUCCESS,"  Initial   account funding "
+            1002,TRANSFER,501,502,15000,1672534800000,PENDING,"'Payment'"
+            "##;
+        let text = "TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                    AMOUNT: 50000\nTIMESTAMP: 1672531200000\nSTATUS: SUCCESS\n\
+                    DESCRIPTION: \"Initial account funding\"\n\n\
+                    TX_ID: 1002\nTX_TYPE: TRANSFER\nFROM_USER_ID: 501\nTO_USER_ID: 502\n\
+                    AMOUNT: 15000\nTIMESTAMP: 1672534800000\nSTATUS: PENDING\n\
+                    DESCRIPTION: \"Payment\"\n";
+        let lhs =
+            ypbank_parser::parse(&mut csv.as_bytes(), types::SupportedFileFormat::Csv).unwrap();
+        let rhs =
+            ypbank_parser::parse(&mut text.as_bytes(), types::SupportedFileFormat::Text).unwrap();
+        let options = CompareOptions::default();
+
+        assert!(compare(&lhs, &rhs, &options).is_some());
+
+        let lhs: Vec<Transaction> = lhs.iter().map(normalize).collect();
+        let rhs: Vec<Transaction> = rhs.iter().map(normalize).collect();
+        assert!(compare(&lhs, &rhs, &options).is_none());
+    }
+
+    #[test]
+    fn test_normalize_keeps_status() {
+        let mut pending = tx(1, 100);
+        pending.status = TxStatus::Pending;
+        assert_eq!(normalize(&pending).status, TxStatus::Pending);
+        assert_eq!(normalize(&tx(1, 100)).status, tx(1, 100).status);
+
+        let missing = "TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                       AMOUNT: 100\nTIMESTAMP: 1672531200000\n\n\
+                       TX_ID: 2\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                       AMOUNT: 100\nTIMESTAMP: 1672531200000\nSTATUS: PENDING\n";
+        let empty_csv = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                         1,DEPOSIT,0,501,100,1672531200000,,\"x\"\n";
+        assert!(
+            ypbank_parser::parse(&mut missing.as_bytes(), types::SupportedFileFormat::Text)
+                .is_err()
+        );
+        assert!(
+            ypbank_parser::parse(&mut empty_csv.as_bytes(), types::SupportedFileFormat::Csv)
+                .is_err()
+        );
+    }
+}
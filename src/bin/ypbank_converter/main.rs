@@ -1,42 +1,234 @@
-use std::{fmt, io, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fmt,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
 use std::fs;
-use ypbank_parser::{error, types};
+use ypbank_parser::{error, filters, types};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
-    /// Путь до исходного файла с транзакциями
-    #[arg(long, required = true)]
-    input_file: PathBuf,
+    /// Путь до исходного файла с транзакциями; `-` - читать из stdin
+    #[arg(
+        long,
+        required_unless_present_any = ["manifest", "list_formats"],
+        conflicts_with = "manifest"
+    )]
+    input_file: Option<PathBuf>,
 
-    /// Формат исходного файла: text/csv/bin
-    #[arg(long, required = true)]
-    input_format: KnownFileFormat,
+    /// Путь до манифеста: по одному исходному файлу на строку, строки с `#` - комментарии.
+    /// Относительные пути считаются от каталога манифеста
+    #[arg(long)]
+    manifest: Option<PathBuf>,
 
-    /// Формат выходного файла: text/csv/bin
-    #[arg(long, required = true)]
-    output_format: KnownFileFormat,
+    /// Формат исходного файла: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "или protobuf")]
+    #[arg(long, required_unless_present = "list_formats")]
+    input_format: Option<types::SupportedFileFormat>,
+
+    /// Формат выходного файла: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "или protobuf")]
+    #[arg(long, required_unless_present_any = ["check", "list_formats"])]
+    output_format: Option<types::SupportedFileFormat>,
+
+    /// Записать результат в файл (он создаётся или перезаписывается) вместо stdout
+    #[arg(long, value_name = "PATH", conflicts_with = "check")]
+    output_file: Option<PathBuf>,
+
+    /// Только проверить, что вход разбирается, ничего не записывая в stdout.
+    /// Если задан --output-format, дополнительно проверяется сериализация
+    #[arg(long, conflicts_with = "manifest_out")]
+    check: bool,
+
+    /// Отсортировать транзакции по полю: timestamp/id/amount (при равенстве - по id)
+    #[arg(long)]
+    sort_by: Option<SortField>,
+
+    /// Сортировать в обратном порядке
+    #[arg(long, requires = "sort_by")]
+    reverse: bool,
+
+    /// Удалить полностью совпадающие транзакции, оставив первое вхождение
+    #[arg(long)]
+    dedup: bool,
+
+    /// Оставить только транзакции указанного типа: deposit/transfer/withdrawal/fee/reversal
+    #[arg(long)]
+    filter_type: Option<FilterType>,
+
+    /// Оставить только транзакции с указанным статусом: success/failure/pending
+    #[arg(long)]
+    filter_status: Option<FilterStatus>,
+
+    /// Оставить только транзакции, в которых пользователь является отправителем или получателем
+    #[arg(long)]
+    filter_user: Option<u64>,
+
+    /// Оставить только транзакции с id не меньше указанного
+    #[arg(long)]
+    id_min: Option<u64>,
+
+    /// Оставить только транзакции с id не больше указанного
+    #[arg(long)]
+    id_max: Option<u64>,
+
+    /// Оставить только транзакции с суммой не меньше указанной
+    #[arg(long)]
+    amount_min: Option<u64>,
+
+    /// Оставить только транзакции с суммой не больше указанной
+    #[arg(long)]
+    amount_max: Option<u64>,
+
+    /// Пропустить первые N транзакций (после фильтрации и сортировки)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    skip: usize,
+
+    /// Вывести не более M транзакций (после --skip)
+    #[arg(long, value_name = "M")]
+    limit: Option<usize>,
+
+    /// Обернуть вывод в блок кода markdown (```csv ... ```), только для text/csv/toml
+    #[arg(long)]
+    wrap_fence: bool,
+
+    /// Выровнять имена полей, чтобы двоеточия стояли в одном столбце (только для text)
+    #[arg(long)]
+    pretty: bool,
+
+    /// Разделять записи строкой `---` (вместе с --pretty)
+    #[arg(long, requires = "pretty")]
+    rule: bool,
+
+    /// Записать в PATH JSON-манифест результата: число записей, формат, CRC32 вывода
+    /// и диапазон временных меток
+    #[arg(long, value_name = "PATH")]
+    manifest_out: Option<PathBuf>,
+
+    /// Вывести список поддерживаемых форматов и выйти
+    #[arg(long, exclusive = true)]
+    list_formats: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum SortField {
+    Timestamp,
+    Id,
+    Amount,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
-enum KnownFileFormat {
-    Bin,
-    Csv,
-    Text,
+impl SortField {
+    fn key(&self, tx: &types::Transaction) -> u64 {
+        match self {
+            SortField::Timestamp => tx.timestamp,
+            SortField::Id => tx.id,
+            SortField::Amount => tx.amount,
+        }
+    }
 }
 
-impl KnownFileFormat {
-    fn as_supported(&self) -> types::SupportedFileFormat {
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum FilterType {
+    Deposit,
+    Transfer,
+    Withdrawal,
+    Fee,
+    Reversal,
+}
+
+impl FilterType {
+    fn as_tx_type(&self) -> types::TxType {
         match self {
-            KnownFileFormat::Bin => types::SupportedFileFormat::Bin,
-            KnownFileFormat::Csv => types::SupportedFileFormat::Csv,
-            KnownFileFormat::Text => types::SupportedFileFormat::Text,
+            FilterType::Deposit => types::TxType::Deposit,
+            FilterType::Transfer => types::TxType::Transfer,
+            FilterType::Withdrawal => types::TxType::Withdrawal,
+            FilterType::Fee => types::TxType::Fee,
+            FilterType::Reversal => types::TxType::Reversal,
         }
     }
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum FilterStatus {
+    Success,
+    Failure,
+    Pending,
+}
+
+impl FilterStatus {
+    fn as_tx_status(&self) -> types::TxStatus {
+        match self {
+            FilterStatus::Success => types::TxStatus::Success,
+            FilterStatus::Failure => types::TxStatus::Failure,
+            FilterStatus::Pending => types::TxStatus::Pending,
+        }
+    }
+}
+
+// Диапазон значений с включёнными границами; незаданная граница не ограничивает диапазон.
+#[derive(Debug, Default, Clone, Copy)]
+struct Range {
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+impl Range {
+    // Создаёт диапазон, проверяя, что нижняя граница не больше верхней.
+    // `name` - имя опций в сообщении об ошибке (например, `id` для --id-min/--id-max).
+    fn new(name: &str, min: Option<u64>, max: Option<u64>) -> Result<Self, Error> {
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(Error::Usage(format!(
+                "--{name}-min ({min}) не может быть больше --{name}-max ({max})"
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+// Условия отбора транзакций. Незаданные условия не ограничивают выборку,
+// заданные объединяются через И.
+#[derive(Debug, Default)]
+struct Filter {
+    tx_type: Option<FilterType>,
+    status: Option<FilterStatus>,
+    user: Option<u64>,
+    id: Range,
+    amount: Range,
+}
+
+impl Filter {
+    fn matches(&self, tx: &types::Transaction) -> bool {
+        self.tx_type
+            .is_none_or(|t| filters::by_type(t.as_tx_type())(tx))
+            && self
+                .status
+                .is_none_or(|s| filters::by_status(s.as_tx_status())(tx))
+            && self
+                .user
+                .is_none_or(|user| filters::involving_user(user)(tx))
+            && self.id.contains(tx.id)
+            && filters::amount_between(
+                self.amount.min.unwrap_or(0),
+                self.amount.max.unwrap_or(u64::MAX),
+            )(tx)
+    }
+
+    fn apply(&self, mut transactions: Vec<types::Transaction>) -> Vec<types::Transaction> {
+        transactions.retain(|tx| self.matches(tx));
+        transactions
+    }
+}
+
 #[derive(Debug)]
 enum Error {
     Parse(String),
@@ -70,8 +262,25 @@ impl fmt::Display for Error {
 impl From<error::ParseError> for Error {
     fn from(value: error::ParseError) -> Self {
         match value {
-            error::ParseError::IOError(str) => Error::Parse(str),
+            error::ParseError::IOError(str) => Error::IO(str),
             error::ParseError::InvalidFormat(err) => Error::Parse(err.to_string()),
+            error::ParseError::ChecksumMismatch { expected, actual } => Error::Parse(format!(
+                "checksum mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            )),
+            error::ParseError::TruncatedRecord { recovered } => Error::Parse(format!(
+                "truncated record after {} complete records",
+                recovered
+            )),
+            error::ParseError::RecordTooLarge { size } => {
+                Error::Parse(format!("record too large: {} bytes", size))
+            }
+            error::ParseError::DuplicateId { id } => {
+                Error::Parse(format!("duplicate transaction id: {}", id))
+            }
+            error::ParseError::ImplausibleTimestamp { value } => {
+                Error::Parse(format!("implausible timestamp: {}", value))
+            }
         }
     }
 }
@@ -81,6 +290,14 @@ impl From<error::DumpError> for Error {
         match value {
             error::DumpError::InternalError => Error::Dump("internal dump error".to_string()),
             error::DumpError::OutputError => Error::Dump("dump error".to_string()),
+            error::DumpError::Io { message, .. } => Error::Dump(format!("dump error: {}", message)),
+            error::DumpError::DescriptionTooLong { len } => {
+                Error::Dump(format!("description too long: {} bytes", len))
+            }
+            error::DumpError::InvalidColumns(msg) => {
+                Error::Dump(format!("invalid columns: {}", msg))
+            }
+            err @ error::DumpError::InvalidXmlChar { .. } => Error::Dump(err.to_string()),
         }
     }
 }
@@ -91,36 +308,415 @@ impl From<io::Error> for Error {
     }
 }
 
-fn run() -> Result<(), Error> {
-    let args = Args::parse();
-
-    let input_file = fs::File::open(&args.input_file);
-    let Ok(mut input_file) = input_file else {
-        return Err(Error::Usage(format!(
-            "невозможно открыть файл {}: {}",
-            args.input_file.display(),
-            input_file.unwrap_err()
-        )));
+// Список поддерживаемых форматов с кратким описанием, по одному на строку.
+fn format_list() -> String {
+    types::SupportedFileFormat::ALL
+        .iter()
+        .map(|format| format!("{:<10}{}\n", format.to_string(), format.description()))
+        .collect()
+}
+
+// Оставляет окно транзакций: пропускает первые `skip` и берёт не более `limit`.
+fn paginate(
+    transactions: Vec<types::Transaction>,
+    skip: usize,
+    limit: Option<usize>,
+) -> Vec<types::Transaction> {
+    transactions
+        .into_iter()
+        .skip(skip)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+// Удаляет дубликаты (сохраняя первое вхождение) и сортирует транзакции.
+// Сортировка стабильная, при равенстве ключа порядок определяется по id.
+fn reorder(
+    transactions: Vec<types::Transaction>,
+    sort_by: Option<SortField>,
+    reverse: bool,
+    dedup: bool,
+) -> Vec<types::Transaction> {
+    let mut result = if dedup {
+        let mut seen = HashSet::with_capacity(transactions.len());
+        transactions
+            .iter()
+            .filter(|tx| seen.insert(*tx))
+            .cloned()
+            .collect()
+    } else {
+        transactions
     };
 
-    let mut output_file = io::stdout();
+    if let Some(field) = sort_by {
+        result.sort_by(|a, b| {
+            let ord = field.key(a).cmp(&field.key(b)).then(a.id.cmp(&b.id));
+            if reverse { ord.reverse() } else { ord }
+        });
+    }
+    result
+}
+
+// Разбирает содержимое манифеста: пустые строки и строки, начинающиеся с `#`, пропускаются,
+// относительные пути дополняются каталогом `base`.
+fn parse_manifest(content: &str, base: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .collect()
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        Error::Usage(format!(
+            "невозможно прочитать манифест {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(parse_manifest(&content, base))
+}
+
+// Открывает исходный файл; путь `-` означает стандартный ввод. С фичей `flate2`
+// файлы с расширением `.gz` прозрачно распаковываются при чтении.
+fn open_input(path: &Path) -> io::Result<Box<dyn io::Read>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file = fs::File::open(path)?;
+    #[cfg(feature = "flate2")]
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        return Ok(Box::new(flate2::read::GzDecoder::new(file)));
+    }
+    Ok(Box::new(file))
+}
+
+// Поток для записи результата: stdout или файл из `--output-file`. С фичей `flate2`
+// файл с расширением `.gz` сжимается при записи; CRC32 в манифесте при этом считается
+// по несжатым данным.
+enum OutputTarget {
+    Stdout(io::Stdout),
+    File(fs::File),
+    #[cfg(feature = "flate2")]
+    Gzip(flate2::write::GzEncoder<fs::File>),
+}
+
+impl OutputTarget {
+    fn create(path: &Path) -> io::Result<Self> {
+        let file = fs::File::create(path)?;
+        #[cfg(feature = "flate2")]
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            return Ok(Self::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )));
+        }
+        Ok(Self::File(file))
+    }
+
+    // Дописывает хвост gzip-потока; ошибки записи, отложенные буферами, всплывают здесь.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Stdout(mut out) => out.flush(),
+            Self::File(mut file) => file.flush(),
+            #[cfg(feature = "flate2")]
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl io::Write for OutputTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(out) => out.write(buf),
+            Self::File(file) => file.write(buf),
+            #[cfg(feature = "flate2")]
+            Self::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(out) => out.flush(),
+            Self::File(file) => file.flush(),
+            #[cfg(feature = "flate2")]
+            Self::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+// Читает транзакции из всех файлов по порядку и объединяет их в один список.
+fn parse_inputs(
+    paths: &[PathBuf],
+    format: types::SupportedFileFormat,
+) -> Result<Vec<types::Transaction>, Error> {
+    let mut result = Vec::new();
+    for path in paths {
+        let mut input_file = open_input(path).map_err(|err| {
+            Error::IO(format!(
+                "невозможно открыть файл {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        let transactions = ypbank_parser::parse(&mut input_file, format).map_err(|err| {
+            match Error::from(err) {
+                Error::IO(msg) => {
+                    Error::IO(format!("ошибка чтения файла {}: {}", path.display(), msg))
+                }
+                err => Error::Parse(format!(
+                    "ошибка при разборе транзакций файла {}: {}",
+                    path.display(),
+                    err
+                )),
+            }
+        })?;
+        result.extend(transactions);
+    }
+    Ok(result)
+}
+
+// Параметры оформления вывода, не влияющие на сами данные.
+#[derive(Clone, Copy, Debug, Default)]
+struct OutputStyle {
+    wrap_fence: bool,
+    pretty: bool,
+    rule: bool,
+}
+
+// Записывает транзакции в выходной поток, при необходимости оборачивая их в блок кода.
+// Возвращает количество записанных транзакций.
+// Проверяет, что оформление из `style` применимо к формату, и возвращает язык блока
+// кода для `--wrap-fence`. Вызывается до создания выходного файла, чтобы ошибка
+// использования не оставляла после себя пустой файл.
+fn fence_language(
+    format: types::SupportedFileFormat,
+    style: OutputStyle,
+) -> Result<&'static str, Error> {
+    if style.pretty && format != types::SupportedFileFormat::Text {
+        return Err(Error::Usage(
+            "--pretty поддерживается только для формата text".to_string(),
+        ));
+    }
+    let language = match format {
+        types::SupportedFileFormat::Csv => "csv",
+        types::SupportedFileFormat::Text => "text",
+        types::SupportedFileFormat::Toml => "toml",
+        types::SupportedFileFormat::Xml => "xml",
+        types::SupportedFileFormat::JsonLines => "json",
+        types::SupportedFileFormat::TextLine => "text",
+        _ if style.wrap_fence => {
+            return Err(Error::Usage(
+                "--wrap-fence поддерживается только для форматов text, csv, toml, xml, jsonl и textline"
+                    .to_string(),
+            ));
+        }
+        _ => "",
+    };
+    Ok(language)
+}
+
+fn write_output(
+    writer: &mut impl io::Write,
+    format: types::SupportedFileFormat,
+    transactions: &[types::Transaction],
+    style: OutputStyle,
+) -> Result<usize, Error> {
+    let language = fence_language(format, style)?;
+    let wrap_fence = style.wrap_fence;
+
+    if wrap_fence {
+        writeln!(writer, "```{}", language)?;
+    }
+    let written = if style.pretty {
+        ypbank_parser::dump_as_text_pretty(writer, transactions, style.rule)?
+    } else {
+        ypbank_parser::dump(writer, format, transactions)?
+    };
+    if wrap_fence {
+        writeln!(writer, "```")?;
+    }
+    Ok(written)
+}
+
+// Пропускает данные во внутренний writer, попутно считая CRC32 всего записанного.
+struct HashingWriter<W: io::Write> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: io::Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    fn checksum(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
+    fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Сведения о результате конвертации для отслеживания происхождения данных.
+#[derive(Debug, PartialEq)]
+struct OutputManifest {
+    count: usize,
+    format: String,
+    crc32: u32,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+}
+
+impl OutputManifest {
+    fn new(
+        format: types::SupportedFileFormat,
+        transactions: &[types::Transaction],
+        crc32: u32,
+    ) -> Self {
+        Self {
+            count: transactions.len(),
+            format: format.to_string(),
+            crc32,
+            min_timestamp: transactions.iter().map(|tx| tx.timestamp).min(),
+            max_timestamp: transactions.iter().map(|tx| tx.timestamp).max(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let timestamp = |value: Option<u64>| value.map_or("null".to_string(), |v| v.to_string());
+        format!(
+            "{{\n  \"count\": {},\n  \"format\": \"{}\",\n  \"crc32\": \"{:08x}\",\n  \
+             \"min_timestamp\": {},\n  \"max_timestamp\": {}\n}}\n",
+            self.count,
+            self.format,
+            self.crc32,
+            timestamp(self.min_timestamp),
+            timestamp(self.max_timestamp)
+        )
+    }
+}
 
-    let input_format = args.input_format;
+// Режим проверки: сериализует транзакции в никуда (если задан формат вывода)
+// и возвращает итоговое сообщение.
+fn check(
+    output_format: Option<types::SupportedFileFormat>,
+    transactions: &[types::Transaction],
+    style: OutputStyle,
+) -> Result<String, Error> {
+    if let Some(format) = output_format {
+        write_output(&mut io::sink(), format, transactions, style)?;
+    }
+    Ok(format!("OK: {} transactions", transactions.len()))
+}
+
+// Разбирает аргументы командной строки. Ошибки clap завершают процесс с кодом
+// ошибки использования 3, а не со стандартным для clap кодом 2, занятым ошибками записи.
+fn parse_args() -> Args {
+    Args::try_parse().unwrap_or_else(|err| {
+        if err.use_stderr() {
+            let _ = err.print();
+            std::process::exit(3);
+        }
+        err.exit()
+    })
+}
+
+fn run() -> Result<(), Error> {
+    let args = parse_args();
+
+    if args.list_formats {
+        print!("{}", format_list());
+        return Ok(());
+    }
+
+    let Some(input_format) = args.input_format else {
+        return Err(Error::Usage("не задан --input-format".to_string()));
+    };
     let output_format = args.output_format;
 
-    let transactions = ypbank_parser::parse(&mut input_file, input_format.as_supported());
-    let Ok(transactions) = transactions else {
-        return Err(Error::Usage(format!(
-            "ошибка при разборе транзакций исходного файла: {:?}",
-            transactions.unwrap_err()
-        )));
+    let inputs = match &args.manifest {
+        Some(manifest) => read_manifest(manifest)?,
+        None => args.input_file.into_iter().collect(),
+    };
+
+    let transactions = parse_inputs(&inputs, input_format)?;
+
+    let filter = Filter {
+        tx_type: args.filter_type,
+        status: args.filter_status,
+        user: args.filter_user,
+        id: Range::new("id", args.id_min, args.id_max)?,
+        amount: Range::new("amount", args.amount_min, args.amount_max)?,
     };
+    let transactions = filter.apply(transactions);
 
-    ypbank_parser::dump(
-        &mut output_file,
-        output_format.as_supported(),
-        &transactions,
-    )?;
+    let transactions = reorder(transactions, args.sort_by, args.reverse, args.dedup);
+    let transactions = paginate(transactions, args.skip, args.limit);
+
+    let style = OutputStyle {
+        wrap_fence: args.wrap_fence,
+        pretty: args.pretty,
+        rule: args.rule,
+    };
+    if args.check {
+        eprintln!("{}", check(output_format, &transactions, style)?);
+        return Ok(());
+    }
+
+    let Some(output_format) = output_format else {
+        return Err(Error::Usage("не задан --output-format".to_string()));
+    };
+    fence_language(output_format, style)?;
+    let output = match &args.output_file {
+        Some(path) => OutputTarget::create(path).map_err(|err| {
+            Error::IO(format!(
+                "невозможно создать файл {}: {}",
+                path.display(),
+                err
+            ))
+        })?,
+        None => OutputTarget::Stdout(io::stdout()),
+    };
+    let mut output_file = HashingWriter::new(output);
+    let written = write_output(&mut output_file, output_format, &transactions, style)?;
+    let checksum = output_file.checksum();
+    output_file
+        .into_inner()
+        .finish()
+        .map_err(|err| Error::IO(format!("ошибка записи результата: {}", err)))?;
+    eprintln!("Записано транзакций: {}", written);
+
+    if let Some(path) = &args.manifest_out {
+        let manifest = OutputManifest::new(output_format, &transactions, checksum);
+        fs::write(path, manifest.to_json()).map_err(|err| {
+            Error::Usage(format!(
+                "невозможно записать манифест {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+    }
 
     Ok(())
 }
@@ -134,3 +730,386 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNSORTED: &str = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+        1003,WITHDRAWAL,502,0,1000,1672538400000,PENDING,"ATM withdrawal"
+        1002,TRANSFER,501,502,15000,1672531200000,FAILURE,"Payment"
+        1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+        1003,WITHDRAWAL,502,0,1000,1672538400000,PENDING,"ATM withdrawal"
+        "##;
+
+    fn convert(sort_by: Option<SortField>, reverse: bool, dedup: bool) -> Vec<u64> {
+        let txs = ypbank_parser::parse(&mut UNSORTED.as_bytes(), types::SupportedFileFormat::Csv)
+            .unwrap();
+        let txs = reorder(txs, sort_by, reverse, dedup);
+
+        let mut buffer = Vec::new();
+        ypbank_parser::dump(&mut buffer, types::SupportedFileFormat::Csv, &txs).unwrap();
+        let emitted =
+            ypbank_parser::parse(&mut buffer.as_slice(), types::SupportedFileFormat::Csv).unwrap();
+        emitted.iter().map(|tx| tx.id).collect()
+    }
+
+    #[test]
+    fn test_sort_by_timestamp_ties_by_id() {
+        assert_eq!(
+            convert(Some(SortField::Timestamp), false, false),
+            vec![1001, 1002, 1003, 1003]
+        );
+    }
+
+    #[test]
+    fn test_sort_reverse() {
+        assert_eq!(
+            convert(Some(SortField::Amount), true, false),
+            vec![1001, 1002, 1003, 1003]
+        );
+        assert_eq!(
+            convert(Some(SortField::Timestamp), true, true),
+            vec![1003, 1002, 1001]
+        );
+    }
+
+    #[test]
+    fn test_dedup_keeps_input_order() {
+        assert_eq!(convert(None, false, true), vec![1003, 1002, 1001]);
+    }
+
+    #[test]
+    fn test_manifest_concatenates_inputs() {
+        let manifest = "# исходные выгрузки\ntransactions.csv\n\n  another_transactions.csv  \n";
+
+        let paths = parse_manifest(manifest, Path::new("example_data"));
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("example_data/transactions.csv"),
+                PathBuf::from("example_data/another_transactions.csv"),
+            ]
+        );
+
+        let txs = parse_inputs(&paths, types::SupportedFileFormat::Csv).unwrap();
+        let ids: Vec<u64> = txs.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, vec![1001, 1002, 1003, 1001, 1006, 1003]);
+    }
+
+    #[test]
+    fn test_manifest_reports_failed_file() {
+        let paths = parse_manifest("transactions.csv\nmissing.csv\n", Path::new("example_data"));
+
+        let err = parse_inputs(&paths, types::SupportedFileFormat::Csv).unwrap_err();
+
+        assert!(err.to_string().contains("missing.csv"));
+    }
+
+    const FENCED: OutputStyle = OutputStyle {
+        wrap_fence: true,
+        pretty: false,
+        rule: false,
+    };
+
+    #[test]
+    fn test_wrap_fence() {
+        let txs = ypbank_parser::parse(&mut UNSORTED.as_bytes(), types::SupportedFileFormat::Csv)
+            .unwrap();
+
+        let mut plain = Vec::new();
+        write_output(
+            &mut plain,
+            types::SupportedFileFormat::Csv,
+            &txs,
+            OutputStyle::default(),
+        )
+        .unwrap();
+        let plain = String::from_utf8(plain).unwrap();
+        assert!(!plain.contains("```"));
+
+        let mut fenced = Vec::new();
+        write_output(&mut fenced, types::SupportedFileFormat::Csv, &txs, FENCED).unwrap();
+        let fenced = String::from_utf8(fenced).unwrap();
+        assert_eq!(fenced, format!("```csv\n{}```\n", plain));
+
+        let mut bin = Vec::new();
+        assert_eq!(
+            write_output(
+                &mut bin,
+                types::SupportedFileFormat::Bin,
+                &txs,
+                OutputStyle::default()
+            )
+            .unwrap(),
+            txs.len()
+        );
+        bin.clear();
+        assert!(write_output(&mut bin, types::SupportedFileFormat::Bin, &txs, FENCED).is_err());
+        assert!(bin.is_empty());
+    }
+
+    #[test]
+    fn test_pretty() {
+        let txs = ypbank_parser::parse(&mut UNSORTED.as_bytes(), types::SupportedFileFormat::Csv)
+            .unwrap();
+        let style = OutputStyle {
+            pretty: true,
+            rule: true,
+            ..OutputStyle::default()
+        };
+
+        let mut buffer = Vec::new();
+        write_output(&mut buffer, types::SupportedFileFormat::Text, &txs, style).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.matches("---\n").count(), txs.len() - 1);
+        assert!(output.contains("TX_ID       : 1003\n"));
+
+        let mut csv = Vec::new();
+        assert!(matches!(
+            write_output(&mut csv, types::SupportedFileFormat::Csv, &txs, style),
+            Err(Error::Usage(_))
+        ));
+    }
+
+    const MIXED: &str = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+        2001,WITHDRAWAL,501,0,1000,1672538400000,FAILURE,"ATM withdrawal"
+        2002,WITHDRAWAL,501,0,2000,1672538500000,SUCCESS,"ATM withdrawal"
+        2003,TRANSFER,502,501,15000,1672538600000,FAILURE,"Payment"
+        2004,WITHDRAWAL,502,0,3000,1672538700000,FAILURE,"ATM withdrawal"
+        2005,DEPOSIT,0,501,50000,1672538800000,SUCCESS,"Salary"
+        2006,WITHDRAWAL,501,0,500,1672538900000,FAILURE,"ATM withdrawal"
+        "##;
+
+    fn filter_ids(filter: Filter, output: types::SupportedFileFormat) -> Vec<u64> {
+        let txs =
+            ypbank_parser::parse(&mut MIXED.as_bytes(), types::SupportedFileFormat::Csv).unwrap();
+        let txs = filter.apply(txs);
+
+        let mut buffer = Vec::new();
+        write_output(&mut buffer, output, &txs, OutputStyle::default()).unwrap();
+        let emitted = ypbank_parser::parse(&mut buffer.as_slice(), output).unwrap();
+        emitted.iter().map(|tx| tx.id).collect()
+    }
+
+    #[test]
+    fn test_filters_combine_with_and() {
+        let filter = Filter {
+            tx_type: Some(FilterType::Withdrawal),
+            status: Some(FilterStatus::Failure),
+            user: Some(501),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(filter, types::SupportedFileFormat::Text),
+            vec![2001, 2006]
+        );
+    }
+
+    #[test]
+    fn test_range_filters() {
+        let range = |name, min, max| Range::new(name, min, max).unwrap();
+
+        let one_sided = Filter {
+            id: range("id", Some(2004), None),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(one_sided, types::SupportedFileFormat::Csv),
+            vec![2004, 2005, 2006]
+        );
+
+        let upper_only = Filter {
+            amount: range("amount", None, Some(1000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(upper_only, types::SupportedFileFormat::Csv),
+            vec![2001, 2006]
+        );
+
+        let combined = Filter {
+            tx_type: Some(FilterType::Withdrawal),
+            id: range("id", Some(2002), Some(2006)),
+            amount: range("amount", Some(2000), Some(3000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(combined, types::SupportedFileFormat::Bin),
+            vec![2002, 2004]
+        );
+
+        let single = Filter {
+            id: range("id", Some(2003), Some(2003)),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(single, types::SupportedFileFormat::Text),
+            vec![2003]
+        );
+    }
+
+    #[test]
+    fn test_invalid_range() {
+        let err = Range::new("amount", Some(10), Some(5)).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "--amount-min (10) не может быть больше --amount-max (5)"
+        );
+        assert_eq!(err.code(), 3);
+    }
+
+    #[test]
+    fn test_filter_user_matches_either_side() {
+        let filter = Filter {
+            user: Some(501),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_ids(filter, types::SupportedFileFormat::Bin),
+            vec![2001, 2002, 2003, 2005, 2006]
+        );
+
+        assert_eq!(
+            filter_ids(Filter::default(), types::SupportedFileFormat::Csv).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_output_manifest_matches_output() {
+        let txs = ypbank_parser::parse(&mut UNSORTED.as_bytes(), types::SupportedFileFormat::Csv)
+            .unwrap();
+        let txs = reorder(txs, None, false, true);
+
+        let mut writer = HashingWriter::new(Vec::new());
+        write_output(
+            &mut writer,
+            types::SupportedFileFormat::Bin,
+            &txs,
+            OutputStyle::default(),
+        )
+        .unwrap();
+        let manifest =
+            OutputManifest::new(types::SupportedFileFormat::Bin, &txs, writer.checksum());
+
+        let emitted = ypbank_parser::parse(
+            &mut writer.inner.as_slice(),
+            types::SupportedFileFormat::Bin,
+        )
+        .unwrap();
+        assert_eq!(manifest.count, emitted.len());
+        assert_eq!(manifest.count, 3);
+        assert_eq!(manifest.crc32, crc32fast::hash(&writer.inner));
+        assert_eq!(manifest.min_timestamp, Some(1672531200000));
+        assert_eq!(manifest.max_timestamp, Some(1672538400000));
+
+        let json = manifest.to_json();
+        assert!(json.contains("\"count\": 3,"));
+        assert!(json.contains("\"format\": \"bin\","));
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn test_gzip_input() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(UNSORTED.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "ypbank_converter_{}_input.csv.gz",
+            std::process::id()
+        ));
+        fs::write(&path, compressed).unwrap();
+        let got = parse_inputs(std::slice::from_ref(&path), types::SupportedFileFormat::Csv);
+        fs::remove_file(&path).unwrap();
+
+        let expected =
+            ypbank_parser::parse(&mut UNSORTED.as_bytes(), types::SupportedFileFormat::Csv)
+                .unwrap();
+        assert_eq!(got.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_check_valid_file() {
+        let paths = vec![PathBuf::from("example_data/transactions.csv")];
+
+        let txs = parse_inputs(&paths, types::SupportedFileFormat::Csv).unwrap();
+
+        assert_eq!(
+            check(None, &txs, OutputStyle::default()).unwrap(),
+            "OK: 3 transactions"
+        );
+        assert_eq!(
+            check(Some(types::SupportedFileFormat::Text), &txs, FENCED).unwrap(),
+            "OK: 3 transactions"
+        );
+        assert!(check(Some(types::SupportedFileFormat::Bin), &txs, FENCED).is_err());
+    }
+
+    #[test]
+    fn test_check_corrupted_file() {
+        let path = std::env::temp_dir().join(format!(
+            "ypbank_converter_{}_corrupted.csv",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+             1001,DEPOSIT,0,501,lots,1672531200000,SUCCESS,\"broken\"\n",
+        )
+        .unwrap();
+
+        let got = parse_inputs(std::slice::from_ref(&path), types::SupportedFileFormat::Csv);
+        fs::remove_file(&path).unwrap();
+
+        let err = got.unwrap_err();
+        assert_eq!(err.code(), 1);
+        assert!(err.to_string().contains("corrupted.csv"));
+    }
+
+    #[test]
+    fn test_duplicate_id_message() {
+        let err = Error::from(error::ParseError::DuplicateId { id: 1001 });
+
+        assert_eq!(err.to_string(), "duplicate transaction id: 1001");
+        assert_eq!(err.code(), 1);
+    }
+
+    #[test]
+    fn test_skip_and_limit() {
+        let input = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1,DEPOSIT,0,501,100,1672531200000,SUCCESS,"first"
+            2,DEPOSIT,0,501,200,1672531200001,SUCCESS,"second"
+            3,DEPOSIT,0,501,300,1672531200002,SUCCESS,"third"
+            4,DEPOSIT,0,501,400,1672531200003,SUCCESS,"fourth"
+            5,DEPOSIT,0,501,500,1672531200004,SUCCESS,"fifth"
+            "##;
+        let txs =
+            ypbank_parser::parse(&mut input.as_bytes(), types::SupportedFileFormat::Csv).unwrap();
+        let ids = |txs: Vec<types::Transaction>| txs.iter().map(|tx| tx.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(paginate(txs.clone(), 1, Some(2))), vec![2, 3]);
+        assert_eq!(ids(paginate(txs.clone(), 3, None)), vec![4, 5]);
+        assert_eq!(ids(paginate(txs.clone(), 0, Some(0))), Vec::<u64>::new());
+        assert!(paginate(txs, 10, Some(2)).is_empty());
+    }
+
+    #[test]
+    fn test_list_formats() {
+        let args = Args::try_parse_from(["ypbank_converter", "--list-formats"]).unwrap();
+        assert!(args.list_formats);
+        assert!(
+            Args::try_parse_from(["ypbank_converter", "--list-formats", "--skip", "1"]).is_err()
+        );
+
+        let list = format_list();
+        for name in ["csv", "bin", "text"] {
+            assert!(list.lines().any(|line| line.starts_with(name)));
+        }
+        assert_eq!(list.lines().count(), types::SupportedFileFormat::ALL.len());
+    }
+}
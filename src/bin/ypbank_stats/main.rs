@@ -0,0 +1,235 @@
+use clap::Parser;
+use core::fmt;
+use std::collections::HashMap;
+use std::{fs, io, path::PathBuf};
+use ypbank_parser::{
+    analytics, error,
+    types::{self, Transaction, TxStatus, TxType},
+};
+
+#[derive(Debug)]
+enum Error {
+    Parse(String),
+    Usage(String),
+    IO(String),
+}
+
+impl Error {
+    fn code(&self) -> i32 {
+        match self {
+            Self::Parse(_) => 1,
+            Self::Usage(_) => 3,
+            Self::IO(_) => 4,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(msg) | Self::Usage(msg) => write!(f, "{}", msg),
+            Self::IO(msg) => write!(f, "IO error: {}", msg),
+        }
+    }
+}
+
+impl From<error::ParseError> for Error {
+    fn from(value: error::ParseError) -> Self {
+        match value {
+            error::ParseError::IOError(str) => Error::IO(str),
+            error::ParseError::InvalidFormat(err) => Error::Parse(err.to_string()),
+            error::ParseError::ChecksumMismatch { expected, actual } => Error::Parse(format!(
+                "checksum mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            )),
+            error::ParseError::TruncatedRecord { recovered } => Error::Parse(format!(
+                "truncated record after {} complete records",
+                recovered
+            )),
+            error::ParseError::RecordTooLarge { size } => {
+                Error::Parse(format!("record too large: {} bytes", size))
+            }
+            error::ParseError::DuplicateId { id } => {
+                Error::Parse(format!("duplicate transaction id: {}", id))
+            }
+            error::ParseError::ImplausibleTimestamp { value } => {
+                Error::Parse(format!("implausible timestamp: {}", value))
+            }
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IO(format!("ошибка ввода-вывода: {}", err))
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Args {
+    /// Путь до файла с транзакциями
+    #[arg(long, required = true)]
+    input_file: PathBuf,
+
+    /// Формат файла: text/csv/bin/toml/xml/jsonl/textline
+    #[cfg_attr(feature = "prost", doc = "или protobuf")]
+    #[arg(long, required = true)]
+    input_format: types::SupportedFileFormat,
+
+    /// Только проверить структуру файла (заголовки и размеры записей), не разбирая
+    /// транзакции. Поддерживается только для формата bin
+    #[arg(long)]
+    verify: bool,
+}
+
+// Сводная статистика по набору транзакций.
+// Суммы считаются в u128, чтобы не переполниться на больших наборах.
+#[derive(Debug, PartialEq)]
+struct Stats {
+    total: usize,
+    amount_by_type: HashMap<TxType, u128>,
+    status_rates: analytics::StatusRates,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+}
+
+impl Stats {
+    fn collect(transactions: &[Transaction]) -> Self {
+        Stats {
+            total: transactions.len(),
+            amount_by_type: analytics::amount_by_type(transactions),
+            status_rates: analytics::status_rates(transactions),
+            min_timestamp: transactions.iter().map(|tx| tx.timestamp).min(),
+            max_timestamp: transactions.iter().map(|tx| tx.timestamp).max(),
+        }
+    }
+
+    fn count_by_status(&self, status: TxStatus) -> usize {
+        match status {
+            TxStatus::Success => self.status_rates.success,
+            TxStatus::Failure => self.status_rates.failure,
+            TxStatus::Pending => self.status_rates.pending,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timestamp = |value: Option<u64>| value.map_or("-".to_string(), |v| v.to_string());
+
+        writeln!(f, "Всего транзакций: {}", self.total)?;
+        writeln!(f, "Сумма по типам:")?;
+        for tx_type in TxType::all() {
+            let amount = self.amount_by_type.get(tx_type).copied().unwrap_or(0);
+            writeln!(f, "  {}: {}", tx_type, amount)?;
+        }
+        writeln!(f, "Количество по статусам:")?;
+        for status in TxStatus::all() {
+            writeln!(f, "  {}: {}", status, self.count_by_status(*status))?;
+        }
+        writeln!(
+            f,
+            "Минимальная временная метка: {}",
+            timestamp(self.min_timestamp)
+        )?;
+        writeln!(
+            f,
+            "Максимальная временная метка: {}",
+            timestamp(self.max_timestamp)
+        )
+    }
+}
+
+// Разбирает аргументы командной строки. Ошибки clap завершают процесс с кодом
+// ошибки использования 3, как в остальных утилитах, а не со стандартным для clap кодом 2.
+fn parse_args() -> Args {
+    Args::try_parse().unwrap_or_else(|err| {
+        if err.use_stderr() {
+            let _ = err.print();
+            std::process::exit(3);
+        }
+        err.exit()
+    })
+}
+
+fn run() -> Result<(), Error> {
+    let args = parse_args();
+
+    let mut input_file = fs::File::open(&args.input_file).map_err(|err| {
+        Error::IO(format!(
+            "невозможно открыть файл {}: {}",
+            args.input_file.display(),
+            err
+        ))
+    })?;
+
+    if args.verify {
+        if args.input_format != types::SupportedFileFormat::Bin {
+            return Err(Error::Usage(
+                "--verify поддерживается только для формата bin".to_string(),
+            ));
+        }
+        let count = ypbank_parser::verify_bin(&mut io::BufReader::new(input_file))?;
+        println!("OK: {} records", count);
+        return Ok(());
+    }
+
+    let transactions = ypbank_parser::parse(&mut input_file, args.input_format)?;
+
+    print!("{}", Stats::collect(&transactions));
+    Ok(())
+}
+
+fn main() {
+    match run() {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.code());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_for_fixture() {
+        let mut file = fs::File::open("example_data/transactions.csv").unwrap();
+        let txs = ypbank_parser::parse(&mut file, types::SupportedFileFormat::Csv).unwrap();
+
+        let printed = Stats::collect(&txs).to_string();
+
+        assert_eq!(
+            printed,
+            "Всего транзакций: 3\n\
+             Сумма по типам:\n  \
+             DEPOSIT: 50000\n  \
+             TRANSFER: 15000\n  \
+             WITHDRAWAL: 1000\n  \
+             FEE: 0\n  \
+             REVERSAL: 0\n\
+             Количество по статусам:\n  \
+             SUCCESS: 1\n  \
+             FAILURE: 1\n  \
+             PENDING: 1\n\
+             Минимальная временная метка: 1672531200000\n\
+             Максимальная временная метка: 1672538400000\n"
+        );
+    }
+
+    #[test]
+    fn test_amount_sum_does_not_overflow() {
+        let tx = types::TransactionBuilder::new()
+            .tx_type(TxType::Fee)
+            .amount(u64::MAX)
+            .build();
+
+        let stats = Stats::collect(&[tx.clone(), tx]);
+
+        assert_eq!(stats.amount_by_type[&TxType::Fee], 2 * u128::from(u64::MAX));
+        assert_eq!(stats.status_rates.pending, 2);
+    }
+}
@@ -6,58 +6,223 @@ use std::{
 
 use crate::types::{Transaction, TxStatus, TxType};
 
-const MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x4E];
+/// Сигнатура в начале заголовка каждой записи (`'YPBN'`).
+pub const MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x4E];
 
-fn read_magic(reader: &mut impl io::Read) -> io::Result<[u8; 4]> {
+/// Поле записи бинарного формата: имя, смещение и размер в байтах.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinField {
+    /// Имя поля, как в описании формата (например, `TX_ID`).
+    pub name: &'static str,
+    /// Смещение поля в байтах от начала тела записи (сразу после заголовка).
+    pub offset: usize,
+    /// Размер поля в байтах. Многобайтовые числа записаны в big-endian
+    /// (или в little-endian, см. [`BinOptions::little_endian`]).
+    pub size: usize,
+}
+
+/// Машиночитаемое описание раскладки записи бинарного формата
+/// (см. [doc/YPBankBinFormat_ru.md](doc/YPBankBinFormat_ru.md)).
+///
+/// Запись состоит из заголовка ([`BinLayout::MAGIC`] и [`BinLayout::RECORD_SIZE`],
+/// всего [`BinLayout::HEADER_SIZE`] байт) и тела. Поля тела фиксированного размера
+/// перечислены в [`BinLayout::FIELDS`]; за ними следует описание длиной `DESC_LEN` байт,
+/// начинающееся со смещения [`MIN_RECORD_SIZE`]. Смещения полей тела отсчитываются
+/// от начала тела.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{BinLayout, MIN_RECORD_SIZE};
+///
+/// let amount = BinLayout::AMOUNT;
+/// assert_eq!((amount.offset, amount.size), (25, 8));
+/// assert_eq!(BinLayout::DESCRIPTION_OFFSET, MIN_RECORD_SIZE as usize);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BinLayout;
+
+impl BinLayout {
+    /// Сигнатура записи; смещение отсчитывается от начала заголовка.
+    pub const MAGIC: BinField = BinField {
+        name: "MAGIC",
+        offset: 0,
+        size: 4,
+    };
+    /// Размер тела записи в байтах; смещение отсчитывается от начала заголовка.
+    pub const RECORD_SIZE: BinField = BinField {
+        name: "RECORD_SIZE",
+        offset: 4,
+        size: 4,
+    };
+    /// Размер заголовка записи в байтах.
+    pub const HEADER_SIZE: usize = 8;
+
+    /// Идентификатор транзакции.
+    pub const TX_ID: BinField = BinField {
+        name: "TX_ID",
+        offset: 0,
+        size: 8,
+    };
+    /// Тип транзакции (числовой код, см. `From<TxType> for u8`).
+    pub const TX_TYPE: BinField = BinField {
+        name: "TX_TYPE",
+        offset: 8,
+        size: 1,
+    };
+    /// Отправитель.
+    pub const FROM_USER_ID: BinField = BinField {
+        name: "FROM_USER_ID",
+        offset: 9,
+        size: 8,
+    };
+    /// Получатель.
+    pub const TO_USER_ID: BinField = BinField {
+        name: "TO_USER_ID",
+        offset: 17,
+        size: 8,
+    };
+    /// Сумма.
+    pub const AMOUNT: BinField = BinField {
+        name: "AMOUNT",
+        offset: 25,
+        size: 8,
+    };
+    /// Время транзакции.
+    pub const TIMESTAMP: BinField = BinField {
+        name: "TIMESTAMP",
+        offset: 33,
+        size: 8,
+    };
+    /// Статус транзакции (числовой код, см. `From<TxStatus> for u8`).
+    pub const STATUS: BinField = BinField {
+        name: "STATUS",
+        offset: 41,
+        size: 1,
+    };
+    /// Длина описания в байтах.
+    pub const DESC_LEN: BinField = BinField {
+        name: "DESC_LEN",
+        offset: 42,
+        size: 4,
+    };
+    /// Смещение начала описания в теле записи.
+    pub const DESCRIPTION_OFFSET: usize = 46;
+
+    /// Поля тела фиксированного размера в порядке следования.
+    pub const FIELDS: [BinField; 8] = [
+        Self::TX_ID,
+        Self::TX_TYPE,
+        Self::FROM_USER_ID,
+        Self::TO_USER_ID,
+        Self::AMOUNT,
+        Self::TIMESTAMP,
+        Self::STATUS,
+        Self::DESC_LEN,
+    ];
+}
+
+/// Читает сигнатуру записи. Возвращает `None`, если поток закончился ровно
+/// на границе записи (не прочитано ни одного байта).
+fn read_magic(reader: &mut impl io::Read) -> io::Result<Option<[u8; 4]>> {
     let mut buf = [0u8; 4];
-    reader.read_exact(&mut buf)?;
-    Ok(buf)
+    loop {
+        match reader.read(&mut buf[..1]) {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    reader.read_exact(&mut buf[1..])?;
+    Ok(Some(buf))
 }
 
-fn read_u32(reader: &mut impl io::Read) -> io::Result<u32> {
+// Все чтения, кроме первого байта сигнатуры, идут через `read_exact`, который сам
+// повторяет вызов при `io::ErrorKind::Interrupted`.
+fn read_u32(reader: &mut impl io::Read, little_endian: bool) -> io::Result<u32> {
     let mut buf = [0u8; 4];
     reader.read_exact(&mut buf)?;
-    Ok(u32::from_be_bytes(buf))
+    Ok(if little_endian {
+        u32::from_le_bytes(buf)
+    } else {
+        u32::from_be_bytes(buf)
+    })
 }
 
-fn read_u64(reader: &mut impl io::Read) -> io::Result<u64> {
+fn read_u64(reader: &mut impl io::Read, little_endian: bool) -> io::Result<u64> {
     let mut buf = [0u8; 8];
     reader.read_exact(&mut buf)?;
-    Ok(u64::from_be_bytes(buf))
+    Ok(if little_endian {
+        u64::from_le_bytes(buf)
+    } else {
+        u64::from_be_bytes(buf)
+    })
+}
+
+fn u32_bytes(value: u32, little_endian: bool) -> [u8; 4] {
+    if little_endian {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    }
 }
 
-fn read_string(size: usize, reader: &mut impl io::Read) -> io::Result<String> {
+fn u64_bytes(value: u64, little_endian: bool) -> [u8; 8] {
+    if little_endian {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    }
+}
+
+/// Читает строку длиной `size` байт (не символов).
+///
+/// `remaining` - сколько байт осталось в записи; длина сверяется с ним до выделения
+/// памяти, чтобы испорченный `DESC_LEN` не приводил к огромной аллокации.
+fn read_string(
+    size: usize,
+    remaining: usize,
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> Result<String, error::ParseError> {
+    if size > remaining {
+        return Err(error::ParseError::InvalidFormat(format!(
+            "description length {} exceeds remaining record size {}",
+            size, remaining
+        )));
+    }
     let mut buf = vec![0u8; size];
     reader.read_exact(&mut buf)?;
-    let Ok(s) = String::from_utf8(buf) else {
-        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-8"));
-    };
-    Ok(s)
+    match options.encoding {
+        DescriptionEncoding::Utf8 if options.lossy_utf8 => {
+            Ok(String::from_utf8_lossy(&buf).into_owned())
+        }
+        DescriptionEncoding::Utf8 => {
+            let Ok(s) = String::from_utf8(buf) else {
+                return Err(error::ParseError::InvalidFormat(
+                    "invalid UTF-8 in description".to_string(),
+                ));
+            };
+            Ok(s)
+        }
+        #[cfg(feature = "encoding")]
+        DescriptionEncoding::Latin1 => Ok(buf.into_iter().map(char::from).collect()),
+    }
 }
 
 fn read_tx_type(reader: &mut impl io::Read) -> io::Result<TxType> {
     let mut buf = [0u8; 1];
     reader.read_exact(&mut buf)?;
-    match buf[0] {
-        0 => Ok(TxType::Deposit),
-        1 => Ok(TxType::Transfer),
-        2 => Ok(TxType::Withdrawal),
-        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid TxType")),
-    }
+    TxType::try_from(buf[0])
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid TxType"))
 }
 
 fn read_tx_status(reader: &mut impl io::Read) -> io::Result<TxStatus> {
     let mut buf = [0u8; 1];
     reader.read_exact(&mut buf)?;
-    match buf[0] {
-        0 => Ok(TxStatus::Success),
-        1 => Ok(TxStatus::Failure),
-        2 => Ok(TxStatus::Pending),
-        _ => Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            "unexpected TxType",
-        )),
-    }
+    TxStatus::try_from(buf[0])
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid TxStatus"))
 }
 
 struct Header {
@@ -66,16 +231,18 @@ struct Header {
 }
 
 impl Header {
-    fn read(reader: &mut impl io::Read) -> io::Result<Self> {
-        let magic = read_magic(reader)?;
+    fn read(reader: &mut impl io::Read, little_endian: bool) -> io::Result<Option<Self>> {
+        let Some(magic) = read_magic(reader)? else {
+            return Ok(None);
+        };
         if magic != MAGIC {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid magic"));
         }
-        let record_size = read_u32(reader)?;
-        Ok(Header {
+        let record_size = read_u32(reader, little_endian)?;
+        Ok(Some(Header {
             _magic: magic,
             record_size,
-        })
+        }))
     }
 
     fn new(size: u32) -> Self {
@@ -85,38 +252,72 @@ impl Header {
         }
     }
 
-    fn dump(&self) -> Vec<u8> {
+    fn dump(&self, little_endian: bool) -> Vec<u8> {
         let mut res = Vec::<u8>::with_capacity(Header::sizeof());
         res.extend_from_slice(&self._magic);
-        res.extend_from_slice(&self.record_size.to_be_bytes());
+        res.extend_from_slice(&u32_bytes(self.record_size, little_endian));
         res
     }
 
     const fn sizeof() -> usize {
-        4 + mem::size_of::<u32>()
+        BinLayout::HEADER_SIZE
+    }
+}
+
+// Дописывает к сообщению об ошибке абсолютную позицию поля в потоке.
+fn at_byte(err: error::ParseError, position: u64) -> error::ParseError {
+    match err {
+        error::ParseError::InvalidFormat(msg) => {
+            error::ParseError::InvalidFormat(format!("{} at byte {}", msg, position))
+        }
+        error::ParseError::IOError(msg) => {
+            error::ParseError::InvalidFormat(format!("{} at byte {}", msg, position))
+        }
+        other => other,
     }
 }
 
+// Читает одно поле тела записи; при ошибке указывает позицию начала поля.
+fn read_field<'a, T>(
+    reader: &mut Cursor<&'a [u8]>,
+    body_offset: u64,
+    read: impl FnOnce(&mut Cursor<&'a [u8]>) -> io::Result<T>,
+) -> Result<T, error::ParseError> {
+    let position = body_offset + reader.position();
+    read(reader).map_err(|err| at_byte(err.into(), position))
+}
+
+/// Разбирает тело записи. `body_offset` — абсолютная позиция начала тела в потоке,
+/// она используется в сообщениях об ошибках.
 fn read_tx(
-    reader: &mut impl io::Read,
-    full_record_size: u32,
+    body: &[u8],
+    body_offset: u64,
+    options: &BinOptions,
 ) -> Result<Transaction, error::ParseError> {
-    let id = read_u64(reader)?;
-    let r#type = read_tx_type(reader)?;
-    let from_user = read_u64(reader)?;
-    let to_user = read_u64(reader)?;
-    let amount = read_u64(reader)?;
-    let timestamp = read_u64(reader)?;
-    let status = read_tx_status(reader)?;
-    let desc_len = read_u32(reader)?;
-
-    if full_record_size != MIN_RECORD_SIZE + desc_len {
-        return Err(error::ParseError::InvalidFormat(
-            "mailformed record. record size mismatch".to_string(),
+    let full_record_size = body.len() as u32;
+    let le = options.little_endian;
+    let mut reader = Cursor::new(body);
+    let id = read_field(&mut reader, body_offset, |r| read_u64(r, le))?;
+    let r#type = read_field(&mut reader, body_offset, read_tx_type)?;
+    let from_user = read_field(&mut reader, body_offset, |r| read_u64(r, le))?;
+    let to_user = read_field(&mut reader, body_offset, |r| read_u64(r, le))?;
+    let amount = read_field(&mut reader, body_offset, |r| read_u64(r, le))?;
+    let timestamp = read_field(&mut reader, body_offset, |r| read_u64(r, le))?;
+    let status = read_field(&mut reader, body_offset, read_tx_status)?;
+    let desc_len = read_field(&mut reader, body_offset, |r| read_u32(r, le))?;
+
+    // сравниваем в u64, чтобы огромный DESC_LEN не переполнил u32
+    if u64::from(full_record_size) != u64::from(MIN_RECORD_SIZE) + u64::from(desc_len) {
+        return Err(at_byte(
+            error::ParseError::InvalidFormat("mailformed record. record size mismatch".to_string()),
+            body_offset + u64::from(MIN_RECORD_SIZE) - mem::size_of::<u32>() as u64,
         ));
     }
 
-    let description = read_string(desc_len as usize, reader)?;
+    let remaining = (full_record_size - MIN_RECORD_SIZE) as usize;
+    let description_offset = body_offset + reader.position();
+    let description = read_string(desc_len as usize, remaining, &mut reader, options)
+        .map_err(|err| at_byte(err, description_offset))?;
 
     Ok(Transaction {
         id,
@@ -130,8 +331,73 @@ fn read_tx(
     })
 }
 
-/// минимально возможный размер записи без описания
-const MIN_RECORD_SIZE: u32 = 46;
+/// Минимально возможный размер тела записи (без описания).
+pub const MIN_RECORD_SIZE: u32 = BinLayout::DESCRIPTION_OFFSET as u32;
+
+/// максимальная длина описания, при которой RECORD_SIZE помещается в u32
+const MAX_DESCRIPTION_LEN: usize = (u32::MAX - MIN_RECORD_SIZE) as usize;
+
+/// Максимальный размер записи, принимаемый парсером по умолчанию (16 МиБ).
+pub const DEFAULT_MAX_RECORD_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Параметры чтения и записи бинарного формата.
+///
+/// Значение по умолчанию соответствует исходному формату без контрольных сумм,
+/// поэтому файлы, записанные через [`dump_as_bin_with`] с настройками по умолчанию,
+/// побайтово совпадают с результатом обычного дампа.
+#[derive(Debug, Clone)]
+pub struct BinOptions {
+    /// Дописывать после тела каждой записи 4 байта CRC32 при записи
+    /// и проверять их при чтении.
+    ///
+    /// Контрольная сумма считается по телу записи (`RECORD_SIZE` байт после заголовка)
+    /// и не учитывается в `RECORD_SIZE`. Файлы с контрольными суммами и без них
+    /// несовместимы: читать их нужно с тем же значением флага, с которым они были записаны.
+    pub checksum: bool,
+    /// Кодировка, в которой читается поле `DESCRIPTION`.
+    pub encoding: DescriptionEncoding,
+    /// Заменять невалидные последовательности UTF-8 в `DESCRIPTION` символом
+    /// `U+FFFD` вместо возврата ошибки. Действует только для [`DescriptionEncoding::Utf8`].
+    pub lossy_utf8: bool,
+    /// Максимально допустимый `RECORD_SIZE` при чтении.
+    ///
+    /// Проверяется сразу после чтения заголовка, до выделения памяти под тело записи,
+    /// чтобы испорченный или враждебный файл не мог вызвать огромную аллокацию.
+    /// По умолчанию [`DEFAULT_MAX_RECORD_SIZE`].
+    pub max_record_size: u32,
+    /// Записывать и читать многобайтовые поля (`RECORD_SIZE`, числовые поля тела,
+    /// `DESC_LEN` и CRC32) в порядке little-endian вместо big-endian.
+    ///
+    /// Сигнатура `MAGIC` от порядка байтов не зависит. Файлы в разных порядках байтов
+    /// несовместимы: читать их нужно с тем же значением флага, с которым они были записаны.
+    pub little_endian: bool,
+}
+
+impl Default for BinOptions {
+    fn default() -> Self {
+        Self {
+            checksum: false,
+            encoding: DescriptionEncoding::default(),
+            lossy_utf8: false,
+            max_record_size: DEFAULT_MAX_RECORD_SIZE,
+            little_endian: false,
+        }
+    }
+}
+
+/// Кодировка поля `DESCRIPTION` при чтении бинарного формата.
+///
+/// Запись всегда выполняется в UTF-8; другие кодировки нужны только для чтения
+/// старых файлов, описания в которых не являются валидным UTF-8.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DescriptionEncoding {
+    /// UTF-8 со строгой проверкой: невалидные последовательности приводят к ошибке.
+    #[default]
+    Utf8,
+    /// ISO-8859-1 (Latin-1): каждый байт отображается в символ Unicode с тем же кодом.
+    #[cfg(feature = "encoding")]
+    Latin1,
+}
 
 /// Читает и парсит транзакции из бинарного формата.
 ///
@@ -147,28 +413,414 @@ const MIN_RECORD_SIZE: u32 = 46;
 /// * Формат данных некорректен.
 /// * Возникла ошибка ввода-вывода при чтении из `reader`.
 fn parse_from_bin(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+    parse_from_bin_with(reader, &BinOptions::default())
+}
+
+/// Читает и парсит транзакции из бинарного формата с заданными параметрами.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`], если:
+/// * Формат данных некорректен.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+/// * Включена проверка контрольных сумм и CRC32 записи не совпал с вычисленным
+///   ([`error::ParseError::ChecksumMismatch`]).
+pub fn parse_from_bin_with(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> Result<Vec<Transaction>, error::ParseError> {
+    match parse_from_bin_partial(reader, options) {
+        (result, None) => Ok(result),
+        (_, Some(err)) => Err(err),
+    }
+}
+
+/// Читает транзакции из бинарного формата, сохраняя записи, прочитанные до ошибки.
+///
+/// В отличие от [`parse_from_bin_with`], при ошибке не теряет уже разобранные транзакции:
+/// возвращает их вместе с ошибкой, остановившей чтение. Если поток закончился ровно
+/// на границе записи, ошибки нет. Если поток оборвался внутри записи (в том числе
+/// внутри заголовка), возвращается [`error::ParseError::TruncatedRecord`].
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+pub fn parse_from_bin_partial(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> (Vec<Transaction>, Option<error::ParseError>) {
     let mut result = Vec::<Transaction>::new();
+    let mut position = 0u64;
     loop {
-        match Header::read(reader) {
-            Ok(header) => {
-                if header.record_size < MIN_RECORD_SIZE {
-                    return Err(error::ParseError::InvalidFormat(
-                        "mailformed record. record size too small".to_string(),
-                    ));
-                }
-                let mut buf = vec![0u8; header.record_size as usize];
-                reader.read_exact(&mut buf)?;
-                let mut buffer_reader = Cursor::new(buf);
-                let tx = read_tx(&mut buffer_reader, header.record_size)?;
-                result.push(tx);
-            }
-            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
-            Err(err) => return Err(error::ParseError::InvalidFormat(err.to_string())),
+        match read_record(reader, options, result.len(), &mut position) {
+            Ok(Some(tx)) => result.push(tx),
+            Ok(None) => return (result, None),
+            Err(err) => return (result, Some(err)),
+        }
+    }
+}
+
+/// Читает записи бинарного формата, не разбирая их в транзакции, а сохраняя исходные байты.
+///
+/// Каждый элемент результата — запись целиком: заголовок, тело и, если включена
+/// проверка контрольных сумм, CRC32. Записи при этом всё равно проверяются на корректность.
+/// Результат можно записать обратно без повторной сериализации через [`dump_raw_bin`].
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_bin_with`].
+pub fn parse_from_bin_raw(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> Result<Vec<Vec<u8>>, error::ParseError> {
+    let mut result = Vec::<Vec<u8>>::new();
+    let mut position = 0u64;
+    while let Some(body) = read_record_body(reader, options, result.len())? {
+        read_tx(&body, position + Header::sizeof() as u64, options)?;
+        position += record_len(body.len(), options);
+        let mut raw = Header::new(body.len() as u32).dump(options.little_endian);
+        raw.extend_from_slice(&body);
+        if options.checksum {
+            raw.extend_from_slice(&u32_bytes(crc32fast::hash(&body), options.little_endian));
         }
+        result.push(raw);
     }
     Ok(result)
 }
 
+/// Читает транзакции из бинарного формата вместе со смещениями записей в потоке.
+///
+/// Смещение — позиция в байтах от начала `reader`, с которой начинается заголовок записи;
+/// у первой записи оно равно нулю. Смещения можно использовать для построения внешнего индекса.
+/// Используются параметры [`BinOptions::default`].
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_bin_with`].
+pub fn parse_from_bin_with_offsets(
+    reader: &mut impl io::Read,
+) -> Result<Vec<(u64, Transaction)>, error::ParseError> {
+    parse_from_bin_with_offsets_with(reader, &BinOptions::default())
+}
+
+/// Читает транзакции вместе со смещениями записей аналогично [`parse_from_bin_with_offsets`],
+/// но с заданными параметрами формата.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_bin_with`].
+pub fn parse_from_bin_with_offsets_with(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> Result<Vec<(u64, Transaction)>, error::ParseError> {
+    let mut result = Vec::<(u64, Transaction)>::new();
+    let mut position = 0u64;
+    loop {
+        let offset = position;
+        match read_record(reader, options, result.len(), &mut position)? {
+            Some(tx) => result.push((offset, tx)),
+            None => return Ok(result),
+        }
+    }
+}
+
+/// Читает одну запись бинарного формата, начинающуюся со смещения `offset` от начала
+/// `reader`, без чтения предыдущих записей. Используются параметры [`BinOptions::default`].
+///
+/// Смещения записей возвращает [`parse_from_bin_with_offsets`].
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`], если:
+/// * По смещению `offset` нет записи: поток заканчивается раньше
+///   ([`error::ParseError::InvalidFormat`]) или обрывается внутри записи
+///   ([`error::ParseError::TruncatedRecord`]).
+/// * Сигнатура или содержимое записи некорректны; позиция в сообщении отсчитывается
+///   от начала `reader`.
+/// * Возникла ошибка ввода-вывода при перемещении или чтении.
+pub fn read_record_at<R: io::Read + io::Seek>(
+    reader: &mut R,
+    offset: u64,
+) -> Result<Transaction, error::ParseError> {
+    read_record_at_with(reader, offset, &BinOptions::default())
+}
+
+/// Читает одну запись по смещению аналогично [`read_record_at`], но с заданными
+/// параметрами формата (они должны совпадать с параметрами, с которыми записан файл).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`read_record_at`].
+pub fn read_record_at_with<R: io::Read + io::Seek>(
+    reader: &mut R,
+    offset: u64,
+    options: &BinOptions,
+) -> Result<Transaction, error::ParseError> {
+    reader.seek(io::SeekFrom::Start(offset))?;
+    let mut position = offset;
+    read_record(reader, options, 0, &mut position)?
+        .ok_or_else(|| error::ParseError::InvalidFormat(format!("no record at byte {}", offset)))
+}
+
+/// Читает транзакции из повреждённого потока бинарного формата, пропуская мусор между записями.
+///
+/// Предназначена для восстановления данных, а не для обычного чтения. Встретив неверную
+/// сигнатуру или запись, которую не удаётся разобрать, функция сдвигается на один байт
+/// и ищет следующую последовательность [`MAGIC`], после чего продолжает чтение. Обрыв
+/// последней записи тоже считается мусором. Поток читается в память целиком.
+///
+/// Возвращает восстановленные транзакции и число пропущенных байт.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError::IOError`], если возникла ошибка ввода-вывода
+/// при чтении из `reader`; ошибки формата не возвращаются.
+pub fn parse_from_bin_resync(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+) -> Result<(Vec<Transaction>, u64), error::ParseError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut result = Vec::<Transaction>::new();
+    let mut skipped = 0u64;
+    let mut position = 0u64;
+    while let Some(rest) = data
+        .get(position as usize..)
+        .filter(|rest| !rest.is_empty())
+    {
+        let start = position;
+        if rest.starts_with(&MAGIC)
+            && let Ok(Some(tx)) = read_record(&mut &rest[..], options, result.len(), &mut position)
+        {
+            result.push(tx);
+            continue;
+        }
+        position = start + 1;
+        skipped += 1;
+    }
+    Ok((result, skipped))
+}
+
+/// Разбирает транзакции из бинарного формата параллельно. Доступна с фичей `rayon`.
+///
+/// Сначала буфер последовательно просматривается по заголовкам, чтобы найти границы
+/// записей, затем тела записей разбираются параллельно. Порядок транзакций в результате
+/// совпадает с порядком записей в буфере. Используются параметры [`BinOptions::default`].
+///
+/// # Аргументы
+///
+/// * `data` - Содержимое файла целиком: для параллельного разбора нужен произвольный доступ.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_bin_with`].
+/// Если некорректных записей несколько, возвращается ошибка первой из них.
+#[cfg(feature = "rayon")]
+pub fn parse_from_bin_parallel(data: &[u8]) -> Result<Vec<Transaction>, error::ParseError> {
+    use rayon::prelude::*;
+
+    let options = BinOptions::default();
+    let records = scan_records(data, &options)?;
+    let parsed: Vec<_> = records
+        .par_iter()
+        .map(|&(offset, size)| read_tx(&data[offset..offset + size], offset as u64, &options))
+        .collect();
+    parsed.into_iter().collect()
+}
+
+// Находит границы записей в буфере: возвращает пары (смещение тела, размер тела).
+#[cfg(feature = "rayon")]
+fn scan_records(
+    data: &[u8],
+    options: &BinOptions,
+) -> Result<Vec<(usize, usize)>, error::ParseError> {
+    let mut records = Vec::new();
+    let mut position = 0;
+    loop {
+        let truncated = error::ParseError::TruncatedRecord {
+            recovered: records.len(),
+        };
+        let header = match Header::read(&mut &data[position..], options.little_endian) {
+            Ok(Some(header)) => header,
+            Ok(None) => return Ok(records),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Err(truncated),
+            Err(err) => return Err(error::ParseError::InvalidFormat(err.to_string())),
+        };
+        check_record_size(&header, options)?;
+        let offset = position + Header::sizeof();
+        let size = header.record_size as usize;
+        if data.len() - offset < size {
+            return Err(truncated);
+        }
+        records.push((offset, size));
+        position = offset + size;
+    }
+}
+
+// Размер записи в потоке: заголовок, тело и, если включена, контрольная сумма.
+fn record_len(body_len: usize, options: &BinOptions) -> u64 {
+    let checksum_size = if options.checksum {
+        mem::size_of::<u32>()
+    } else {
+        0
+    };
+    (Header::sizeof() + body_len + checksum_size) as u64
+}
+
+/// Читает одну запись целиком. Возвращает `None` при чистом конце потока.
+///
+/// `position` — смещение начала записи в потоке; после чтения сдвигается на её длину.
+fn read_record(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+    recovered: usize,
+    position: &mut u64,
+) -> Result<Option<Transaction>, error::ParseError> {
+    let Some(body) = read_record_body(reader, options, recovered)? else {
+        return Ok(None);
+    };
+    let tx = read_tx(&body, *position + Header::sizeof() as u64, options)?;
+    *position += record_len(body.len(), options);
+    Ok(Some(tx))
+}
+
+// Проверяет RECORD_SIZE из заголовка до выделения памяти под тело записи.
+fn check_record_size(header: &Header, options: &BinOptions) -> Result<(), error::ParseError> {
+    if header.record_size < MIN_RECORD_SIZE {
+        return Err(error::ParseError::InvalidFormat(
+            "mailformed record. record size too small".to_string(),
+        ));
+    }
+    if header.record_size > options.max_record_size {
+        return Err(error::ParseError::RecordTooLarge {
+            size: header.record_size,
+        });
+    }
+    Ok(())
+}
+
+// Читает заголовок записи и проверяет RECORD_SIZE. Возвращает `None` при чистом конце потока.
+fn read_header(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+    recovered: usize,
+) -> Result<Option<Header>, error::ParseError> {
+    let header = match Header::read(reader, options.little_endian) {
+        Ok(Some(header)) => header,
+        Ok(None) => return Ok(None),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            return Err(error::ParseError::TruncatedRecord { recovered });
+        }
+        Err(err) => return Err(error::ParseError::InvalidFormat(err.to_string())),
+    };
+    check_record_size(&header, options)?;
+    Ok(Some(header))
+}
+
+/// Быстро проверяет структуру потока бинарного формата, не разбирая транзакции.
+///
+/// Для каждой записи проверяются сигнатура, `RECORD_SIZE` (не меньше минимального и не
+/// больше [`DEFAULT_MAX_RECORD_SIZE`]) и его согласованность с `DESC_LEN`; описание
+/// пропускается без декодирования, значения полей не проверяются. Используются параметры
+/// [`BinOptions::default`].
+///
+/// Возвращает количество записей.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`], если:
+/// * Сигнатура записи неверна или `RECORD_SIZE` не согласован с `DESC_LEN`
+///   ([`error::ParseError::InvalidFormat`]).
+/// * Запись слишком велика ([`error::ParseError::RecordTooLarge`]).
+/// * Поток оборвался посреди записи ([`error::ParseError::TruncatedRecord`]).
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+pub fn verify_bin(reader: &mut impl io::Read) -> Result<usize, error::ParseError> {
+    let options = BinOptions::default();
+    let mut count = 0;
+    let mut position = 0u64;
+    while let Some(header) = read_header(reader, &options, count)? {
+        let truncated = |err: io::Error| {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                error::ParseError::TruncatedRecord { recovered: count }
+            } else {
+                error::ParseError::from(err)
+            }
+        };
+        let mut fixed = [0u8; BinLayout::DESCRIPTION_OFFSET];
+        reader.read_exact(&mut fixed).map_err(truncated)?;
+        let desc_len = read_u32(
+            &mut &fixed[BinLayout::DESC_LEN.offset..],
+            options.little_endian,
+        )?;
+        if u64::from(header.record_size) != u64::from(MIN_RECORD_SIZE) + u64::from(desc_len) {
+            return Err(at_byte(
+                error::ParseError::InvalidFormat(
+                    "mailformed record. record size mismatch".to_string(),
+                ),
+                position + (Header::sizeof() + BinLayout::DESC_LEN.offset) as u64,
+            ));
+        }
+        let skipped = io::copy(
+            &mut io::Read::take(&mut *reader, u64::from(desc_len)),
+            &mut io::sink(),
+        )?;
+        if skipped < u64::from(desc_len) {
+            return Err(error::ParseError::TruncatedRecord { recovered: count });
+        }
+        position += record_len(header.record_size as usize, &options);
+        count += 1;
+    }
+    Ok(count)
+}
+
+// Читает заголовок и тело записи, проверяя размер и контрольную сумму.
+fn read_record_body(
+    reader: &mut impl io::Read,
+    options: &BinOptions,
+    recovered: usize,
+) -> Result<Option<Vec<u8>>, error::ParseError> {
+    let truncated = |err: io::Error| {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            error::ParseError::TruncatedRecord { recovered }
+        } else {
+            error::ParseError::from(err)
+        }
+    };
+
+    let Some(header) = read_header(reader, options, recovered)? else {
+        return Ok(None);
+    };
+    let mut buf = vec![0u8; header.record_size as usize];
+    reader.read_exact(&mut buf).map_err(truncated)?;
+    if options.checksum {
+        let expected = read_u32(reader, options.little_endian).map_err(truncated)?;
+        verify_checksum(expected, &buf)?;
+    }
+    Ok(Some(buf))
+}
+
 /// Сериализует список транзакций в бинарный формат, записывая результат во `writer`.
 ///
 /// # Аргументы
@@ -177,6 +829,8 @@ fn parse_from_bin(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error:
 ///   Должен реализовывать трейт [`std::io::Write`].
 /// * `transactions` - Слайс транзакций для записи.
 ///
+/// Возвращает количество записанных транзакций.
+///
 /// # Ошибки
 ///
 /// Возвращает [`DumpError`], если:
@@ -184,23 +838,122 @@ fn parse_from_bin(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error:
 fn dump_as_bin<W: io::Write>(
     writer: &mut W,
     transactions: &[Transaction],
-) -> Result<(), error::DumpError> {
+) -> Result<usize, error::DumpError> {
+    dump_as_bin_with(writer, transactions, &BinOptions::default())
+}
+
+/// Сериализует список транзакций в бинарный формат с заданными параметрами.
+///
+/// # Аргументы
+///
+/// * `writer` - Приемник данных, реализующий [`std::io::Write`].
+/// * `transactions` - Слайс транзакций для записи.
+/// * `options` - Параметры формата (см. [`BinOptions`]).
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::DumpError`], если:
+/// * Произошла ошибка ввода-вывода при записи во `writer`.
+/// * Описание транзакции длиннее, чем позволяет 32-битный `RECORD_SIZE`
+///   ([`error::DumpError::DescriptionTooLong`]). Транзакции до неё уже записаны.
+pub fn dump_as_bin_with<W: io::Write>(
+    writer: &mut W,
+    transactions: &[Transaction],
+    options: &BinOptions,
+) -> Result<usize, error::DumpError> {
+    dump_records(writer, transactions, options, &mut parser::Progress::none())
+}
+
+fn dump_records<W: io::Write>(
+    writer: &mut W,
+    transactions: &[Transaction],
+    options: &BinOptions,
+    progress: &mut parser::Progress,
+) -> Result<usize, error::DumpError> {
+    // Один буфер на весь вызов: в цикле записи нет аллокаций на каждую транзакцию.
+    let mut scratch = Vec::<u8>::new();
     for tx in transactions {
-        writer.write_all(&tx_to_bin(tx))?;
+        scratch.clear();
+        write_record(tx, &mut scratch, options.little_endian)?;
+        writer.write_all(&scratch)?;
+        if options.checksum {
+            let crc = crc32fast::hash(&scratch[Header::sizeof()..]);
+            writer.write_all(&u32_bytes(crc, options.little_endian))?;
+        }
+        progress.tick();
     }
-    Ok(())
+    Ok(transactions.len())
 }
 
-fn tx_to_bin(tx: &Transaction) -> Vec<u8> {
-    let tx_bytes_size = calculate_size(tx);
-    let mut result = Vec::<u8>::with_capacity(tx_bytes_size);
-    let raw_header = Header::new(tx_bytes_size as u32).dump();
-    let raw_tx = dump_tx(tx);
+/// Дописывает транзакции в конец существующего потока бинарного формата.
+///
+/// У формата нет общего заголовка файла: каждая запись самодостаточна (сигнатура, размер,
+/// тело и, если включена, контрольная сумма), поэтому функцию можно многократно вызывать
+/// для одного и того же открытого файла (например, открытого с
+/// [`std::fs::OpenOptions::append`]). Параметры `options` должны совпадать с теми,
+/// с которыми был записан файл, иначе он не прочитается целиком.
+///
+/// Возвращает количество дописанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_bin_append<W: io::Write>(
+    writer: &mut W,
+    transactions: &[Transaction],
+    options: &BinOptions,
+) -> Result<usize, error::DumpError> {
+    dump_as_bin_with(writer, transactions, options)
+}
 
-    result.extend_from_slice(&raw_header);
-    result.extend_from_slice(&raw_tx);
+/// Записывает сохранённые байты записей бинарного формата как есть, без повторной сериализации.
+///
+/// Используется вместе с [`parse_from_bin_raw`] для побайтно точного воспроизведения
+/// исходного файла (например, при архивации). Возвращает количество записанных записей.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_raw_bin<W: io::Write>(
+    writer: &mut W,
+    raw_records: &[Vec<u8>],
+) -> Result<usize, error::DumpError> {
+    for raw in raw_records {
+        writer.write_all(raw)?;
+    }
+    Ok(raw_records.len())
+}
 
-    result
+fn verify_checksum(expected: u32, body: &[u8]) -> Result<(), error::ParseError> {
+    let actual = crc32fast::hash(body);
+    if expected != actual {
+        return Err(error::ParseError::ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+// Дописывает в `out` запись целиком: заголовок и тело транзакции.
+// Слишком длинное описание не обрезается, а приводит к ошибке.
+fn write_record(
+    tx: &Transaction,
+    out: &mut Vec<u8>,
+    little_endian: bool,
+) -> Result<(), error::DumpError> {
+    check_description_len(tx.description.len())?;
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&u32_bytes(calculate_size(tx) as u32, little_endian));
+    dump_tx(tx, out, little_endian);
+    Ok(())
+}
+
+// Проверяет, что описание длиной `len` байт помещается в запись с размером в u32.
+fn check_description_len(len: usize) -> Result<(), error::DumpError> {
+    if len > MAX_DESCRIPTION_LEN {
+        return Err(error::DumpError::DescriptionTooLong { len });
+    }
+    Ok(())
 }
 
 fn calculate_size(tx: &Transaction) -> usize {
@@ -214,28 +967,25 @@ fn calculate_size(tx: &Transaction) -> usize {
 
 fn sizeof_tx(tx: &Transaction) -> usize {
     size_of_val(&tx.id)
-        + size_of_val(&(tx.r#type as u8))
+        + size_of_val(&u8::from(tx.r#type))
         + size_of_val(&tx.from_user)
         + size_of_val(&tx.to_user)
         + size_of_val(&tx.amount)
         + size_of_val(&tx.timestamp)
-        + size_of_val(&(tx.status as u8))
+        + size_of_val(&u8::from(tx.status))
         + tx.description.len()
 }
 
-fn dump_tx(tx: &Transaction) -> Vec<u8> {
-    let mut res = Vec::<u8>::with_capacity(sizeof_tx(tx));
-    res.extend_from_slice(&tx.id.to_be_bytes());
-    res.extend_from_slice(&(tx.r#type as u8).to_be_bytes());
-    res.extend_from_slice(&tx.from_user.to_be_bytes());
-    res.extend_from_slice(&tx.to_user.to_be_bytes());
-    res.extend_from_slice(&tx.amount.to_be_bytes());
-    res.extend_from_slice(&tx.timestamp.to_be_bytes());
-    res.extend_from_slice(&(tx.status as u8).to_be_bytes());
-    res.extend_from_slice(&(tx.description.len() as u32).to_be_bytes());
+fn dump_tx(tx: &Transaction, res: &mut Vec<u8>, little_endian: bool) {
+    res.extend_from_slice(&u64_bytes(tx.id, little_endian));
+    res.push(u8::from(tx.r#type));
+    res.extend_from_slice(&u64_bytes(tx.from_user, little_endian));
+    res.extend_from_slice(&u64_bytes(tx.to_user, little_endian));
+    res.extend_from_slice(&u64_bytes(tx.amount, little_endian));
+    res.extend_from_slice(&u64_bytes(tx.timestamp, little_endian));
+    res.push(u8::from(tx.status));
+    res.extend_from_slice(&u32_bytes(tx.description.len() as u32, little_endian));
     res.extend_from_slice(tx.description.as_bytes());
-
-    res
 }
 
 pub(crate) struct BinParser;
@@ -248,14 +998,82 @@ impl parser::Parser for BinParser {
     fn dump(
         writer: &mut impl io::Write,
         transactions: &[Transaction],
-    ) -> Result<(), error::DumpError> {
+    ) -> Result<usize, error::DumpError> {
         dump_as_bin(writer, transactions)
     }
+
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut parser::Progress,
+    ) -> Result<Vec<Transaction>, error::ParseError> {
+        let options = BinOptions::default();
+        let mut result = Vec::new();
+        let mut position = 0u64;
+        while let Some(tx) = read_record(reader, &options, result.len(), &mut position)? {
+            result.push(tx);
+            progress.tick();
+        }
+        Ok(result)
+    }
+
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+        progress: &mut parser::Progress,
+    ) -> Result<usize, error::DumpError> {
+        dump_records(writer, transactions, &BinOptions::default(), progress)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::fee_and_reversal;
+
+    /// Размер контрольной суммы CRC32, дописываемой после тела записи.
+    const CHECKSUM_SIZE: usize = mem::size_of::<u32>();
+
+    // Перед каждой порцией данных возвращает `Interrupted`, как сетевой поток,
+    // прерванный сигналом; данные отдаёт по нескольку байт.
+    struct InterruptingReader<'a> {
+        data: &'a [u8],
+        interrupt: bool,
+    }
+
+    impl io::Read for InterruptingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let n = buf.len().min(self.data.len()).min(3);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_parse_retries_interrupted() {
+        let txs = vec![
+            crate::types::TransactionBuilder::new()
+                .id(1)
+                .description("first")
+                .build(),
+            crate::types::TransactionBuilder::new()
+                .id(2)
+                .description("second")
+                .build(),
+        ];
+        let mut data = Vec::new();
+        dump_as_bin(&mut data, &txs).unwrap();
+
+        let mut reader = InterruptingReader {
+            data: &data,
+            interrupt: false,
+        };
+        assert_eq!(parse_from_bin(&mut reader).unwrap(), txs);
+    }
 
     #[test]
     fn test_dump_header() {
@@ -267,7 +1085,7 @@ mod tests {
             0x00, 0x00, 0x00, 0x0A
         ];
 
-        let got = header.dump();
+        let got = header.dump(false);
 
         assert_eq!(got.len(), 8);
 
@@ -300,7 +1118,8 @@ mod tests {
             0x74, 0x65, 0x73, 0x74,
         ];
 
-        let got = dump_tx(&tx);
+        let mut got = Vec::new();
+        dump_tx(&tx, &mut got, false);
 
         assert_eq!(expected[..], got[..]);
     }
@@ -403,4 +1222,736 @@ mod tests {
 
         assert!(got.is_err());
     }
+
+    fn checksum_sample() -> Vec<Transaction> {
+        vec![
+            Transaction {
+                id: 1001,
+                r#type: TxType::Deposit,
+                from_user: 0,
+                to_user: 501,
+                amount: 50000,
+                timestamp: 1672531200000,
+                status: TxStatus::Success,
+                description: "first".to_string(),
+            },
+            Transaction {
+                id: 1002,
+                r#type: TxType::Transfer,
+                from_user: 501,
+                to_user: 502,
+                amount: 15000,
+                timestamp: 1672534800000,
+                status: TxStatus::Failure,
+                description: "second".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_checksum_roundtrip() {
+        let txs = checksum_sample();
+        let options = BinOptions {
+            checksum: true,
+            ..Default::default()
+        };
+
+        let mut buffer = Vec::new();
+        dump_as_bin_with(&mut buffer, &txs, &options).unwrap();
+
+        let mut plain = Vec::new();
+        dump_as_bin(&mut plain, &txs).unwrap();
+        assert_eq!(buffer.len(), plain.len() + 2 * CHECKSUM_SIZE);
+
+        let got = parse_from_bin_with(&mut buffer.as_slice(), &options).unwrap();
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_checksum_detects_flipped_byte() {
+        let txs = checksum_sample();
+        let options = BinOptions {
+            checksum: true,
+            ..Default::default()
+        };
+
+        let mut buffer = Vec::new();
+        dump_as_bin_with(&mut buffer, &txs, &options).unwrap();
+
+        // портим сумму (AMOUNT) первой записи: заголовок + TX_ID + TX_TYPE + 2 * USER_ID
+        buffer[Header::sizeof() + 8 + 1 + 8 + 8 + 7] ^= 0xFF;
+
+        let got = parse_from_bin_with(&mut buffer.as_slice(), &options);
+
+        assert!(matches!(
+            got,
+            Err(error::ParseError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_old_format_parses_without_checksum() {
+        let txs = checksum_sample();
+
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        let got = parse_from_bin_with(&mut buffer.as_slice(), &BinOptions::default()).unwrap();
+        assert_eq!(got, txs);
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_latin1_description() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            0x59, 0x50, 0x42, 0x4e,
+            0x00, 0x00, 0x00, 0x31,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0x00, 0x00, 0x00, 0x03,
+            0x63, 0x61, 0xe9, // "caé" в Latin-1, 0xe9 - невалидный UTF-8
+        ];
+
+        let strict = parse_from_bin(&mut &data[..]);
+        assert!(strict.is_err());
+
+        let options = BinOptions {
+            encoding: DescriptionEncoding::Latin1,
+            ..Default::default()
+        };
+        let got = parse_from_bin_with(&mut &data[..], &options).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].description, "caé");
+    }
+
+    #[test]
+    fn test_lossy_utf8_description() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            0x59, 0x50, 0x42, 0x4e,
+            0x00, 0x00, 0x00, 0x31,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0x00, 0x00, 0x00, 0x03,
+            0x61, 0xff, 0x62, // 0xff - невалидный байт UTF-8
+        ];
+
+        assert!(parse_from_bin(&mut &data[..]).is_err());
+
+        let options = BinOptions {
+            lossy_utf8: true,
+            ..Default::default()
+        };
+        let got = parse_from_bin_with(&mut &data[..], &options).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].description, "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_fee_and_reversal_roundtrip() {
+        let txs = fee_and_reversal();
+        let mut buffer = Vec::new();
+
+        dump_as_bin(&mut buffer, &txs).unwrap();
+        let got = parse_from_bin(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_new_tx_type_discriminants() {
+        let txs = fee_and_reversal();
+        let mut fee = Vec::new();
+        dump_tx(&txs[0], &mut fee, false);
+        let mut reversal = Vec::new();
+        dump_tx(&txs[1], &mut reversal, false);
+        assert_eq!(fee[8], 3);
+        assert_eq!(reversal[8], 4);
+        assert_eq!(calculate_size(&txs[0]) as u32, MIN_RECORD_SIZE + 11);
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+        let first_len = calculate_size(&txs[0]) + Header::sizeof();
+
+        let cases = [
+            (0, None),
+            (3, Some(0)),
+            (Header::sizeof(), Some(0)),
+            (Header::sizeof() + 10, Some(0)),
+            (first_len, None),
+            (first_len + 5, Some(1)),
+            (buffer.len() - 1, Some(1)),
+            (buffer.len(), None),
+        ];
+
+        for (len, recovered) in cases {
+            let got = parse_from_bin(&mut &buffer[..len]);
+            match recovered {
+                None => assert!(got.is_ok(), "offset {}", len),
+                Some(count) => assert!(
+                    matches!(
+                        got,
+                        Err(error::ParseError::TruncatedRecord { recovered }) if recovered == count
+                    ),
+                    "offset {}: {:?}",
+                    len,
+                    got
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_partial_keeps_records() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        let (got, err) =
+            parse_from_bin_partial(&mut &buffer[..buffer.len() - 3], &BinOptions::default());
+
+        assert_eq!(got, txs[..1]);
+        assert!(matches!(
+            err,
+            Some(error::ParseError::TruncatedRecord { recovered: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_multibyte_description_roundtrip() {
+        let txs = vec![Transaction {
+            id: 1001,
+            r#type: TxType::Transfer,
+            from_user: 501,
+            to_user: 502,
+            amount: 15000,
+            timestamp: 1672534800000,
+            status: TxStatus::Success,
+            description: "Платёж за услуги 💳".to_string(),
+        }];
+        // DESC_LEN хранит длину в байтах, а не в символах
+        assert_ne!(txs[0].description.len(), txs[0].description.chars().count());
+
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        let desc_len_offset = Header::sizeof() + MIN_RECORD_SIZE as usize - 4;
+        let desc_len = u32::from_be_bytes(
+            buffer[desc_len_offset..desc_len_offset + 4]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(desc_len as usize, txs[0].description.len());
+
+        let got = parse_from_bin(&mut buffer.as_slice()).unwrap();
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_read_string_guards_size() {
+        let mut data: &[u8] = b"test";
+
+        let got = read_string(usize::MAX, 4, &mut data, &BinOptions::default());
+
+        assert!(matches!(got, Err(error::ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_huge_desc_len_does_not_overflow() {
+        #[rustfmt::skip]
+        let mut data: &[u8] = &[
+            0x59, 0x50, 0x42, 0x4e,
+            0x00, 0x00, 0x00, 0x32,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe9,
+            0x00,
+            0xff, 0xff, 0xff, 0xff,
+            0x74, 0x65, 0x73, 0x74,
+        ];
+
+        let got = parse_from_bin(&mut data);
+
+        assert!(matches!(got, Err(error::ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_record_too_large() {
+        #[rustfmt::skip]
+        let mut data: &[u8] = &[
+            0x59, 0x50, 0x42, 0x4e,
+            0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let got = parse_from_bin(&mut data);
+
+        assert!(matches!(
+            got,
+            Err(error::ParseError::RecordTooLarge { size: 0xffff_ffff })
+        ));
+    }
+
+    #[test]
+    fn test_max_record_size_configurable() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        let strict = BinOptions {
+            max_record_size: MIN_RECORD_SIZE + 5,
+            ..Default::default()
+        };
+        let got = parse_from_bin_with(&mut buffer.as_slice(), &strict);
+        assert!(matches!(
+            got,
+            Err(error::ParseError::RecordTooLarge { size: 52 })
+        ));
+
+        let relaxed = BinOptions {
+            max_record_size: u32::MAX,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_from_bin_with(&mut buffer.as_slice(), &relaxed).unwrap(),
+            txs
+        );
+    }
+
+    #[test]
+    fn test_raw_roundtrip_is_byte_identical() {
+        for checksum in [false, true] {
+            let options = BinOptions {
+                checksum,
+                ..Default::default()
+            };
+            let mut original = Vec::new();
+            dump_as_bin_with(&mut original, &checksum_sample(), &options).unwrap();
+
+            let raw = parse_from_bin_raw(&mut original.as_slice(), &options).unwrap();
+            let mut got = Vec::new();
+            dump_raw_bin(&mut got, &raw).unwrap();
+
+            assert_eq!(raw.len(), 2);
+            assert_eq!(got, original);
+        }
+    }
+
+    // Прежняя реализация с отдельными векторами на каждую запись.
+    fn legacy_dump(transactions: &[Transaction], checksum: bool) -> Vec<u8> {
+        let mut result = Vec::new();
+        for tx in transactions {
+            let size = calculate_size(tx);
+            let mut raw = Header::new(size as u32).dump(false);
+            let mut body = Vec::with_capacity(sizeof_tx(tx));
+            dump_tx(tx, &mut body, false);
+            raw.extend_from_slice(&body);
+            result.extend_from_slice(&raw);
+            if checksum {
+                result.extend_from_slice(&crc32fast::hash(&body).to_be_bytes());
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_scratch_buffer_output_is_byte_identical() {
+        let mut txs = checksum_sample();
+        txs.extend(fee_and_reversal());
+        txs.push(Transaction {
+            description: String::new(),
+            ..txs[0].clone()
+        });
+
+        for checksum in [false, true] {
+            let options = BinOptions {
+                checksum,
+                ..Default::default()
+            };
+            let mut got = Vec::new();
+            dump_as_bin_with(&mut got, &txs, &options).unwrap();
+
+            assert_eq!(got, legacy_dump(&txs, checksum));
+        }
+    }
+
+    #[test]
+    fn test_parse_with_offsets() {
+        let txs = checksum_sample();
+        for checksum in [false, true] {
+            let options = BinOptions {
+                checksum,
+                ..BinOptions::default()
+            };
+            let mut buffer = Vec::new();
+            dump_as_bin_with(&mut buffer, &txs, &options).unwrap();
+
+            let got = parse_from_bin_with_offsets_with(&mut buffer.as_slice(), &options).unwrap();
+
+            let checksum_size = if checksum { CHECKSUM_SIZE } else { 0 };
+            let first_record_size = calculate_size(&txs[0]) + checksum_size;
+            assert_eq!(got[0], (0, txs[0].clone()));
+            assert_eq!(
+                got[1],
+                (
+                    (Header::sizeof() + first_record_size) as u64,
+                    txs[1].clone()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_record_at() {
+        let txs: Vec<_> = (1..=5)
+            .map(|id| {
+                crate::types::TransactionBuilder::new()
+                    .id(id)
+                    .amount(id * 100)
+                    .description("x".repeat(id as usize))
+                    .build()
+            })
+            .collect();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+        let offsets: Vec<u64> = parse_from_bin_with_offsets(&mut buffer.as_slice())
+            .unwrap()
+            .into_iter()
+            .map(|(offset, _)| offset)
+            .collect();
+
+        let mut cursor = io::Cursor::new(buffer.clone());
+        assert_eq!(read_record_at(&mut cursor, offsets[2]).unwrap(), txs[2]);
+        // порядок чтения не важен
+        assert_eq!(read_record_at(&mut cursor, offsets[0]).unwrap(), txs[0]);
+        assert_eq!(read_record_at(&mut cursor, offsets[4]).unwrap(), txs[4]);
+
+        let end = buffer.len() as u64;
+        assert!(matches!(
+            read_record_at(&mut cursor, end),
+            Err(error::ParseError::InvalidFormat(msg)) if msg == format!("no record at byte {}", end)
+        ));
+        // смещение не на границе записи
+        assert!(read_record_at(&mut cursor, offsets[2] + 1).is_err());
+
+        let options = BinOptions {
+            checksum: true,
+            ..BinOptions::default()
+        };
+        let mut buffer = Vec::new();
+        dump_as_bin_with(&mut buffer, &txs, &options).unwrap();
+        let offsets = parse_from_bin_with_offsets_with(&mut buffer.as_slice(), &options).unwrap();
+        let mut cursor = io::Cursor::new(buffer);
+        assert_eq!(
+            read_record_at_with(&mut cursor, offsets[3].0, &options).unwrap(),
+            txs[3]
+        );
+    }
+
+    #[test]
+    fn test_parse_resync_skips_garbage() {
+        let txs = checksum_sample();
+        let mut first = Vec::new();
+        dump_as_bin(&mut first, &txs[..1]).unwrap();
+        let mut second = Vec::new();
+        dump_as_bin(&mut second, &txs[1..]).unwrap();
+
+        // мусор содержит обрывок сигнатуры, а в конце — оборванная запись
+        let garbage = b"junk YPB\x00\x01";
+        let mut buffer = b"xx".to_vec();
+        buffer.extend_from_slice(&first);
+        buffer.extend_from_slice(garbage);
+        buffer.extend_from_slice(&second);
+        buffer.extend_from_slice(&second[..10]);
+        assert!(parse_from_bin(&mut buffer.as_slice()).is_err());
+
+        let (got, skipped) =
+            parse_from_bin_resync(&mut buffer.as_slice(), &BinOptions::default()).unwrap();
+        assert_eq!(got, txs);
+        assert_eq!(skipped, (2 + garbage.len() + 10) as u64);
+
+        let (got, skipped) =
+            parse_from_bin_resync(&mut first.as_slice(), &BinOptions::default()).unwrap();
+        assert_eq!(got, txs[..1]);
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn test_error_reports_byte_offset() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        // TX_TYPE идёт в теле записи сразу после 8-байтового TX_ID
+        let second_record = Header::sizeof() + calculate_size(&txs[0]);
+        let type_offset = second_record + Header::sizeof() + mem::size_of::<u64>();
+        buffer[type_offset] = 42;
+
+        let got = parse_from_bin(&mut buffer.as_slice());
+
+        assert!(
+            matches!(&got, Err(error::ParseError::InvalidFormat(msg))
+                if msg == &format!("invalid TxType at byte {}", type_offset)),
+            "{:?}",
+            got
+        );
+    }
+
+    #[test]
+    fn test_append_batches() {
+        let first = checksum_sample();
+        let second = fee_and_reversal();
+        for checksum in [false, true] {
+            let options = BinOptions {
+                checksum,
+                ..BinOptions::default()
+            };
+            let mut buffer = Vec::new();
+
+            assert_eq!(
+                dump_as_bin_append(&mut buffer, &first, &options).unwrap(),
+                2
+            );
+            let boundary = buffer.len();
+            dump_as_bin_append(&mut buffer, &second, &options).unwrap();
+
+            assert_eq!(buffer[boundary..boundary + MAGIC.len()], MAGIC);
+            let got = parse_from_bin_with(&mut buffer.as_slice(), &options).unwrap();
+            let expected: Vec<Transaction> = first.iter().chain(&second).cloned().collect();
+            assert_eq!(got, expected);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let txs: Vec<Transaction> = checksum_sample()
+            .into_iter()
+            .chain(fee_and_reversal())
+            .cycle()
+            .take(50)
+            .collect();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        assert_eq!(parse_from_bin_parallel(&buffer).unwrap(), txs);
+        assert_eq!(
+            parse_from_bin_parallel(&buffer).unwrap(),
+            parse_from_bin(&mut buffer.as_slice()).unwrap()
+        );
+        assert!(parse_from_bin_parallel(&[]).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_reports_first_error() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        let second_record = Header::sizeof() + calculate_size(&txs[0]);
+        let type_offset = second_record + Header::sizeof() + mem::size_of::<u64>();
+        let mut malformed = buffer.clone();
+        malformed[type_offset] = 42;
+        assert_eq!(
+            format!("{:?}", parse_from_bin_parallel(&malformed)),
+            format!("{:?}", parse_from_bin(&mut malformed.as_slice()))
+        );
+
+        let truncated = &buffer[..buffer.len() - 1];
+        assert!(matches!(
+            parse_from_bin_parallel(truncated),
+            Err(error::ParseError::TruncatedRecord { recovered: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_description_too_long() {
+        assert!(check_description_len(MAX_DESCRIPTION_LEN).is_ok());
+
+        let got = check_description_len(MAX_DESCRIPTION_LEN + 1);
+
+        assert!(matches!(
+            got,
+            Err(error::DumpError::DescriptionTooLong { len }) if len == MAX_DESCRIPTION_LEN + 1
+        ));
+    }
+
+    #[test]
+    fn test_layout_matches_dump() {
+        let mut next = 0;
+        for field in BinLayout::FIELDS {
+            assert_eq!(field.offset, next, "{}", field.name);
+            next += field.size;
+        }
+        assert_eq!(next, MIN_RECORD_SIZE as usize);
+        assert_eq!(
+            BinLayout::RECORD_SIZE.offset + BinLayout::RECORD_SIZE.size,
+            Header::sizeof()
+        );
+
+        let tx = checksum_sample()[1].clone();
+        let mut body = Vec::new();
+        dump_tx(&tx, &mut body, false);
+        let field = |f: BinField| &body[f.offset..f.offset + f.size];
+
+        assert_eq!(field(BinLayout::TX_ID), tx.id.to_be_bytes());
+        assert_eq!(field(BinLayout::TX_TYPE), [u8::from(tx.r#type)]);
+        assert_eq!(field(BinLayout::FROM_USER_ID), tx.from_user.to_be_bytes());
+        assert_eq!(field(BinLayout::TO_USER_ID), tx.to_user.to_be_bytes());
+        assert_eq!(field(BinLayout::AMOUNT), tx.amount.to_be_bytes());
+        assert_eq!(field(BinLayout::TIMESTAMP), tx.timestamp.to_be_bytes());
+        assert_eq!(field(BinLayout::STATUS), [u8::from(tx.status)]);
+        assert_eq!(
+            field(BinLayout::DESC_LEN),
+            (tx.description.len() as u32).to_be_bytes()
+        );
+        assert_eq!(
+            &body[BinLayout::DESCRIPTION_OFFSET..],
+            tx.description.as_bytes()
+        );
+
+        let mut record = Vec::new();
+        write_record(&tx, &mut record, false).unwrap();
+        let header = |f: BinField| &record[f.offset..f.offset + f.size];
+        assert_eq!(header(BinLayout::MAGIC), MAGIC);
+        assert_eq!(
+            header(BinLayout::RECORD_SIZE),
+            (body.len() as u32).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_endianness_roundtrip() {
+        for little_endian in [false, true] {
+            for checksum in [false, true] {
+                let options = BinOptions {
+                    little_endian,
+                    checksum,
+                    ..BinOptions::default()
+                };
+                let txs = checksum_sample();
+                let mut buffer = Vec::new();
+                dump_as_bin_with(&mut buffer, &txs, &options).unwrap();
+
+                let got = parse_from_bin_with(&mut buffer.as_slice(), &options).unwrap();
+                assert_eq!(
+                    got, txs,
+                    "little_endian={little_endian} checksum={checksum}"
+                );
+                let raw = parse_from_bin_raw(&mut buffer.as_slice(), &options).unwrap();
+                assert_eq!(raw.concat(), buffer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_endianness_outputs_differ() {
+        let tx = Transaction {
+            id: 0x0102_0304_0506_0708,
+            r#type: TxType::Transfer,
+            from_user: 1,
+            to_user: 2,
+            amount: 3,
+            timestamp: 4,
+            status: TxStatus::Success,
+            description: "x".to_string(),
+        };
+        let dump = |little_endian| {
+            let options = BinOptions {
+                little_endian,
+                ..BinOptions::default()
+            };
+            let mut buffer = Vec::new();
+            dump_as_bin_with(&mut buffer, std::slice::from_ref(&tx), &options).unwrap();
+            buffer
+        };
+        let be = dump(false);
+        let le = dump(true);
+
+        assert_eq!(be.len(), le.len());
+        assert_ne!(be, le);
+        // сигнатура одинакова, многобайтовые поля развёрнуты
+        assert_eq!(be[..4], MAGIC);
+        assert_eq!(le[..4], MAGIC);
+        let field = |data: &[u8], field: BinField| {
+            let start = BinLayout::HEADER_SIZE + field.offset;
+            data[start..start + field.size].to_vec()
+        };
+        for f in [BinLayout::TX_ID, BinLayout::AMOUNT, BinLayout::DESC_LEN] {
+            let mut reversed = field(&be, f);
+            reversed.reverse();
+            assert_eq!(field(&le, f), reversed, "{}", f.name);
+        }
+        assert_eq!(
+            field(&be, BinLayout::TX_TYPE),
+            field(&le, BinLayout::TX_TYPE)
+        );
+
+        // файл в чужом порядке байтов не читается
+        assert!(parse_from_bin_with(&mut le.as_slice(), &BinOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_verify_bin() {
+        let txs = checksum_sample();
+        let mut buffer = Vec::new();
+        dump_as_bin(&mut buffer, &txs).unwrap();
+
+        assert_eq!(verify_bin(&mut buffer.as_slice()).unwrap(), txs.len());
+        assert_eq!(verify_bin(&mut io::empty()).unwrap(), 0);
+
+        // обрыв в описании, в заголовке и в фиксированной части второй записи
+        let first_len = Header::sizeof() + MIN_RECORD_SIZE as usize + txs[0].description.len();
+        for cut in [
+            1,
+            buffer.len() - first_len - 2,
+            buffer.len() - first_len - 30,
+        ] {
+            let got = verify_bin(&mut &buffer[..buffer.len() - cut]);
+            assert!(
+                matches!(
+                    got,
+                    Err(error::ParseError::TruncatedRecord { recovered: 1 })
+                ),
+                "cut {}: {:?}",
+                cut,
+                got
+            );
+        }
+
+        let mut bad_magic = buffer.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            verify_bin(&mut bad_magic.as_slice()),
+            Err(error::ParseError::InvalidFormat(_))
+        ));
+
+        let mut bad_len = buffer.clone();
+        bad_len[Header::sizeof() + BinLayout::DESC_LEN.offset + 3] ^= 1;
+        assert!(matches!(
+            verify_bin(&mut bad_len.as_slice()),
+            Err(error::ParseError::InvalidFormat(msg)) if msg.ends_with("at byte 50")
+        ));
+    }
 }
@@ -1,18 +1,42 @@
-use std::io::{self, BufRead};
-
-use crate::types::{Transaction, TxStatus, TxType};
-use crate::{error, parser};
-
-const EXPECTED_HEADER: &[&str] = &[
-    "TX_ID",
-    "TX_TYPE",
-    "FROM_USER_ID",
-    "TO_USER_ID",
-    "AMOUNT",
-    "TIMESTAMP",
-    "STATUS",
-    "DESCRIPTION",
-];
+use std::io::{self, BufRead, Write};
+use std::num::IntErrorKind;
+
+use crate::parser::{self, DumpOptions};
+use crate::types::{FIELD_NAMES, Transaction, TxField, TxStatus, TxType};
+use crate::{error, utils};
+
+/// Параметры диалекта CSV.
+///
+/// По умолчанию используется стандартный диалект: разделитель `,` и кавычки `"`,
+/// пустые строки между записями допускаются, файл без записей не считается ошибкой.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Символ-разделитель полей.
+    pub delimiter: char,
+    /// Символ кавычек. Внутри поля в кавычках экранируется удвоением.
+    pub quote: char,
+    /// Строгий режим: файл, в котором после заголовка нет ни одной записи, считается ошибкой.
+    pub strict_trailing: bool,
+    /// Разрешить пустые строки между записями. Если выключено, пустая строка,
+    /// за которой следует ещё одна запись, считается ошибкой. Пустые строки в конце
+    /// файла допускаются всегда.
+    pub lenient_blank_only: bool,
+    /// Принимать `TX_TYPE` и `STATUS` только в верхнем регистре. По умолчанию
+    /// регистр не важен: `deposit` и `Deposit` читаются как `DEPOSIT`.
+    pub strict_case: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            strict_trailing: false,
+            lenient_blank_only: true,
+            strict_case: false,
+        }
+    }
+}
 
 /// Читает и парсит транзакции из формата CSV.
 ///
@@ -28,52 +52,289 @@ const EXPECTED_HEADER: &[&str] = &[
 /// * Формат данных некорректен.
 /// * Возникла ошибка ввода-вывода при чтении из `reader`.
 fn parse_from_csv(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
-    let mut lines = io::BufReader::new(reader).lines();
-    let header_types = parse_header(&mut lines)?;
-    if !header_is_valid(&header_types) {
-        return Err(error::ParseError::InvalidFormat(
-            "invalid header".to_string(),
-        ));
+    parse_from_csv_with(reader, &CsvOptions::default())
+}
+
+/// Читает и парсит транзакции из CSV с заданным диалектом.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+/// * `options` - Диалект и строгость разбора (см. [`CsvOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`], если:
+/// * Формат данных некорректен.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+/// * Нарушены ограничения [`CsvOptions::strict_trailing`] или [`CsvOptions::lenient_blank_only`].
+pub fn parse_from_csv_with(
+    reader: &mut impl io::Read,
+    options: &CsvOptions,
+) -> Result<Vec<Transaction>, error::ParseError> {
+    parse_csv(reader, options, 0, &mut parser::Progress::none())
+}
+
+/// Читает транзакции из CSV с диалектом по умолчанию, заранее выделяя память
+/// под `expected` транзакций.
+///
+/// Подсказка влияет только на начальную ёмкость результата: если записей окажется
+/// больше, вектор вырастет как обычно, если меньше — лишняя память останется
+/// незанятой. Для больших файлов с известным числом строк это избавляет от повторных
+/// перевыделений при росте вектора.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_csv_with`].
+pub fn parse_from_csv_sized(
+    reader: &mut impl io::Read,
+    expected: usize,
+) -> Result<Vec<Transaction>, error::ParseError> {
+    parse_csv(
+        reader,
+        &CsvOptions::default(),
+        expected,
+        &mut parser::Progress::none(),
+    )
+}
+
+/// Читает транзакции из CSV с диалектом по умолчанию и возвращает вместе с ними
+/// имена колонок заголовка в порядке их следования в файле.
+///
+/// Колонки, которых нет среди полей транзакции (например, `CHANNEL`), при разборе
+/// игнорируются, но попадают в список, так что по нему можно узнать, какие
+/// дополнительные колонки были во входных данных.
+///
+/// ```rust
+/// use ypbank_parser::parse_from_csv_meta;
+///
+/// let data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION,CHANNEL\n\
+///             1,DEPOSIT,0,501,100,1672531200000,SUCCESS,\"funding\",web\n";
+/// let (txs, columns) = parse_from_csv_meta(&mut data.as_bytes()).unwrap();
+/// assert_eq!(txs.len(), 1);
+/// assert_eq!(columns.last().map(String::as_str), Some("CHANNEL"));
+/// ```
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`] в тех же случаях, что и [`parse_from_csv_with`].
+pub fn parse_from_csv_meta(
+    reader: &mut impl io::Read,
+) -> Result<(Vec<Transaction>, Vec<String>), error::ParseError> {
+    parse_csv_meta(
+        reader,
+        &CsvOptions::default(),
+        0,
+        &mut parser::Progress::none(),
+    )
+}
+
+fn parse_csv(
+    reader: &mut impl io::Read,
+    options: &CsvOptions,
+    capacity: usize,
+    progress: &mut parser::Progress,
+) -> Result<Vec<Transaction>, error::ParseError> {
+    parse_csv_meta(reader, options, capacity, progress).map(|(txs, _)| txs)
+}
+
+fn parse_csv_meta(
+    reader: &mut impl io::Read,
+    options: &CsvOptions,
+    capacity: usize,
+    progress: &mut parser::Progress,
+) -> Result<(Vec<Transaction>, Vec<String>), error::ParseError> {
+    let mut lines = numbered_records(reader, options.quote);
+    let header_types = parse_header(&mut (&mut lines).map(|l| l.map(|(_, l)| l)), options)?;
+    let columns = ColumnMap::from_header(&header_types)?;
+    let txs = parse_transactions(&mut lines, options, &columns, capacity, progress)?;
+    Ok((txs, header_types))
+}
+
+// Записи CSV вместе с номером их первой строки в файле (с единицы).
+//
+// Обычно запись занимает одну строку, но поле в кавычках может содержать переводы строк
+// (RFC 4180): пока кавычка открыта, следующие строки дописываются к записи вместе со своим
+// переводом строки (`\n` или `\r\n` как в файле). Удвоенная кавычка внутри поля не меняет
+// чётность, поэтому открытость кавычки определяется по числу символов `quote`.
+//
+// Строки-комментарии (первый непробельный символ `#`) между записями пропускаются;
+// `#` внутри поля в кавычках, в том числе на строке-продолжении, комментарием не считается.
+fn numbered_records(
+    reader: impl io::Read,
+    quote: char,
+) -> impl Iterator<Item = io::Result<(usize, String)>> {
+    let mut reader = io::BufReader::new(reader);
+    let mut number = 0;
+    std::iter::from_fn(move || {
+        let mut start = number + 1;
+        let mut record = String::new();
+        let mut open = false;
+        loop {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+            if number == 0
+                && let Some(rest) = line.strip_prefix('\u{feff}')
+            {
+                line = rest.to_string();
+            }
+            number += 1;
+            if record.is_empty() && line.trim_start().starts_with('#') {
+                start = number + 1;
+                continue;
+            }
+            open ^= line.matches(quote).count() % 2 == 1;
+            record.push_str(&line);
+            if !open {
+                break;
+            }
+        }
+        if number < start {
+            return None;
+        }
+        let end = record
+            .strip_suffix("\r\n")
+            .or_else(|| record.strip_suffix('\n'))
+            .map_or(record.len(), str::len);
+        record.truncate(end);
+        Some(Ok((start, record)))
+    })
+}
+
+// Номер строки в файле (с единицы) и ошибка её разбора.
+type RowError = (usize, error::ParseError);
+
+/// Читает транзакции из CSV, пропуская строки, которые не удалось разобрать.
+///
+/// В отличие от строгого разбора, ошибка в отдельной строке не прерывает чтение: строка
+/// пропускается, а ошибка вместе с номером строки в файле (с единицы) добавляется в список.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+///
+/// # Ошибки
+///
+/// Возвращает [`error::ParseError`], если заголовок некорректен или возникла
+/// ошибка ввода-вывода при чтении из `reader`.
+pub fn parse_from_csv_lenient(
+    reader: &mut impl io::Read,
+) -> Result<(Vec<Transaction>, Vec<RowError>), error::ParseError> {
+    let options = CsvOptions::default();
+    let mut lines = numbered_records(reader, options.quote);
+    let header_types = parse_header(&mut (&mut lines).map(|l| l.map(|(_, l)| l)), &options)?;
+    let columns = ColumnMap::from_header(&header_types)?;
+
+    let mut result = Vec::new();
+    let mut errors = Vec::new();
+    for line in lines {
+        let (number, line) = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match parse_transaction(trimmed, &options, &columns) {
+            Ok(tx) => result.push(tx),
+            Err(err) => errors.push((number, err)),
+        }
     }
-    parse_transactions(&mut lines)
+    Ok((result, errors))
+}
+
+// Состояние разбора одного поля строки CSV.
+enum FieldState {
+    // Начало поля: пробелы пропускаются, кавычка открывает поле в кавычках.
+    Start,
+    // Поле без кавычек, читается до разделителя.
+    Unquoted,
+    // Внутри кавычек; удвоенная кавычка означает символ кавычки.
+    Quoted,
+    // После закрывающей кавычки допустимы только пробелы и разделитель.
+    Closed,
 }
 
-fn parse_csv_line(line: &str) -> Result<Vec<String>, error::ParseError> {
+// Разбирает строку CSV на поля по грамматике:
+//
+//   line     = field *(DELIMITER field)
+//   field    = *WSP (quoted / unquoted) *WSP
+//   quoted   = QUOTE *(любой символ кроме QUOTE / QUOTE QUOTE) QUOTE
+//   unquoted = *(любой символ кроме DELIMITER и QUOTE)
+//
+// Значение поля без кавычек обрезается по краям, значение в кавычках сохраняется как есть.
+// Любой символ кроме пробелов и разделителя после закрывающей кавычки, кавычка внутри
+// поля без кавычек и незакрытая кавычка считаются ошибкой.
+fn parse_csv_line(line: &str, options: &CsvOptions) -> Result<Vec<String>, error::ParseError> {
     let mut result = Vec::with_capacity(8);
     let mut current = String::new();
-    let mut in_quotes = false;
-    let mut chars = line.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        match c {
-            '"' => {
-                if in_quotes && chars.peek() == Some(&'"') {
-                    current.push('"');
-                    chars.next();
-                } else {
-                    in_quotes = !in_quotes;
-                }
-            }
-            ',' if !in_quotes => {
+    let mut state = FieldState::Start;
+    let mut chars = line.chars().enumerate().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        state = match state {
+            FieldState::Start | FieldState::Unquoted if c == options.delimiter => {
                 result.push(current.trim().to_string());
                 current.clear();
+                FieldState::Start
             }
-            _ => {
+            FieldState::Start if c == options.quote => FieldState::Quoted,
+            FieldState::Start if c.is_whitespace() => FieldState::Start,
+            FieldState::Start | FieldState::Unquoted if c == options.quote => {
+                return Err(error::ParseError::InvalidFormat(format!(
+                    "unexpected quote at column {} in CSV line",
+                    position + 1
+                )));
+            }
+            FieldState::Start | FieldState::Unquoted => {
                 current.push(c);
+                FieldState::Unquoted
             }
-        }
+            FieldState::Quoted if c == options.quote => {
+                if chars.next_if(|&(_, next)| next == options.quote).is_some() {
+                    current.push(options.quote);
+                    FieldState::Quoted
+                } else {
+                    FieldState::Closed
+                }
+            }
+            FieldState::Quoted => {
+                current.push(c);
+                FieldState::Quoted
+            }
+            FieldState::Closed if c == options.delimiter => {
+                result.push(std::mem::take(&mut current));
+                FieldState::Start
+            }
+            FieldState::Closed if c.is_whitespace() => FieldState::Closed,
+            FieldState::Closed => {
+                return Err(error::ParseError::InvalidFormat(format!(
+                    "unexpected character after closing quote at column {} in CSV line",
+                    position + 1
+                )));
+            }
+        };
     }
-    if in_quotes {
-        return Err(error::ParseError::InvalidFormat(
+    match state {
+        FieldState::Quoted => Err(error::ParseError::InvalidFormat(
             "unclosed quotes in CSV line".to_string(),
-        ));
+        )),
+        FieldState::Closed => {
+            result.push(current);
+            Ok(result)
+        }
+        FieldState::Start | FieldState::Unquoted => {
+            result.push(current.trim().to_string());
+            Ok(result)
+        }
     }
-    result.push(current.trim().to_string());
-    Ok(result)
 }
 
 fn parse_header<I: Iterator<Item = io::Result<String>>>(
     lines: &mut I,
+    options: &CsvOptions,
 ) -> Result<Vec<String>, error::ParseError> {
     for line in lines {
         let line = line?;
@@ -81,49 +342,152 @@ fn parse_header<I: Iterator<Item = io::Result<String>>>(
         if trimmed.is_empty() {
             continue;
         }
-        return parse_csv_line(trimmed);
+        return parse_csv_line(trimmed, options);
     }
     Err(error::ParseError::InvalidFormat(
         "invalid header".to_string(),
     ))
 }
 
-fn header_is_valid(header: &Vec<String>) -> bool {
-    EXPECTED_HEADER == header
+// Позиции обязательных колонок в строке, в порядке FIELD_NAMES.
+// Колонки в файле могут идти в любом порядке, неизвестные колонки игнорируются.
+struct ColumnMap {
+    indices: [usize; FIELD_NAMES.len()],
+    width: usize,
 }
 
-fn parse_transactions<I: Iterator<Item = io::Result<String>>>(
+impl ColumnMap {
+    fn from_header(header: &[String]) -> Result<Self, error::ParseError> {
+        let mut indices = [0; FIELD_NAMES.len()];
+        for (index, name) in FIELD_NAMES.iter().enumerate() {
+            let mut found = header.iter().enumerate().filter(|(_, h)| h == name);
+            let Some((position, _)) = found.next() else {
+                return Err(error::ParseError::InvalidFormat(format!(
+                    "invalid header: missing column {}",
+                    name
+                )));
+            };
+            if found.next().is_some() {
+                return Err(error::ParseError::InvalidFormat(format!(
+                    "invalid header: duplicate column {}",
+                    name
+                )));
+            }
+            indices[index] = position;
+        }
+        Ok(Self {
+            indices,
+            width: header.len(),
+        })
+    }
+
+    // Колонки в порядке FIELD_NAMES, без лишних.
+    fn standard() -> Self {
+        Self {
+            indices: std::array::from_fn(|index| index),
+            width: FIELD_NAMES.len(),
+        }
+    }
+
+    fn get<'a>(&self, values: &'a [String], column: usize) -> &'a str {
+        &values[self.indices[column]]
+    }
+}
+
+fn parse_transactions<I: Iterator<Item = io::Result<(usize, String)>>>(
     lines: &mut I,
+    options: &CsvOptions,
+    columns: &ColumnMap,
+    capacity: usize,
+    progress: &mut parser::Progress,
 ) -> Result<Vec<Transaction>, error::ParseError> {
-    let mut result = Vec::<Transaction>::new();
+    let mut result = Vec::<Transaction>::with_capacity(capacity);
+    let mut blank_line = None;
     for line in lines {
-        let line = line?;
+        let (number, line) = line?;
         let trimmed = line.trim();
         if trimmed.is_empty() {
+            blank_line = blank_line.or(Some(number));
             continue;
         }
-        result.push(parse_transaction(trimmed)?);
+        if let Some(blank) = blank_line.take()
+            && !options.lenient_blank_only
+            && !result.is_empty()
+        {
+            return Err(error::ParseError::InvalidFormat(format!(
+                "unexpected blank line {} between records",
+                blank
+            )));
+        }
+        let tx = parse_transaction(trimmed, options, columns).map_err(|err| match err {
+            error::ParseError::InvalidFormat(msg) => {
+                error::ParseError::InvalidFormat(format!("line {}: {}", number, msg))
+            }
+            err => err,
+        })?;
+        result.push(tx);
+        progress.tick();
+    }
+    if options.strict_trailing && result.is_empty() {
+        return Err(error::ParseError::InvalidFormat(
+            "no records after header".to_string(),
+        ));
     }
     Ok(result)
 }
 
-fn parse_transaction(tx: &str) -> Result<Transaction, error::ParseError> {
-    let values: Vec<String> = parse_csv_line(tx)?;
-    if values.len() != EXPECTED_HEADER.len() {
+// Разбирает одну строку данных с колонками в стандартном порядке, без заголовка.
+pub(crate) fn parse_csv_row(row: &str) -> Result<Transaction, error::ParseError> {
+    parse_transaction(row.trim(), &CsvOptions::default(), &ColumnMap::standard())
+}
+
+// Разбирает числовое поле; ошибка содержит имя поля и исходное значение,
+// например `AMOUNT: invalid digit in '-5'`.
+fn parse_number(
+    values: &[String],
+    columns: &ColumnMap,
+    index: usize,
+) -> Result<u64, error::ParseError> {
+    let value = columns.get(values, index);
+    value.parse::<u64>().map_err(|err| {
+        let reason = match err.kind() {
+            IntErrorKind::Empty => "empty value",
+            IntErrorKind::PosOverflow => "number too large",
+            _ => "invalid digit",
+        };
+        error::ParseError::InvalidFormat(format!(
+            "{}: {} in '{}'",
+            FIELD_NAMES[index], reason, value
+        ))
+    })
+}
+
+fn parse_transaction(
+    tx: &str,
+    options: &CsvOptions,
+    columns: &ColumnMap,
+) -> Result<Transaction, error::ParseError> {
+    let values: Vec<String> = parse_csv_line(tx, options)?;
+    if values.len() != columns.width {
         return Err(error::ParseError::InvalidFormat(format!(
             "invalid fields count: {}",
             values.len()
         )));
     }
 
-    let id = values[0].parse::<u64>()?;
-    let r#type = values[1].parse::<TxType>()?;
-    let from_user = values[2].parse::<u64>()?;
-    let to_user = values[3].parse::<u64>()?;
-    let amount = values[4].parse::<u64>()?;
-    let timestamp = values[5].parse::<u64>()?;
-    let status = values[6].parse::<TxStatus>()?;
-    let description = values[7].clone();
+    let id = parse_number(&values, columns, 0)?;
+    let r#type = TxType::parse_with_case(columns.get(&values, 1), options.strict_case)?;
+    let from_user = parse_number(&values, columns, 2)?;
+    let to_user = parse_number(&values, columns, 3)?;
+    let amount = parse_number(&values, columns, 4)?;
+    let timestamp = utils::parse_timestamp(columns.get(&values, 5)).map_err(|err| match err {
+        error::ParseError::InvalidFormat(msg) => {
+            error::ParseError::InvalidFormat(format!("{}: {}", FIELD_NAMES[5], msg))
+        }
+        err => err,
+    })?;
+    let status = TxStatus::parse_with_case(columns.get(&values, 6), options.strict_case)?;
+    let description = columns.get(&values, 7).to_string();
 
     Ok(Transaction {
         id,
@@ -145,6 +509,8 @@ fn parse_transaction(tx: &str) -> Result<Transaction, error::ParseError> {
 ///   Должен реализовывать трейт [`std::io::Write`].
 /// * `transactions` - Слайс транзакций для записи.
 ///
+/// Возвращает количество записанных транзакций.
+///
 /// # Ошибки
 ///
 /// Возвращает [`DumpError`], если:
@@ -152,40 +518,181 @@ fn parse_transaction(tx: &str) -> Result<Transaction, error::ParseError> {
 fn dump_as_csv(
     writer: &mut impl io::Write,
     transactions: &[Transaction],
-) -> Result<(), error::DumpError> {
-    write_title(writer)?;
+) -> Result<usize, error::DumpError> {
+    dump_as_csv_with(writer, transactions, &CsvOptions::default())
+}
+
+/// Сериализует список транзакций в CSV с заданным диалектом.
+///
+/// # Аргументы
+///
+/// * `writer` - Приемник данных, реализующий [`std::io::Write`].
+/// * `transactions` - Слайс транзакций для записи.
+/// * `options` - Разделитель полей и символ кавычек (см. [`CsvOptions`]).
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_csv_with(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    options: &CsvOptions,
+) -> Result<usize, error::DumpError> {
+    dump_as_csv_with_options(
+        writer,
+        transactions,
+        options,
+        &DumpOptions::default(),
+        &mut parser::Progress::none(),
+    )
+}
+
+pub(crate) fn dump_as_csv_with_options(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    options: &CsvOptions,
+    dump_options: &DumpOptions,
+    progress: &mut parser::Progress,
+) -> Result<usize, error::DumpError> {
+    let mut writer = io::BufWriter::new(writer);
+    utils::write_header_comment(&mut writer, dump_options)?;
+    write_title(&mut writer, options, dump_options)?;
     for tx in transactions {
-        write_tx(writer, tx)?;
+        write_tx(&mut writer, tx, options, dump_options)?;
+        progress.tick();
     }
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+fn write_title(
+    writer: &mut impl io::Write,
+    options: &CsvOptions,
+    dump_options: &DumpOptions,
+) -> Result<(), error::DumpError> {
+    let title = FIELD_NAMES.join(&options.delimiter.to_string());
+    write!(writer, "{}{}", title, dump_options.line_ending.as_str())?;
     Ok(())
 }
 
-fn write_title(writer: &mut impl io::Write) -> Result<(), error::DumpError> {
-    let title = EXPECTED_HEADER.join(",");
-    writeln!(writer, "{}", title)?;
+/// Сериализует транзакции в CSV только с указанными колонками и в указанном порядке.
+///
+/// Заголовок содержит имена колонок из `columns` (`TX_ID`, `AMOUNT`, ...), значения
+/// в строках записываются так же, как в [`dump_as_csv_with`] с параметрами по умолчанию.
+/// Чтобы результат читался обратно парсером CSV, в `columns` должны быть все поля:
+/// порядок при чтении не важен, а отсутствующие поля можно узнать через
+/// [`missing_csv_columns`].
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`error::DumpError`], если:
+/// * `columns` пуст или содержит повторяющиеся поля ([`error::DumpError::InvalidColumns`]).
+///   В этом случае во `writer` ничего не записывается.
+/// * Произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_csv_with_columns(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    columns: &[TxField],
+) -> Result<usize, error::DumpError> {
+    if columns.is_empty() {
+        return Err(error::DumpError::InvalidColumns(
+            "no columns given".to_string(),
+        ));
+    }
+    if let Some((index, field)) = columns
+        .iter()
+        .enumerate()
+        .find(|(index, field)| columns[..*index].contains(field))
+    {
+        return Err(error::DumpError::InvalidColumns(format!(
+            "duplicate column {} at position {}",
+            header_name(*field),
+            index + 1
+        )));
+    }
+
+    let options = CsvOptions::default();
+    let delimiter = options.delimiter.to_string();
+    let mut writer = io::BufWriter::new(writer);
+    let title: Vec<&str> = columns.iter().map(|field| header_name(*field)).collect();
+    writeln!(writer, "{}", title.join(&delimiter))?;
+    for tx in transactions {
+        let values = tx_values(tx, &options, &DumpOptions::default());
+        let row: Vec<&str> = columns
+            .iter()
+            .map(|field| values[column_index(*field)].as_str())
+            .collect();
+        writeln!(writer, "{}", row.join(&delimiter))?;
+    }
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+/// Поля, которых не хватает в `columns`, чтобы CSV, записанный
+/// [`dump_as_csv_with_columns`], можно было прочитать обратно. Парсер требует все поля,
+/// поэтому пустой результат означает полный набор колонок в любом порядке.
+pub fn missing_csv_columns(columns: &[TxField]) -> Vec<TxField> {
+    TxField::ALL
+        .into_iter()
+        .filter(|field| !columns.contains(field))
+        .collect()
+}
+
+// Позиция колонки поля в `FIELD_NAMES`: порядок совпадает с `TxField::ALL`.
+fn column_index(field: TxField) -> usize {
+    TxField::ALL
+        .iter()
+        .position(|f| *f == field)
+        .expect("TxField::ALL contains every field")
+}
+
+fn header_name(field: TxField) -> &'static str {
+    FIELD_NAMES[column_index(field)]
+}
+
+fn write_tx(
+    writer: &mut impl io::Write,
+    tx: &Transaction,
+    options: &CsvOptions,
+    dump_options: &DumpOptions,
+) -> Result<(), error::DumpError> {
+    let values = tx_values(tx, options, dump_options);
+    write!(
+        writer,
+        "{}{}",
+        values.join(&options.delimiter.to_string()),
+        dump_options.line_ending.as_str()
+    )?;
     Ok(())
 }
 
-fn write_tx(writer: &mut impl io::Write, tx: &Transaction) -> Result<(), error::DumpError> {
-    let values = [
+// Значения полей транзакции в порядке `FIELD_NAMES`, готовые к записи в строку CSV.
+fn tx_values(tx: &Transaction, options: &CsvOptions, dump_options: &DumpOptions) -> [String; 8] {
+    [
         tx.id.to_string(),
         tx.r#type.to_string(),
         tx.from_user.to_string(),
         tx.to_user.to_string(),
         tx.amount.to_string(),
-        tx.timestamp.to_string(),
+        utils::format_timestamp(tx.timestamp, dump_options),
         tx.status.to_string(),
-        format!("\"{}\"", make_escaped_string(&tx.description)),
-    ];
-    writeln!(writer, "{}", values.join(","))?;
-    Ok(())
+        format!(
+            "{q}{}{q}",
+            make_escaped_string(&tx.description, options.quote),
+            q = options.quote
+        ),
+    ]
 }
 
-fn make_escaped_string(input: &str) -> String {
+fn make_escaped_string(input: &str, quote: char) -> String {
     let mut escaped = String::new();
     for c in input.chars() {
-        if c == '"' {
-            escaped.push('"');
+        if c == quote {
+            escaped.push(quote);
         }
         escaped.push(c);
     }
@@ -202,14 +709,36 @@ impl parser::Parser for CsvParser {
     fn dump(
         writer: &mut impl io::Write,
         transactions: &[Transaction],
-    ) -> Result<(), error::DumpError> {
+    ) -> Result<usize, error::DumpError> {
         dump_as_csv(writer, transactions)
     }
+
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut parser::Progress,
+    ) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_csv(reader, &CsvOptions::default(), 0, progress)
+    }
+
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+        progress: &mut parser::Progress,
+    ) -> Result<usize, error::DumpError> {
+        dump_as_csv_with_options(
+            writer,
+            transactions,
+            &CsvOptions::default(),
+            &DumpOptions::default(),
+            progress,
+        )
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::types::fee_and_reversal;
 
     #[test]
     fn test_parse() {
@@ -295,11 +824,96 @@ mod test {
         assert!(got.is_err());
     }
 
+    #[test]
+    fn test_strict_case() {
+        let data = format!(
+            "{}\n1,deposit,0,501,100,1672531200000,Success,\"x\"\n",
+            FIELD_NAMES.join(",")
+        );
+        let txs = parse_from_csv_with(&mut data.as_bytes(), &CsvOptions::default()).unwrap();
+        assert_eq!(txs[0].r#type, TxType::Deposit);
+        assert_eq!(txs[0].status, TxStatus::Success);
+
+        let strict = CsvOptions {
+            strict_case: true,
+            ..CsvOptions::default()
+        };
+        assert!(parse_from_csv_with(&mut data.as_bytes(), &strict).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_line_quote_edge_cases() {
+        let options = CsvOptions::default();
+
+        assert!(matches!(
+            parse_csv_line(r#""a"b,c"#, &options),
+            Err(error::ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            parse_csv_line(r#""a"""#, &options),
+            Err(error::ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            parse_csv_line(r#"""#, &options),
+            Err(error::ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            parse_csv_line(r#"a"b,c"#, &options),
+            Err(error::ParseError::InvalidFormat(_))
+        ));
+        assert_eq!(parse_csv_line(r#""",x"#, &options).unwrap(), ["", "x"]);
+        assert_eq!(
+            parse_csv_line(r#" "a""b" , " c ",d "#, &options).unwrap(),
+            [r#"a"b"#, " c ", "d"]
+        );
+    }
+
+    #[test]
+    fn test_numeric_errors_name_field() {
+        let header = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION";
+        let cases = [
+            (
+                r#"1001,DEPOSIT,0,501,-5,1672531200000,SUCCESS,"x""#,
+                "AMOUNT: invalid digit in '-5'",
+            ),
+            (
+                r#"1001,DEPOSIT,0,501,10.50,1672531200000,SUCCESS,"x""#,
+                "AMOUNT: invalid digit in '10.50'",
+            ),
+            (
+                r#"1001,DEPOSIT,,501,10,1672531200000,SUCCESS,"x""#,
+                "FROM_USER_ID: empty value in ''",
+            ),
+            (
+                r#"99999999999999999999,DEPOSIT,0,501,10,1672531200000,SUCCESS,"x""#,
+                "TX_ID: number too large in '99999999999999999999'",
+            ),
+        ];
+        for (row, expected) in cases {
+            let input = format!("{}\n{}\n", header, row);
+            let got = parse_from_csv(&mut input.as_bytes());
+            assert!(
+                matches!(&got, Err(error::ParseError::InvalidFormat(msg)) if *msg == format!("line 2: {}", expected)),
+                "{:?}",
+                got
+            );
+        }
+
+        let input = format!(
+            "{}\n{}\n",
+            header, r#"1001,DEPOSIT,0,501,10,-1,SUCCESS,"x""#
+        );
+        assert!(matches!(
+            parse_from_csv(&mut input.as_bytes()),
+            Err(error::ParseError::InvalidFormat(msg)) if msg.starts_with("line 2: TIMESTAMP: ")
+        ));
+    }
+
     #[test]
     fn test_escaped_string() {
         let input = r##"String with "quotes" and , commas"##;
         let expected = r##"String with ""quotes"" and , commas"##.to_string();
-        let got = make_escaped_string(input);
+        let got = make_escaped_string(input, '"');
         assert_eq!(got, expected);
     }
 
@@ -353,4 +967,414 @@ mod test {
             "1002,DEPOSIT,1001,1001,1001,1633036800000,SUCCESS,\"Description with, comma and \"\"quotes\"\"\"",
         );
     }
+
+    #[test]
+    fn test_fee_and_reversal_roundtrip() {
+        let txs = fee_and_reversal();
+        let mut buffer = Vec::new();
+
+        dump_as_csv(&mut buffer, &txs).unwrap();
+        let got = parse_from_csv(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_semicolon_single_quote_roundtrip() {
+        let options = CsvOptions {
+            delimiter: ';',
+            quote: '\'',
+            ..CsvOptions::default()
+        };
+        let txs = vec![Transaction {
+            id: 1001,
+            r#type: TxType::Transfer,
+            from_user: 501,
+            to_user: 502,
+            amount: 15000,
+            timestamp: 1672534800000,
+            status: TxStatus::Success,
+            description: "Partner's payment; invoice #1, \"urgent\"".to_string(),
+        }];
+
+        let mut buffer = Vec::new();
+        dump_as_csv_with(&mut buffer, &txs, &options).unwrap();
+
+        let result_string = String::from_utf8(buffer.clone()).unwrap();
+        let lines: Vec<&str> = result_string.lines().collect();
+        assert_eq!(
+            lines[0],
+            "TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;AMOUNT;TIMESTAMP;STATUS;DESCRIPTION"
+        );
+        assert_eq!(
+            lines[1],
+            "1001;TRANSFER;501;502;15000;1672534800000;SUCCESS;'Partner''s payment; invoice #1, \"urgent\"'"
+        );
+
+        let got = parse_from_csv_with(&mut buffer.as_slice(), &options).unwrap();
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_parse_shuffled_columns() {
+        let input = r##"
+        DESCRIPTION,STATUS,AMOUNT,TX_ID,TIMESTAMP,TO_USER_ID,FROM_USER_ID,TX_TYPE
+        "simple string",FAILURE,50000,1002,1672531200000,501,0,WITHDRAWAL
+        "##;
+
+        let got = parse_from_csv(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(
+            got,
+            vec![Transaction {
+                id: 1002,
+                r#type: TxType::Withdrawal,
+                from_user: 0,
+                to_user: 501,
+                amount: 50000,
+                timestamp: 1672531200000,
+                status: TxStatus::Failure,
+                description: "simple string".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_dump_with_columns() {
+        let txs = vec![
+            crate::types::TransactionBuilder::new()
+                .id(1001)
+                .to_user(501)
+                .amount(50000)
+                .timestamp(1672531200000)
+                .status(TxStatus::Success)
+                .description("Initial, \"funding\"")
+                .build(),
+            crate::types::TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Fee)
+                .amount(5)
+                .status(TxStatus::Failure)
+                .build(),
+        ];
+
+        // полный набор в другом порядке читается обратно
+        let reordered = [
+            TxField::Description,
+            TxField::Amount,
+            TxField::Id,
+            TxField::Status,
+            TxField::Timestamp,
+            TxField::Type,
+            TxField::ToUser,
+            TxField::FromUser,
+        ];
+        let mut buffer = Vec::new();
+        assert_eq!(
+            dump_as_csv_with_columns(&mut buffer, &txs, &reordered).unwrap(),
+            2
+        );
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output.lines().next().unwrap(),
+            "DESCRIPTION,AMOUNT,TX_ID,STATUS,TIMESTAMP,TX_TYPE,TO_USER_ID,FROM_USER_ID"
+        );
+        assert_eq!(
+            output.lines().nth(1).unwrap(),
+            "\"Initial, \"\"funding\"\"\",50000,1001,SUCCESS,1672531200000,DEPOSIT,501,0"
+        );
+        assert!(missing_csv_columns(&reordered).is_empty());
+        assert_eq!(parse_from_csv(&mut output.as_bytes()).unwrap(), txs);
+
+        // подмножество колонок
+        let subset = [TxField::Id, TxField::Amount, TxField::Status];
+        let mut buffer = Vec::new();
+        dump_as_csv_with_columns(&mut buffer, &txs, &subset).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TX_ID,AMOUNT,STATUS\n1001,50000,SUCCESS\n1002,5,FAILURE\n"
+        );
+        assert_eq!(
+            missing_csv_columns(&subset),
+            [
+                TxField::Type,
+                TxField::FromUser,
+                TxField::ToUser,
+                TxField::Timestamp,
+                TxField::Description
+            ]
+        );
+
+        let mut buffer = Vec::new();
+        assert!(matches!(
+            dump_as_csv_with_columns(&mut buffer, &txs, &[TxField::Id, TxField::Amount, TxField::Id]),
+            Err(error::DumpError::InvalidColumns(msg)) if msg == "duplicate column TX_ID at position 3"
+        ));
+        assert!(matches!(
+            dump_as_csv_with_columns(&mut buffer, &txs, &[]),
+            Err(error::DumpError::InvalidColumns(_))
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ignores_extra_column() {
+        let input = r##"
+        TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION,BRANCH
+        1002,WITHDRAWAL,0,501,50000,1672531200000,FAILURE,"simple string",Moscow
+        "##;
+
+        let got = parse_from_csv(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].id, 1002);
+        assert_eq!(got[0].description, "simple string");
+    }
+
+    #[test]
+    fn test_parse_meta_reports_columns() {
+        let input = r##"
+        TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,CHANNEL,TIMESTAMP,STATUS,DESCRIPTION
+        1002,WITHDRAWAL,0,501,50000,mobile,1672531200000,FAILURE,"simple string"
+        "##;
+
+        let (txs, columns) = parse_from_csv_meta(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(txs, parse_from_csv(&mut input.as_bytes()).unwrap());
+        assert_eq!(txs[0].amount, 50000);
+        assert_eq!(txs[0].timestamp, 1672531200000);
+        assert_eq!(columns.len(), FIELD_NAMES.len() + 1);
+        assert_eq!(columns[5], "CHANNEL");
+        assert!(
+            FIELD_NAMES
+                .iter()
+                .all(|name| columns.iter().any(|c| c == name))
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_column() {
+        let input = r##"
+        TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,TIMESTAMP,STATUS,DESCRIPTION
+        1002,WITHDRAWAL,0,501,1672531200000,FAILURE,"simple string"
+        "##;
+
+        let got = parse_from_csv(&mut input.as_bytes());
+
+        assert!(
+            matches!(got, Err(error::ParseError::InvalidFormat(msg)) if msg.contains("AMOUNT"))
+        );
+    }
+
+    #[test]
+    fn test_numeric_timestamp_roundtrip() {
+        let tx = crate::types::TransactionBuilder::new()
+            .timestamp(1672531200123)
+            .build();
+        let mut buffer = Vec::new();
+
+        dump_as_csv(&mut buffer, std::slice::from_ref(&tx)).unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer).contains(",1672531200123,"));
+        assert_eq!(parse_from_csv(&mut buffer.as_slice()).unwrap(), vec![tx]);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_iso_timestamp_roundtrip() {
+        let tx = crate::types::TransactionBuilder::new()
+            .timestamp(1672531200123)
+            .build();
+        let options = DumpOptions {
+            human_timestamps: true,
+            ..DumpOptions::default()
+        };
+        let mut buffer = Vec::new();
+
+        dump_as_csv_with_options(
+            &mut buffer,
+            std::slice::from_ref(&tx),
+            &CsvOptions::default(),
+            &options,
+            &mut parser::Progress::none(),
+        )
+        .unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer).contains(",2023-01-01T00:00:00.123Z,"));
+        assert_eq!(parse_from_csv(&mut buffer.as_slice()).unwrap(), vec![tx]);
+    }
+
+    #[test]
+    fn test_bom_and_crlf() {
+        let clean = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,\"Initial funding\"\n\
+                     1002,TRANSFER,501,502,15000,1672534800000,FAILURE,\"Invoice, #123\"\n";
+        let windows = format!("\u{feff}{}", clean.replace('\n', "\r\n"));
+
+        let expected = parse_from_csv(&mut clean.as_bytes()).unwrap();
+        let got = parse_from_csv(&mut windows.as_bytes()).unwrap();
+
+        assert_eq!(expected.len(), 2);
+        assert_eq!(got, expected);
+        assert_eq!(got[1].description, "Invoice, #123");
+    }
+
+    #[test]
+    fn test_parse_sized() {
+        let data = format!(
+            "{}\n1,DEPOSIT,0,501,100,1,SUCCESS,\"a\"\n2,FEE,501,0,5,2,PENDING,\"b\"\n\
+             3,WITHDRAWAL,501,0,50,3,FAILURE,\"c\"\n",
+            FIELD_NAMES.join(",")
+        );
+        let expected = parse_from_csv(&mut data.as_bytes()).unwrap();
+
+        for hint in [0, 1, 3, 100] {
+            let got = parse_from_csv_sized(&mut data.as_bytes(), hint).unwrap();
+            assert_eq!(got, expected, "hint {}", hint);
+            assert!(got.capacity() >= hint);
+        }
+    }
+
+    #[test]
+    fn test_multiline_quoted_description() {
+        let txs = vec![
+            crate::types::TransactionBuilder::new()
+                .id(1)
+                .description("first line\nsecond, \"quoted\"\n")
+                .build(),
+            crate::types::TransactionBuilder::new()
+                .id(2)
+                .description("windows\r\nline")
+                .build(),
+            crate::types::TransactionBuilder::new()
+                .id(3)
+                .description("plain")
+                .build(),
+        ];
+        let mut buffer = Vec::new();
+
+        dump_as_csv(&mut buffer, &txs).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(",\"first line\nsecond, \"\"quoted\"\"\n\"\n2,"));
+        assert_eq!(parse_from_csv(&mut output.as_bytes()).unwrap(), txs);
+
+        // номер строки в ошибке — физическая строка файла, где начинается запись
+        let broken = output.replace("3,DEPOSIT,0", "3,DEPOSIT,x");
+        let (parsed, errors) = parse_from_csv_lenient(&mut broken.as_bytes()).unwrap();
+        assert_eq!(parsed, txs[..2]);
+        assert_eq!(errors[0].0, 7);
+
+        let unclosed = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,0,0,0,PENDING,\"open\nstill open\n";
+        assert!(parse_from_csv(&mut unclosed.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_comment_lines() {
+        let input = "# exported \"today\n\
+                     \x20  # second comment\n\
+                     TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     1,DEPOSIT,0,501,100,1,SUCCESS,\"# not a comment\"\n\
+                     # between records\n\
+                     2,DEPOSIT,0,501,100,1,SUCCESS,\"multi\n# still the description\"\n\
+                     3,DEPOSIT,0,x,100,1,SUCCESS,\"\"\n";
+
+        let (txs, errors) = parse_from_csv_lenient(&mut input.as_bytes()).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].description, "# not a comment");
+        assert_eq!(txs[1].description, "multi\n# still the description");
+        // комментарии учитываются в нумерации строк
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 8);
+
+        let options = DumpOptions {
+            header_comment: Some("YPBank export\n\nv1".to_string()),
+            ..DumpOptions::default()
+        };
+        let mut buffer = Vec::new();
+        dump_as_csv_with_options(
+            &mut buffer,
+            &txs,
+            &CsvOptions::default(),
+            &options,
+            &mut parser::Progress::none(),
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("# YPBank export\n#\n# v1\nTX_ID,"));
+        assert_eq!(parse_from_csv(&mut output.as_bytes()).unwrap(), txs);
+    }
+
+    #[test]
+    fn test_parse_lenient_skips_bad_row() {
+        let input = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,\"ok\"\n\
+                     1002,TRANSFER,501,502,lots,1672534800000,FAILURE,\"bad amount\"\n\
+                     1003,WITHDRAWAL,502,0,1000,1672538400000,PENDING,\"ok\"\n";
+
+        let (txs, errors) = parse_from_csv_lenient(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(
+            txs.iter().map(|tx| tx.id).collect::<Vec<_>>(),
+            vec![1001, 1003]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 3);
+        assert!(matches!(errors[0].1, error::ParseError::InvalidFormat(_)));
+        assert!(parse_from_csv(&mut input.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_strictness_toggles() {
+        let header = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n";
+        let row = |id: u64| format!("{},DEPOSIT,0,501,100,1672531200000,SUCCESS,\"x\"\n", id);
+        let interior_blank = format!("{}{}\n{}\n", header, row(1), row(2));
+        let empty = format!("{}\n", header);
+
+        for strict_trailing in [false, true] {
+            for lenient_blank_only in [false, true] {
+                let options = CsvOptions {
+                    strict_trailing,
+                    lenient_blank_only,
+                    ..CsvOptions::default()
+                };
+                let parse = |input: &str| parse_from_csv_with(&mut input.as_bytes(), &options);
+
+                // пустая строка в конце файла допустима в любом режиме
+                assert_eq!(
+                    parse(&format!("{}{}\n\n", header, row(1))).unwrap().len(),
+                    1
+                );
+
+                let got = parse(&interior_blank);
+                if lenient_blank_only {
+                    assert_eq!(got.unwrap().len(), 2);
+                } else {
+                    assert!(
+                        matches!(got, Err(error::ParseError::InvalidFormat(msg)) if msg.contains("line 3"))
+                    );
+                }
+
+                // строка, которую не удалось разобрать, — ошибка с номером строки
+                let got = parse(&format!(
+                    "{}{}{}",
+                    header,
+                    row(1),
+                    row(2).replace("100", "x")
+                ));
+                assert!(
+                    matches!(got, Err(error::ParseError::InvalidFormat(ref msg)) if msg.starts_with("line 3: AMOUNT")),
+                    "{:?}",
+                    got
+                );
+
+                let got = parse(&empty);
+                if strict_trailing {
+                    assert!(matches!(got, Err(error::ParseError::InvalidFormat(_))));
+                } else {
+                    assert!(got.unwrap().is_empty());
+                }
+            }
+        }
+    }
 }
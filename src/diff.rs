@@ -0,0 +1,261 @@
+//! Модуль сравнения наборов транзакций.
+//!
+//! Сравнение позиционное: транзакции двух наборов сопоставляются по индексу,
+//! а все несовпавшие позиции возвращаются в виде списка [`Difference`].
+
+use crate::types::Transaction;
+
+/// Различие между двумя наборами транзакций на одной позиции.
+#[derive(Debug, PartialEq)]
+pub enum Difference<'a> {
+    /// Транзакции есть в обоих наборах, но не равны.
+    Changed {
+        /// Позиция транзакции в наборах (с нуля).
+        index: usize,
+        /// Транзакция из левого набора.
+        lhs: &'a Transaction,
+        /// Транзакция из правого набора.
+        rhs: &'a Transaction,
+    },
+    /// Транзакция есть только в левом наборе.
+    Removed {
+        /// Позиция транзакции в левом наборе (с нуля).
+        index: usize,
+        /// Транзакция из левого набора.
+        lhs: &'a Transaction,
+    },
+    /// Транзакция есть только в правом наборе.
+    Added {
+        /// Позиция транзакции в правом наборе (с нуля).
+        index: usize,
+        /// Транзакция из правого набора.
+        rhs: &'a Transaction,
+    },
+}
+
+impl<'a> Difference<'a> {
+    /// Позиция, на которой обнаружено различие.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Changed { index, .. }
+            | Self::Removed { index, .. }
+            | Self::Added { index, .. } => *index,
+        }
+    }
+
+    /// Транзакция из левого набора, если она есть на этой позиции.
+    pub fn lhs(&self) -> Option<&'a Transaction> {
+        match self {
+            Self::Changed { lhs, .. } | Self::Removed { lhs, .. } => Some(lhs),
+            Self::Added { .. } => None,
+        }
+    }
+
+    /// Транзакция из правого набора, если она есть на этой позиции.
+    pub fn rhs(&self) -> Option<&'a Transaction> {
+        match self {
+            Self::Changed { rhs, .. } | Self::Added { rhs, .. } => Some(rhs),
+            Self::Removed { .. } => None,
+        }
+    }
+}
+
+/// Сравнивает два набора транзакций позиционно и возвращает все различия
+/// в порядке возрастания позиции.
+///
+/// Пустой результат означает, что наборы идентичны.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::diff::{diff, Difference};
+/// use ypbank_parser::types::{Transaction, TxStatus, TxType};
+///
+/// let tx = Transaction{id: 1, r#type: TxType::Deposit,
+///                      from_user: 0, to_user: 1,
+///                      amount: 100, timestamp: 1633036800000,
+///                      status: TxStatus::Success,
+///                      description: "Description".to_string()};
+/// let lhs = vec![tx.clone()];
+/// let rhs = vec![tx.clone(), tx.clone()];
+///
+/// let diffs = diff(&lhs, &rhs);
+/// assert_eq!(diffs, vec![Difference::Added { index: 1, rhs: &rhs[1] }]);
+/// ```
+pub fn diff<'a>(lhs: &'a [Transaction], rhs: &'a [Transaction]) -> Vec<Difference<'a>> {
+    diff_by(lhs, rhs, |l, r| l == r)
+}
+
+/// Сравнивает два набора транзакций позиционно, используя `eq` для проверки
+/// равенства транзакций на одной позиции.
+///
+/// Позволяет, например, не учитывать отдельные поля при сравнении.
+pub fn diff_by<'a>(
+    lhs: &'a [Transaction],
+    rhs: &'a [Transaction],
+    eq: impl Fn(&Transaction, &Transaction) -> bool,
+) -> Vec<Difference<'a>> {
+    let mut result = Vec::new();
+    for index in 0..std::cmp::max(lhs.len(), rhs.len()) {
+        match (lhs.get(index), rhs.get(index)) {
+            (Some(l), Some(r)) if !eq(l, r) => result.push(Difference::Changed {
+                index,
+                lhs: l,
+                rhs: r,
+            }),
+            (Some(l), None) => result.push(Difference::Removed { index, lhs: l }),
+            (None, Some(r)) => result.push(Difference::Added { index, rhs: r }),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Изменение суммы транзакции, отличающейся только полем `amount`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountDelta {
+    /// Позиция транзакции в наборах (с нуля).
+    pub index: usize,
+    /// Идентификатор транзакции.
+    pub id: u64,
+    /// Сумма в левом (старом) наборе.
+    pub old: u64,
+    /// Сумма в правом (новом) наборе.
+    pub new: u64,
+    /// Разница `new - old`; положительна, если сумма выросла.
+    pub delta: i128,
+}
+
+/// Отбирает из списка различий изменённые транзакции, у которых отличается только сумма,
+/// и возвращает для них изменение суммы.
+///
+/// Различия других видов (добавленные, удалённые записи и изменения прочих полей)
+/// пропускаются.
+pub fn amount_deltas(diffs: &[Difference]) -> Vec<AmountDelta> {
+    diffs
+        .iter()
+        .filter_map(|d| match d {
+            Difference::Changed { index, lhs, rhs } if only_amount_differs(lhs, rhs) => {
+                Some(AmountDelta {
+                    index: *index,
+                    id: rhs.id,
+                    old: lhs.amount,
+                    new: rhs.amount,
+                    delta: i128::from(rhs.amount) - i128::from(lhs.amount),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Суммарное изменение сумм по всем записям (см. [`amount_deltas`]).
+pub fn total_amount_drift(deltas: &[AmountDelta]) -> i128 {
+    deltas.iter().map(|d| d.delta).sum()
+}
+
+fn only_amount_differs(lhs: &Transaction, rhs: &Transaction) -> bool {
+    lhs.amount != rhs.amount
+        && lhs.id == rhs.id
+        && lhs.r#type == rhs.r#type
+        && lhs.from_user == rhs.from_user
+        && lhs.to_user == rhs.to_user
+        && lhs.timestamp == rhs.timestamp
+        && lhs.status == rhs.status
+        && lhs.description == rhs.description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TxStatus, TxType};
+
+    fn tx(id: u64, amount: u64) -> Transaction {
+        Transaction {
+            id,
+            r#type: TxType::Deposit,
+            from_user: 0,
+            to_user: 501,
+            amount,
+            timestamp: 1672531200000,
+            status: TxStatus::Success,
+            description: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_identical() {
+        let lhs = vec![tx(1, 100), tx(2, 200)];
+        let rhs = lhs.clone();
+
+        assert!(diff(&lhs, &rhs).is_empty());
+    }
+
+    #[test]
+    fn test_all_kinds() {
+        let lhs = vec![tx(1, 100), tx(2, 200), tx(3, 300)];
+        let rhs = vec![tx(1, 100), tx(2, 250)];
+
+        let got = diff(&lhs, &rhs);
+
+        assert_eq!(
+            got,
+            vec![
+                Difference::Changed {
+                    index: 1,
+                    lhs: &lhs[1],
+                    rhs: &rhs[1]
+                },
+                Difference::Removed {
+                    index: 2,
+                    lhs: &lhs[2]
+                },
+            ]
+        );
+        assert_eq!(got[0].rhs(), Some(&rhs[1]));
+        assert_eq!(got[1].rhs(), None);
+    }
+
+    #[test]
+    fn test_diff_by_custom_eq() {
+        let lhs = vec![tx(1, 100), tx(2, 200)];
+        let rhs = vec![tx(1, 150), tx(3, 200)];
+
+        let got = diff_by(&lhs, &rhs, |l, r| l.id == r.id);
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].index(), 1);
+    }
+
+    #[test]
+    fn test_amount_deltas() {
+        let lhs = vec![tx(1, 500), tx(2, 200), tx(3, 300)];
+        let mut rhs = vec![tx(1, 600), tx(2, 150), tx(3, 300)];
+        rhs[2].description = "changed".to_string();
+        rhs[2].amount = 1;
+
+        let diffs = diff(&lhs, &rhs);
+        let deltas = amount_deltas(&diffs);
+
+        assert_eq!(
+            deltas,
+            vec![
+                AmountDelta {
+                    index: 0,
+                    id: 1,
+                    old: 500,
+                    new: 600,
+                    delta: 100
+                },
+                AmountDelta {
+                    index: 1,
+                    id: 2,
+                    old: 200,
+                    new: 150,
+                    delta: -50
+                },
+            ]
+        );
+        assert_eq!(total_amount_drift(&deltas), 50);
+    }
+}
@@ -22,8 +22,85 @@ pub enum ParseError {
     /// - Несовпадение сигнатуры в начале записи в BIN формате.
     /// - Дублирующиеся поля или неизвестные значения перечислений в Text формате.
     InvalidFormat(String),
+    /// Контрольная сумма записи в BIN формате не совпала с вычисленной по её телу.
+    ///
+    /// Возникает только при чтении с включённой проверкой CRC32
+    /// (см. [`crate::BinOptions::checksum`]).
+    ChecksumMismatch {
+        /// Значение CRC32, записанное в файле.
+        expected: u32,
+        /// Значение CRC32, вычисленное по прочитанному телу записи.
+        actual: u32,
+    },
+    /// Поток BIN формата оборвался посреди записи.
+    ///
+    /// Конец потока ровно на границе записи ошибкой не считается.
+    TruncatedRecord {
+        /// Количество записей, успешно прочитанных до обрыва.
+        recovered: usize,
+    },
+    /// Заголовок записи BIN формата объявляет размер больше допустимого
+    /// (см. [`crate::BinOptions::max_record_size`]).
+    RecordTooLarge {
+        /// Объявленный в заголовке размер записи.
+        size: u32,
+    },
+    /// В наборе транзакций встретились две записи с одинаковым идентификатором
+    /// (см. [`crate::validation::check_unique_ids`]).
+    DuplicateId {
+        /// Идентификатор, встретившийся повторно.
+        id: u64,
+    },
+    /// Временная метка равна нулю или превышает допустимую верхнюю границу
+    /// (см. [`crate::validation::check_timestamps`]).
+    ImplausibleTimestamp {
+        /// Значение временной метки в миллисекундах.
+        value: u64,
+    },
+}
+
+/// Ошибка в отдельном блоке текстового формата, обнаруженная в режиме сбора ошибок
+/// (см. [`crate::parse_from_text_collect`]).
+///
+/// Блок с ошибкой пропускается, разбор продолжается со следующего блока.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBlockError {
+    /// Порядковый номер блока в файле (с нуля), пустые строки блоками не считаются.
+    pub block: usize,
+    /// Имя поля, вызвавшего ошибку; `None`, если строку не удалось разделить на имя и значение.
+    pub field: Option<String>,
+    /// Значение поля (или вся строка, если её не удалось разделить).
+    pub value: String,
+    /// Причина ошибки.
+    pub reason: String,
+}
+
+/// Строка не является именем известного формата файла
+/// (см. [`crate::types::SupportedFileFormat`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownFormatError {
+    /// Строка, которую не удалось распознать.
+    pub name: String,
+}
+
+impl std::fmt::Display for UnknownFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Список строится по включённым фичам, чтобы не предлагать отключённые форматы.
+        let names: Vec<String> = crate::types::SupportedFileFormat::ALL
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        write!(
+            f,
+            "unknown file format '{}', expected one of: {}",
+            self.name,
+            names.join(", ")
+        )
+    }
 }
 
+impl std::error::Error for UnknownFormatError {}
+
 impl From<std::io::Error> for ParseError {
     fn from(value: std::io::Error) -> Self {
         ParseError::IOError(value.to_string())
@@ -45,12 +122,319 @@ pub enum DumpError {
     /// Возникает, если структура данных находится в несогласованном состоянии
     /// (например, отсутствуют обязательные поля при формировании текстового вывода).
     InternalError,
-    /// Ошибка ввода-вывода при записи в целевой поток (например, ошибка записи в файл).
+    /// Ошибка записи, не связанная с потоком ввода-вывода (например, ошибка базы SQLite).
+    /// Ошибки самого `writer` передаются вариантом [`DumpError::Io`].
     OutputError,
+    /// Ошибка ввода-вывода при записи в целевой поток.
+    ///
+    /// `kind` позволяет отличить, например, закрытый канал ([`std::io::ErrorKind::BrokenPipe`])
+    /// от переполнения диска ([`std::io::ErrorKind::StorageFull`]).
+    Io {
+        /// Вид исходной ошибки [`std::io::Error`].
+        kind: std::io::ErrorKind,
+        /// Текст исходной ошибки.
+        message: String,
+    },
+    /// Описание транзакции слишком длинное для BIN формата: размер записи
+    /// (`RECORD_SIZE`) не помещается в 32 бита.
+    DescriptionTooLong {
+        /// Длина описания в байтах.
+        len: usize,
+    },
+    /// Некорректный список колонок для записи CSV: пустой или с повторяющимися полями
+    /// (см. [`crate::dump_as_csv_with_columns`]).
+    InvalidColumns(String),
+    /// Описание содержит символ, который нельзя записать в XML 1.0 даже через
+    /// ссылку на символ (например, U+0001).
+    InvalidXmlChar {
+        /// Недопустимый символ.
+        ch: char,
+    },
 }
 
 impl From<std::io::Error> for DumpError {
-    fn from(_: std::io::Error) -> Self {
-        DumpError::OutputError
+    fn from(err: std::io::Error) -> Self {
+        DumpError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Ошибки конвертации между форматами (см. [`crate::convert`]).
+///
+/// Объединяет ошибки чтения исходного потока и записи в целевой.
+#[derive(Debug)]
+pub enum ConvertError {
+    /// Ошибка при чтении исходных данных.
+    Parse(ParseError),
+    /// Ошибка при записи результата.
+    Dump(DumpError),
+}
+
+impl From<ParseError> for ConvertError {
+    fn from(err: ParseError) -> Self {
+        ConvertError::Parse(err)
+    }
+}
+
+impl From<DumpError> for ConvertError {
+    fn from(err: DumpError) -> Self {
+        ConvertError::Dump(err)
+    }
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::Parse(err) => write!(f, "parse error: {}", err),
+            ConvertError::Dump(err) => write!(f, "dump error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvertError::Parse(err) => Some(err),
+            ConvertError::Dump(err) => Some(err),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::IOError(msg) => write!(f, "IO error: {}", msg),
+            ParseError::InvalidFormat(msg) => write!(f, "{}", msg),
+            ParseError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            ),
+            ParseError::TruncatedRecord { recovered } => {
+                write!(f, "truncated record after {} complete records", recovered)
+            }
+            ParseError::RecordTooLarge { size } => write!(f, "record too large: {} bytes", size),
+            ParseError::DuplicateId { id } => write!(f, "duplicate transaction id: {}", id),
+            ParseError::ImplausibleTimestamp { value } => {
+                write!(f, "implausible timestamp: {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl std::fmt::Display for DumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DumpError::InternalError => write!(f, "internal dump error"),
+            DumpError::OutputError => write!(f, "dump error"),
+            DumpError::Io { message, .. } => write!(f, "IO error: {}", message),
+            DumpError::DescriptionTooLong { len } => {
+                write!(f, "description too long: {} bytes", len)
+            }
+            DumpError::InvalidColumns(msg) => write!(f, "invalid columns: {}", msg),
+            DumpError::InvalidXmlChar { ch } => {
+                write!(
+                    f,
+                    "character U+{:04X} is not allowed in XML",
+                    u32::from(*ch)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+/// Общая ошибка крейта, объединяющая ошибки чтения и записи.
+///
+/// Удобна в коде, который и читает, и записывает транзакции: благодаря реализациям
+/// [`From`] оба вида ошибок пробрасываются оператором `?`. Конкретные типы
+/// [`ParseError`] и [`DumpError`] по-прежнему доступны внутри вариантов.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{dump, parse, types::SupportedFileFormat};
+///
+/// fn csv_to_bin(input: &str) -> ypbank_parser::Result<Vec<u8>> {
+///     let txs = parse(&mut input.as_bytes(), SupportedFileFormat::Csv)?;
+///     let mut output = Vec::new();
+///     dump(&mut output, SupportedFileFormat::Bin, &txs)?;
+///     Ok(output)
+/// }
+///
+/// let err = csv_to_bin("not a csv").unwrap_err();
+/// assert!(matches!(err, ypbank_parser::Error::Parse(_)));
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// Ошибка при чтении данных.
+    Parse(ParseError),
+    /// Ошибка при записи данных.
+    Dump(DumpError),
+}
+
+/// Результат операций крейта с общей ошибкой [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "parse error: {}", err),
+            Error::Dump(err) => write!(f, "dump error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Dump(err) => Some(err),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<DumpError> for Error {
+    fn from(err: DumpError) -> Self {
+        Error::Dump(err)
+    }
+}
+
+impl From<ConvertError> for Error {
+    fn from(err: ConvertError) -> Self {
+        match err {
+            ConvertError::Parse(err) => Error::Parse(err),
+            ConvertError::Dump(err) => Error::Dump(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_error_from_parse_error() {
+        let cases = [
+            (
+                ParseError::IOError("broken pipe".to_string()),
+                "parse error: IO error: broken pipe",
+            ),
+            (
+                ParseError::InvalidFormat("invalid header".to_string()),
+                "parse error: invalid header",
+            ),
+            (
+                ParseError::ChecksumMismatch {
+                    expected: 0xdeadbeef,
+                    actual: 1,
+                },
+                "parse error: checksum mismatch: expected 0xdeadbeef, got 0x00000001",
+            ),
+            (
+                ParseError::TruncatedRecord { recovered: 2 },
+                "parse error: truncated record after 2 complete records",
+            ),
+            (
+                ParseError::RecordTooLarge { size: 100 },
+                "parse error: record too large: 100 bytes",
+            ),
+            (
+                ParseError::DuplicateId { id: 1001 },
+                "parse error: duplicate transaction id: 1001",
+            ),
+            (
+                ParseError::ImplausibleTimestamp { value: 0 },
+                "parse error: implausible timestamp: 0",
+            ),
+        ];
+        for (source, expected) in cases {
+            let err = Error::from(source);
+            assert!(matches!(err, Error::Parse(_)));
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_unified_error_from_dump_error() {
+        let cases = [
+            (DumpError::InternalError, "dump error: internal dump error"),
+            (DumpError::OutputError, "dump error: dump error"),
+            (
+                DumpError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe)),
+                "dump error: IO error: broken pipe",
+            ),
+            (
+                DumpError::DescriptionTooLong { len: 5 },
+                "dump error: description too long: 5 bytes",
+            ),
+            (
+                DumpError::InvalidXmlChar { ch: '\u{1}' },
+                "dump error: character U+0001 is not allowed in XML",
+            ),
+        ];
+        for (source, expected) in cases {
+            let err = Error::from(source);
+            assert!(matches!(err, Error::Dump(_)));
+            assert_eq!(err.to_string(), expected);
+            assert!(std::error::Error::source(&err).is_some());
+        }
+
+        let err = Error::from(ConvertError::Dump(DumpError::OutputError));
+        assert!(matches!(err, Error::Dump(DumpError::OutputError)));
+    }
+
+    #[test]
+    fn test_convert_error_display_and_source() {
+        let parse = ConvertError::from(ParseError::InvalidFormat("invalid header".to_string()));
+        assert_eq!(parse.to_string(), "parse error: invalid header");
+        assert!(std::error::Error::source(&parse).is_some());
+
+        let dump: Box<dyn std::error::Error> = ConvertError::from(DumpError::OutputError).into();
+        assert_eq!(dump.to_string(), "dump error: dump error");
+        assert!(dump.source().is_some());
+    }
+
+    // Отказывает при любой записи с заданным видом ошибки.
+    struct FailingWriter(std::io::ErrorKind);
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(self.0, "writer failed"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dump_error_keeps_io_kind() {
+        let txs = [crate::TransactionBuilder::new().build()];
+        for kind in [
+            std::io::ErrorKind::BrokenPipe,
+            std::io::ErrorKind::StorageFull,
+        ] {
+            for format in crate::types::SupportedFileFormat::ALL.iter().copied() {
+                let got = crate::dump(&mut FailingWriter(kind), format, &txs);
+                assert!(
+                    matches!(got, Err(DumpError::Io { kind: k, ref message }) if k == kind && message == "writer failed"),
+                    "{}: {:?}",
+                    format,
+                    got
+                );
+            }
+        }
     }
 }
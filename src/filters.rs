@@ -0,0 +1,121 @@
+//! Модуль предикатов для отбора транзакций.
+//!
+//! Каждая функция возвращает замыкание, которое можно передать прямо в
+//! [`Iterator::filter`] — как для `iter()` (элементы `&Transaction`), так и для
+//! `into_iter()` (элементы `Transaction`):
+//!
+//! ```rust
+//! use ypbank_parser::filters::{amount_between, by_type};
+//! use ypbank_parser::types::{TransactionBuilder, TxType};
+//!
+//! let txs = vec![
+//!     TransactionBuilder::new().id(1).amount(100).build(),
+//!     TransactionBuilder::new().id(2).tx_type(TxType::Fee).amount(5).build(),
+//! ];
+//! let deposits: Vec<_> = txs
+//!     .iter()
+//!     .filter(by_type(TxType::Deposit))
+//!     .filter(amount_between(50, 500))
+//!     .collect();
+//! assert_eq!(deposits.len(), 1);
+//! assert_eq!(deposits[0].id, 1);
+//! ```
+
+use std::borrow::Borrow;
+
+use crate::types::{Transaction, TxStatus, TxType};
+
+/// Транзакции указанного типа.
+pub fn by_type<T: Borrow<Transaction>>(tx_type: TxType) -> impl Fn(&T) -> bool + Copy {
+    move |tx| tx.borrow().r#type == tx_type
+}
+
+/// Транзакции с указанным статусом.
+pub fn by_status<T: Borrow<Transaction>>(status: TxStatus) -> impl Fn(&T) -> bool + Copy {
+    move |tx| tx.borrow().status == status
+}
+
+/// Транзакции, в которых пользователь `user` является отправителем или получателем.
+pub fn involving_user<T: Borrow<Transaction>>(user: u64) -> impl Fn(&T) -> bool + Copy {
+    move |tx| {
+        let tx = tx.borrow();
+        tx.from_user == user || tx.to_user == user
+    }
+}
+
+/// Транзакции с суммой в диапазоне `[min, max]`, обе границы включаются.
+/// Если `min > max`, ни одна транзакция не подходит.
+pub fn amount_between<T: Borrow<Transaction>>(min: u64, max: u64) -> impl Fn(&T) -> bool + Copy {
+    move |tx| (min..=max).contains(&tx.borrow().amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    fn sample() -> Vec<Transaction> {
+        vec![
+            TransactionBuilder::new()
+                .id(1)
+                .to_user(501)
+                .amount(100)
+                .status(TxStatus::Success)
+                .build(),
+            TransactionBuilder::new()
+                .id(2)
+                .tx_type(TxType::Transfer)
+                .from_user(501)
+                .to_user(502)
+                .amount(200)
+                .status(TxStatus::Failure)
+                .build(),
+            TransactionBuilder::new()
+                .id(3)
+                .tx_type(TxType::Withdrawal)
+                .from_user(502)
+                .amount(300)
+                .status(TxStatus::Pending)
+                .build(),
+        ]
+    }
+
+    fn ids<'a>(txs: impl Iterator<Item = &'a Transaction>) -> Vec<u64> {
+        txs.map(|tx| tx.id).collect()
+    }
+
+    #[test]
+    fn test_by_type_and_status() {
+        let txs = sample();
+        assert_eq!(ids(txs.iter().filter(by_type(TxType::Transfer))), [2]);
+        assert_eq!(ids(txs.iter().filter(by_type(TxType::Fee))), [] as [u64; 0]);
+        assert_eq!(ids(txs.iter().filter(by_status(TxStatus::Pending))), [3]);
+
+        let owned: Vec<_> = txs.into_iter().filter(by_type(TxType::Deposit)).collect();
+        assert_eq!(ids(owned.iter()), [1]);
+    }
+
+    #[test]
+    fn test_involving_user() {
+        let txs = sample();
+        assert_eq!(ids(txs.iter().filter(involving_user(501))), [1, 2]);
+        assert_eq!(ids(txs.iter().filter(involving_user(502))), [2, 3]);
+        assert_eq!(ids(txs.iter().filter(involving_user(7))), [] as [u64; 0]);
+    }
+
+    #[test]
+    fn test_amount_between_boundaries() {
+        let txs = sample();
+        assert_eq!(ids(txs.iter().filter(amount_between(100, 300))), [1, 2, 3]);
+        assert_eq!(ids(txs.iter().filter(amount_between(101, 299))), [2]);
+        assert_eq!(ids(txs.iter().filter(amount_between(200, 200))), [2]);
+        assert_eq!(
+            ids(txs.iter().filter(amount_between(0, u64::MAX))),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            ids(txs.iter().filter(amount_between(300, 100))),
+            [] as [u64; 0]
+        );
+    }
+}
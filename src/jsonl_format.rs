@@ -0,0 +1,414 @@
+use crate::error::{self, DumpError, ParseError};
+use crate::parser;
+use crate::types::{FIELD_NAMES, Transaction, TxStatus, TxType};
+use crate::utils;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+// Экранирует строку по правилам JSON и оборачивает её в кавычки.
+fn escape_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Значение поля объекта: поддерживаются только строки и неотрицательные целые числа.
+enum Value {
+    Number(u64),
+    String(String),
+}
+
+// Последовательно разбирает одну строку с JSON-объектом.
+struct JsonReader<'a> {
+    input: &'a str,
+}
+
+impl<'a> JsonReader<'a> {
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    fn try_char(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        match self.input.strip_prefix(c) {
+            Some(rest) => {
+                self.input = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect_char(&mut self, c: char) -> Result<(), ParseError> {
+        if self.try_char(c) {
+            Ok(())
+        } else {
+            Err(ParseError::InvalidFormat(format!("expected '{}'", c)))
+        }
+    }
+
+    fn read_hex4(chars: &mut std::str::Chars) -> Result<u32, ParseError> {
+        let hex: String = chars.by_ref().take(4).collect();
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 4)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("invalid unicode escape \\u{}", hex)))
+    }
+
+    // Читает строку в кавычках, раскрывая escape-последовательности JSON.
+    fn read_string(&mut self) -> Result<String, ParseError> {
+        self.expect_char('"')?;
+        let mut result = String::new();
+        let mut chars = self.input.chars();
+        loop {
+            let Some(c) = chars.next() else {
+                return Err(ParseError::InvalidFormat("unterminated string".to_string()));
+            };
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('"') => result.push('"'),
+                    Some('\\') => result.push('\\'),
+                    Some('/') => result.push('/'),
+                    Some('b') => result.push('\u{8}'),
+                    Some('f') => result.push('\u{c}'),
+                    Some('n') => result.push('\n'),
+                    Some('r') => result.push('\r'),
+                    Some('t') => result.push('\t'),
+                    Some('u') => {
+                        let mut code = Self::read_hex4(&mut chars)?;
+                        // Символы вне BMP записываются суррогатной парой.
+                        if (0xD800..0xDC00).contains(&code) {
+                            if chars.next() != Some('\\') || chars.next() != Some('u') {
+                                return Err(ParseError::InvalidFormat(
+                                    "unpaired surrogate in string".to_string(),
+                                ));
+                            }
+                            let low = Self::read_hex4(&mut chars)?;
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return Err(ParseError::InvalidFormat(
+                                    "unpaired surrogate in string".to_string(),
+                                ));
+                            }
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                        }
+                        let c = char::from_u32(code).ok_or_else(|| {
+                            ParseError::InvalidFormat("invalid unicode escape".to_string())
+                        })?;
+                        result.push(c);
+                    }
+                    _ => {
+                        return Err(ParseError::InvalidFormat(
+                            "invalid escape sequence in string".to_string(),
+                        ));
+                    }
+                },
+                c if c.is_control() => {
+                    return Err(ParseError::InvalidFormat(
+                        "unescaped control character in string".to_string(),
+                    ));
+                }
+                _ => result.push(c),
+            }
+        }
+        self.input = chars.as_str();
+        Ok(result)
+    }
+
+    fn read_value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        if self.input.starts_with('"') {
+            return Ok(Value::String(self.read_string()?));
+        }
+        let end = self
+            .input
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.input.len());
+        if end == 0 {
+            return Err(ParseError::InvalidFormat(
+                "expected string or unsigned integer".to_string(),
+            ));
+        }
+        let (number, rest) = self.input.split_at(end);
+        self.input = rest;
+        Ok(Value::Number(number.parse()?))
+    }
+}
+
+// Разбирает одну строку файла в транзакцию.
+fn parse_line(line: &str) -> Result<Transaction, ParseError> {
+    let mut reader = JsonReader { input: line };
+    let mut fields = HashMap::<String, Value>::with_capacity(FIELD_NAMES.len());
+    reader.expect_char('{')?;
+    if !reader.try_char('}') {
+        loop {
+            let name = reader.read_string()?;
+            if !FIELD_NAMES.contains(&name.as_str()) {
+                return Err(ParseError::InvalidFormat(format!("unknown field {}", name)));
+            }
+            reader.expect_char(':')?;
+            let value = reader.read_value()?;
+            if fields.contains_key(&name) {
+                return Err(ParseError::InvalidFormat(format!(
+                    "duplicate field {}",
+                    name
+                )));
+            }
+            fields.insert(name, value);
+            if reader.try_char('}') {
+                break;
+            }
+            reader.expect_char(',')?;
+        }
+    }
+    reader.skip_whitespace();
+    if !reader.input.is_empty() {
+        return Err(ParseError::InvalidFormat(
+            "unexpected data after object".to_string(),
+        ));
+    }
+
+    let get = |name: &str| {
+        fields
+            .get(name)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("missing field {}", name)))
+    };
+    let number = |name: &str| match get(name)? {
+        Value::Number(n) => Ok(*n),
+        Value::String(_) => Err(ParseError::InvalidFormat(format!(
+            "field {} must be a number",
+            name
+        ))),
+    };
+    let string = |name: &str| match get(name)? {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(_) => Err(ParseError::InvalidFormat(format!(
+            "field {} must be a string",
+            name
+        ))),
+    };
+    Ok(Transaction {
+        id: number("TX_ID")?,
+        r#type: string("TX_TYPE")?.parse::<TxType>()?,
+        from_user: number("FROM_USER_ID")?,
+        to_user: number("TO_USER_ID")?,
+        amount: number("AMOUNT")?,
+        timestamp: number("TIMESTAMP")?,
+        status: string("STATUS")?.parse::<TxStatus>()?,
+        description: string("DESCRIPTION")?,
+    })
+}
+
+/// Читает и парсит транзакции из формата JSON Lines.
+///
+/// Каждая непустая строка содержит один JSON-объект с полями транзакции,
+/// строки разбираются независимо друг от друга.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Строка не является корректным объектом или значение поля некорректно;
+///   в сообщении указывается номер строки.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+fn parse_from_jsonl(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
+    parse_from_jsonl_impl(reader, &mut parser::Progress::none())
+}
+
+fn parse_from_jsonl_impl(
+    reader: &mut impl io::Read,
+    progress: &mut parser::Progress,
+) -> Result<Vec<Transaction>, ParseError> {
+    let mut result = Vec::new();
+    for (index, line) in utils::lines_without_bom(reader).enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx = parse_line(&line).map_err(|err| match err {
+            ParseError::InvalidFormat(msg) => {
+                ParseError::InvalidFormat(format!("line {}: {}", index + 1, msg))
+            }
+            err => err,
+        })?;
+        result.push(tx);
+        progress.tick();
+    }
+    Ok(result)
+}
+
+/// Сериализует транзакции в формат JSON Lines: по одному компактному объекту на строку.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+fn dump_as_jsonl(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+) -> Result<usize, DumpError> {
+    dump_as_jsonl_impl(writer, transactions, &mut parser::Progress::none())
+}
+
+fn dump_as_jsonl_impl(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    progress: &mut parser::Progress,
+) -> Result<usize, DumpError> {
+    let mut writer = io::BufWriter::new(writer);
+    for tx in transactions {
+        writeln!(
+            writer,
+            "{{\"TX_ID\":{},\"TX_TYPE\":\"{}\",\"FROM_USER_ID\":{},\"TO_USER_ID\":{},\
+             \"AMOUNT\":{},\"TIMESTAMP\":{},\"STATUS\":\"{}\",\"DESCRIPTION\":{}}}",
+            tx.id,
+            tx.r#type,
+            tx.from_user,
+            tx.to_user,
+            tx.amount,
+            tx.timestamp,
+            tx.status,
+            escape_string(&tx.description)
+        )?;
+        progress.tick();
+    }
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+pub(crate) struct JsonLinesParser;
+
+impl parser::Parser for JsonLinesParser {
+    fn parse(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_jsonl(reader)
+    }
+
+    fn dump(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+    ) -> Result<usize, error::DumpError> {
+        dump_as_jsonl(writer, transactions)
+    }
+
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut parser::Progress,
+    ) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_jsonl_impl(reader, progress)
+    }
+
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+        progress: &mut parser::Progress,
+    ) -> Result<usize, error::DumpError> {
+        dump_as_jsonl_impl(writer, transactions, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    fn sample() -> Vec<Transaction> {
+        vec![
+            TransactionBuilder::new()
+                .id(1001)
+                .to_user(501)
+                .amount(50000)
+                .timestamp(1672531200000)
+                .description("Initial account funding")
+                .build(),
+            TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Transfer)
+                .from_user(501)
+                .to_user(502)
+                .status(TxStatus::Failure)
+                .description("Invoice \"#123\"\nsecond line\\tail\u{1}")
+                .build(),
+            TransactionBuilder::new()
+                .id(1003)
+                .tx_type(TxType::Withdrawal)
+                .status(TxStatus::Pending)
+                .description("Возврат 😀")
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let txs = sample();
+        let mut buffer = Vec::new();
+
+        assert_eq!(dump_as_jsonl(&mut buffer, &txs).unwrap(), txs.len());
+        let got = parse_from_jsonl(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_each_line_is_independent_object() {
+        let txs = sample();
+        let mut buffer = Vec::new();
+        dump_as_jsonl(&mut buffer, &txs).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+
+        let lines: Vec<&str> = dumped.lines().collect();
+        assert_eq!(lines.len(), txs.len());
+        for (line, tx) in lines.iter().zip(&txs) {
+            assert!(line.starts_with('{') && line.ends_with('}'));
+            assert_eq!(&parse_line(line).unwrap(), tx);
+        }
+        assert!(
+            lines[1].contains(r##""DESCRIPTION":"Invoice \"#123\"\nsecond line\\tail\u0001""##)
+        );
+    }
+
+    #[test]
+    fn test_parse_accepts_any_field_order_and_escapes() {
+        let input = "\n{ \"DESCRIPTION\": \"\\ud83d\\ude00 \\/ok\", \"STATUS\": \"SUCCESS\", \
+                     \"TIMESTAMP\": 5, \"AMOUNT\": 10, \"TO_USER_ID\": 0, \
+                     \"FROM_USER_ID\": 1, \"TX_TYPE\": \"FEE\", \"TX_ID\": 7 }\n\n";
+
+        let got = parse_from_jsonl(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].id, 7);
+        assert_eq!(got[0].r#type, TxType::Fee);
+        assert_eq!(got[0].description, "😀 /ok");
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let mut buffer = Vec::new();
+        dump_as_jsonl(&mut buffer, &sample()).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+        let broken = dumped.replacen("\"AMOUNT\":0", "\"AMOUNT\":\"0\"", 1);
+
+        let Err(ParseError::InvalidFormat(msg)) = parse_from_jsonl(&mut broken.as_bytes()) else {
+            panic!("expected InvalidFormat");
+        };
+        assert_eq!(msg, "line 2: field AMOUNT must be a number");
+
+        for input in ["{\"TX_ID\":1}", "[1]", "{\"TX_ID\":1,}", "{\"TX_ID\":-1}"] {
+            assert!(matches!(
+                parse_from_jsonl(&mut input.as_bytes()),
+                Err(ParseError::InvalidFormat(_))
+            ));
+        }
+    }
+}
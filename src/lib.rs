@@ -3,10 +3,29 @@
 //!
 //! Библиотека для парсинга и дампа истории транзакций в различных форматах.
 //!
-//! Этот крейт предоставляет унифицированный интерфейс для работы с тремя основными форматами:
+//! Этот крейт предоставляет унифицированный интерфейс для работы с семью основными форматами:
 //! * **CSV** (описание формата в [doc/YPBankCsvFormat_ru.md](doc/YPBankCsvFormat_ru.md))
 //! * **BIN** (описание формата в [doc/YPBankBinFormat_ru.md](doc/YPBankBinFormat_ru.md))
 //! * **Text** (описание формата в [doc/YPBankTextFormat_ru.md](doc/YPBankTextFormat_ru.md))
+//! * **TOML** (описание формата в [doc/YPBankTomlFormat_ru.md](doc/YPBankTomlFormat_ru.md))
+//! * **XML** (описание формата в [doc/YPBankXmlFormat_ru.md](doc/YPBankXmlFormat_ru.md))
+//! * **JSON Lines** (описание формата в [doc/YPBankJsonLinesFormat_ru.md](doc/YPBankJsonLinesFormat_ru.md))
+//! * **TextLine** (описание формата в [doc/YPBankTextLineFormat_ru.md](doc/YPBankTextLineFormat_ru.md))
+//!
+//! Форматы CSV, BIN и Text можно отключить, чтобы не компилировать лишний код: они
+//! включаются фичами `csv`, `bin` и `text` (все три включены по умолчанию).
+//! Например, `default-features = false, features = ["csv"]` оставляет из этих трёх
+//! только CSV.
+//!
+//! С фичей `proptest` для [`types::Transaction`], [`types::TxType`] и [`types::TxStatus`]
+//! реализован `proptest::arbitrary::Arbitrary`, что позволяет генерировать их в
+//! property-based тестах.
+//!
+//! С фичей `prost` также доступен формат **Protobuf**
+//! (описание в [doc/YPBankProtobufFormat_ru.md](doc/YPBankProtobufFormat_ru.md)).
+//! С фичей `rusqlite` транзакции можно выгрузить в базу SQLite и прочитать обратно
+//! (`dump_as_sqlite` и `parse_from_sqlite`); этот формат работает с путём к файлу,
+//! а не с потоком, поэтому не входит в [`types::SupportedFileFormat`].
 //!
 //! ## Быстрый старт
 //!
@@ -28,14 +47,60 @@
 //! ## Обработка ошибок
 //! Функции парсинга и дампа возвращают [`Result`], который содержит либо успешный результат,
 //! либо ошибки одного из типов [`error::ParseError`, `error::DumpError`] в зависимости от типа операции.
+//! Для кода, который и читает, и записывает данные, есть общая ошибка [`Error`]
+//! и псевдоним [`Result`]: обе конкретные ошибки преобразуются в неё оператором `?`.
 
+pub mod analytics;
+pub mod diff;
 pub mod error;
+pub mod filters;
 pub mod types;
+pub mod validation;
 
+#[cfg(feature = "proptest")]
+mod arbitrary;
+#[cfg(feature = "bin")]
 mod bin_format;
+#[cfg(feature = "csv")]
 mod csv_format;
+mod jsonl_format;
 mod parser;
+#[cfg(feature = "prost")]
+mod protobuf_format;
+#[cfg(feature = "rusqlite")]
+mod sqlite_format;
+#[cfg(feature = "text")]
 mod text_format;
+mod textline_format;
+mod toml_format;
 mod utils;
+mod xml_format;
 
-pub use parser::{dump, parse};
+#[cfg(feature = "rayon")]
+pub use bin_format::parse_from_bin_parallel;
+#[cfg(feature = "bin")]
+pub use bin_format::{
+    BinField, BinLayout, BinOptions, DEFAULT_MAX_RECORD_SIZE, DescriptionEncoding, MAGIC,
+    MIN_RECORD_SIZE, dump_as_bin_append, dump_as_bin_with, dump_raw_bin, parse_from_bin_partial,
+    parse_from_bin_raw, parse_from_bin_resync, parse_from_bin_with, parse_from_bin_with_offsets,
+    parse_from_bin_with_offsets_with, read_record_at, read_record_at_with, verify_bin,
+};
+#[cfg(feature = "csv")]
+pub use csv_format::{
+    CsvOptions, dump_as_csv_with, dump_as_csv_with_columns, missing_csv_columns,
+    parse_from_csv_lenient, parse_from_csv_meta, parse_from_csv_sized, parse_from_csv_with,
+};
+pub use error::{Error, Result};
+pub use parser::{
+    DumpOptions, LineEnding, convert, dump, dump_with, dump_with_progress, parse,
+    parse_with_progress, parse_with_warnings,
+};
+#[cfg(feature = "rusqlite")]
+pub use sqlite_format::{dump_as_sqlite, parse_from_sqlite};
+#[cfg(feature = "text")]
+pub use text_format::{
+    TextOptions, dump_as_text_pretty, dump_as_text_with, parse_from_text_collect,
+    parse_from_text_with,
+};
+pub use textline_format::{dump_as_text_oneline, parse_from_text_oneline};
+pub use types::{FIELD_NAMES, TransactionBatch, TransactionBuilder};
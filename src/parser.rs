@@ -3,18 +3,135 @@
 //! Предоставляет унифицированный интерфейс для работы с различными форматами файлов
 //! через функции [`parse`] и [`dump`].
 
-use crate::{error, types};
+use crate::{error, types, validation};
 use std::io;
 
 /// Трейт, который должны реализовывать все парсеры конкретных форматов.
 pub(crate) trait Parser {
     /// Читает транзакции из потока.
     fn parse(reader: &mut impl io::Read) -> Result<Vec<types::Transaction>, error::ParseError>;
-    /// Записывает транзакции в поток.
+    /// Записывает транзакции в поток и возвращает количество записанных транзакций.
     fn dump(
         writer: &mut impl io::Write,
         transactions: &[types::Transaction],
-    ) -> Result<(), error::DumpError>;
+    ) -> Result<usize, error::DumpError>;
+
+    /// Читает транзакции, сообщая о прогрессе через `progress`.
+    ///
+    /// По умолчанию формат разбирается целиком и прогресс сообщается один раз в конце;
+    /// потоковые форматы переопределяют метод и вызывают [`Progress::tick`] на каждую запись.
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut Progress,
+    ) -> Result<Vec<types::Transaction>, error::ParseError> {
+        let transactions = Self::parse(reader)?;
+        progress.advance(transactions.len());
+        Ok(transactions)
+    }
+
+    /// Записывает транзакции, сообщая о прогрессе через `progress`.
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[types::Transaction],
+        progress: &mut Progress,
+    ) -> Result<usize, error::DumpError> {
+        let written = Self::dump(writer, transactions)?;
+        progress.advance(written);
+        Ok(written)
+    }
+}
+
+/// Счётчик обработанных записей, который вызывает колбэк каждые `every` записей.
+pub(crate) struct Progress<'a> {
+    every: usize,
+    count: usize,
+    reported: usize,
+    callback: Option<&'a mut dyn FnMut(usize)>,
+}
+
+impl<'a> Progress<'a> {
+    /// `every == 0` считается равным единице.
+    pub(crate) fn new(every: usize, callback: &'a mut dyn FnMut(usize)) -> Self {
+        Self {
+            every: every.max(1),
+            count: 0,
+            reported: 0,
+            callback: Some(callback),
+        }
+    }
+
+    /// Счётчик без колбэка для вызовов без отслеживания прогресса.
+    pub(crate) fn none() -> Self {
+        Self {
+            every: 1,
+            count: 0,
+            reported: 0,
+            callback: None,
+        }
+    }
+
+    /// Учитывает одну обработанную запись.
+    pub(crate) fn tick(&mut self) {
+        self.count += 1;
+        if self.count.is_multiple_of(self.every) {
+            self.report();
+        }
+    }
+
+    /// Учитывает сразу `n` записей, не вызывая колбэк.
+    fn advance(&mut self, n: usize) {
+        self.count += n;
+    }
+
+    /// Сообщает итоговое количество, если оно ещё не было сообщено.
+    fn finish(&mut self) {
+        if self.reported != self.count {
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        self.reported = self.count;
+        if let Some(callback) = self.callback.as_mut() {
+            callback(self.count);
+        }
+    }
+}
+
+/// Параметры сериализации текстовых форматов.
+///
+/// Эти параметры используют только Text и CSV, остальные форматы их игнорируют.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DumpOptions {
+    /// Записывать `timestamp` строкой ISO-8601 в UTC (например, `2023-01-01T00:00:00.000Z`)
+    /// вместо числа миллисекунд. При чтении принимаются обе формы.
+    #[cfg(feature = "chrono")]
+    pub human_timestamps: bool,
+    /// Символы конца строки при записи. При чтении принимаются оба варианта.
+    pub line_ending: LineEnding,
+    /// Комментарий в начале файла. Каждая его строка записывается с префиксом `# `;
+    /// при чтении такие строки пропускаются.
+    pub header_comment: Option<String>,
+}
+
+/// Символы конца строки в текстовых форматах.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, как в Unix.
+    #[default]
+    Lf,
+    /// `\r\n`, как в Windows.
+    Crlf,
+}
+
+impl LineEnding {
+    /// Символы конца строки: `"\n"` или `"\r\n"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
 }
 
 /// Читает список транзакций из предоставленного потока в заданном формате.
@@ -58,9 +175,24 @@ pub fn parse(
     format: types::SupportedFileFormat,
 ) -> Result<Vec<types::Transaction>, error::ParseError> {
     match format {
+        #[cfg(feature = "csv")]
         types::SupportedFileFormat::Csv => crate::csv_format::CsvParser::parse(reader),
+        #[cfg(feature = "bin")]
         types::SupportedFileFormat::Bin => crate::bin_format::BinParser::parse(reader),
+        #[cfg(feature = "text")]
         types::SupportedFileFormat::Text => crate::text_format::TextParser::parse(reader),
+        types::SupportedFileFormat::Toml => crate::toml_format::TomlParser::parse(reader),
+        types::SupportedFileFormat::Xml => crate::xml_format::XmlParser::parse(reader),
+        types::SupportedFileFormat::JsonLines => {
+            crate::jsonl_format::JsonLinesParser::parse(reader)
+        }
+        types::SupportedFileFormat::TextLine => {
+            crate::textline_format::TextLineParser::parse(reader)
+        }
+        #[cfg(feature = "prost")]
+        types::SupportedFileFormat::Protobuf => {
+            crate::protobuf_format::ProtobufParser::parse(reader)
+        }
     }
 }
 
@@ -74,7 +206,7 @@ pub fn parse(
 ///
 /// ## Возвращаемое значение
 ///
-/// Возвращает `Ok(())` в случае успеха или ошибку [`error::DumpError`].
+/// Возвращает количество записанных транзакций в случае успеха или ошибку [`error::DumpError`].
 ///
 /// # Пример
 ///
@@ -90,7 +222,8 @@ pub fn parse(
 ///                            description: "Description".to_string()}];
 /// let mut buffer = Vec::new();
 ///
-/// dump(&mut buffer, SupportedFileFormat::Text, &txs).expect("Ошибка записи");
+/// let written = dump(&mut buffer, SupportedFileFormat::Text, &txs).expect("Ошибка записи");
+/// assert_eq!(written, 1);
 ///
 /// let result_string = String::from_utf8(buffer).expect("Невалидный UTF-8");
 /// assert!(result_string.contains("STATUS: SUCCESS"));
@@ -99,12 +232,495 @@ pub fn dump(
     writer: &mut impl io::Write,
     format: types::SupportedFileFormat,
     transactions: &[types::Transaction],
-) -> Result<(), error::DumpError> {
+) -> Result<usize, error::DumpError> {
     match format {
+        #[cfg(feature = "csv")]
         types::SupportedFileFormat::Csv => crate::csv_format::CsvParser::dump(writer, transactions),
+        #[cfg(feature = "bin")]
         types::SupportedFileFormat::Bin => crate::bin_format::BinParser::dump(writer, transactions),
+        #[cfg(feature = "text")]
         types::SupportedFileFormat::Text => {
             crate::text_format::TextParser::dump(writer, transactions)
         }
+        types::SupportedFileFormat::Toml => {
+            crate::toml_format::TomlParser::dump(writer, transactions)
+        }
+        types::SupportedFileFormat::Xml => crate::xml_format::XmlParser::dump(writer, transactions),
+        types::SupportedFileFormat::JsonLines => {
+            crate::jsonl_format::JsonLinesParser::dump(writer, transactions)
+        }
+        types::SupportedFileFormat::TextLine => {
+            crate::textline_format::TextLineParser::dump(writer, transactions)
+        }
+        #[cfg(feature = "prost")]
+        types::SupportedFileFormat::Protobuf => {
+            crate::protobuf_format::ProtobufParser::dump(writer, transactions)
+        }
+    }
+}
+
+/// Записывает транзакции аналогично [`dump`], но с дополнительными параметрами [`DumpOptions`].
+///
+/// Возвращает количество записанных транзакций в случае успеха или ошибку [`error::DumpError`].
+// Без фич `csv` и `text` параметры не используются ни одним форматом.
+#[cfg_attr(not(any(feature = "csv", feature = "text")), allow(unused_variables))]
+pub fn dump_with(
+    writer: &mut impl io::Write,
+    format: types::SupportedFileFormat,
+    transactions: &[types::Transaction],
+    options: &DumpOptions,
+) -> Result<usize, error::DumpError> {
+    match format {
+        #[cfg(feature = "csv")]
+        types::SupportedFileFormat::Csv => crate::csv_format::dump_as_csv_with_options(
+            writer,
+            transactions,
+            &crate::csv_format::CsvOptions::default(),
+            options,
+            &mut Progress::none(),
+        ),
+        #[cfg(feature = "text")]
+        types::SupportedFileFormat::Text => crate::text_format::dump_as_text_with_options(
+            writer,
+            transactions,
+            &crate::text_format::TextOptions::default(),
+            options,
+            &mut Progress::none(),
+        ),
+        _ => dump(writer, format, transactions),
+    }
+}
+
+/// Читает транзакции аналогично [`parse`], сообщая о прогрессе.
+///
+/// `on_progress` вызывается с количеством уже прочитанных транзакций после каждых
+/// `every` записей (`0` считается равным `1`) и один раз в конце с итоговым количеством,
+/// если оно не кратно `every`. Потоковые форматы (CSV, Text, TextLine, JSON Lines, BIN)
+/// сообщают о прогрессе по мере чтения; TOML, XML и Protobuf разбираются целиком, поэтому
+/// для них колбэк вызывается только в конце.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{parse_with_progress, types::SupportedFileFormat};
+///
+/// let data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+///             1,DEPOSIT,0,501,10,0,SUCCESS,\"a\"\n\
+///             2,DEPOSIT,0,501,20,0,SUCCESS,\"b\"\n\
+///             3,DEPOSIT,0,501,30,0,SUCCESS,\"c\"\n";
+/// let mut counts = Vec::new();
+/// let txs = parse_with_progress(&mut data.as_bytes(), SupportedFileFormat::Csv, 2, |n| {
+///     counts.push(n)
+/// })
+/// .unwrap();
+/// assert_eq!(txs.len(), 3);
+/// assert_eq!(counts, [2, 3]);
+/// ```
+pub fn parse_with_progress(
+    reader: &mut impl io::Read,
+    format: types::SupportedFileFormat,
+    every: usize,
+    mut on_progress: impl FnMut(usize),
+) -> Result<Vec<types::Transaction>, error::ParseError> {
+    #[cfg(feature = "bin")]
+    use crate::bin_format::BinParser;
+    #[cfg(feature = "csv")]
+    use crate::csv_format::CsvParser;
+    #[cfg(feature = "text")]
+    use crate::text_format::TextParser;
+    use crate::{
+        jsonl_format::JsonLinesParser, textline_format::TextLineParser, toml_format::TomlParser,
+        xml_format::XmlParser,
+    };
+
+    let mut progress = Progress::new(every, &mut on_progress);
+    let transactions = match format {
+        #[cfg(feature = "csv")]
+        types::SupportedFileFormat::Csv => CsvParser::parse_with_progress(reader, &mut progress),
+        #[cfg(feature = "bin")]
+        types::SupportedFileFormat::Bin => BinParser::parse_with_progress(reader, &mut progress),
+        #[cfg(feature = "text")]
+        types::SupportedFileFormat::Text => TextParser::parse_with_progress(reader, &mut progress),
+        types::SupportedFileFormat::Toml => TomlParser::parse_with_progress(reader, &mut progress),
+        types::SupportedFileFormat::Xml => XmlParser::parse_with_progress(reader, &mut progress),
+        types::SupportedFileFormat::JsonLines => {
+            JsonLinesParser::parse_with_progress(reader, &mut progress)
+        }
+        types::SupportedFileFormat::TextLine => {
+            TextLineParser::parse_with_progress(reader, &mut progress)
+        }
+        #[cfg(feature = "prost")]
+        types::SupportedFileFormat::Protobuf => {
+            crate::protobuf_format::ProtobufParser::parse_with_progress(reader, &mut progress)
+        }
+    }?;
+    progress.finish();
+    Ok(transactions)
+}
+
+/// Читает транзакции аналогично [`parse`] и дополнительно возвращает предупреждения
+/// о подозрительных, но допустимых данных (см. [`crate::validation::collect_warnings`]).
+///
+/// Предупреждения не прерывают разбор: ошибка возвращается только в тех же случаях,
+/// что и у [`parse`].
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{parse_with_warnings, types::SupportedFileFormat};
+/// use ypbank_parser::validation::Warning;
+///
+/// let data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+///             1,DEPOSIT,0,501,10,5,SUCCESS,\"a\"\n\
+///             1,DEPOSIT,0,501,20,6,SUCCESS,\"b\"\n";
+/// let (txs, warnings) = parse_with_warnings(&mut data.as_bytes(), SupportedFileFormat::Csv).unwrap();
+/// assert_eq!(txs.len(), 2);
+/// assert_eq!(warnings, [Warning::DuplicateId { id: 1 }]);
+/// ```
+pub fn parse_with_warnings(
+    reader: &mut impl io::Read,
+    format: types::SupportedFileFormat,
+) -> Result<(Vec<types::Transaction>, Vec<validation::Warning>), error::ParseError> {
+    let transactions = parse(reader, format)?;
+    let warnings = validation::collect_warnings(&transactions);
+    Ok((transactions, warnings))
+}
+
+/// Записывает транзакции аналогично [`dump`], сообщая о прогрессе.
+///
+/// `on_progress` вызывается по тем же правилам, что и в [`parse_with_progress`],
+/// с количеством уже записанных транзакций.
+pub fn dump_with_progress(
+    writer: &mut impl io::Write,
+    format: types::SupportedFileFormat,
+    transactions: &[types::Transaction],
+    every: usize,
+    mut on_progress: impl FnMut(usize),
+) -> Result<usize, error::DumpError> {
+    #[cfg(feature = "bin")]
+    use crate::bin_format::BinParser;
+    #[cfg(feature = "csv")]
+    use crate::csv_format::CsvParser;
+    #[cfg(feature = "text")]
+    use crate::text_format::TextParser;
+    use crate::{
+        jsonl_format::JsonLinesParser, textline_format::TextLineParser, toml_format::TomlParser,
+        xml_format::XmlParser,
+    };
+
+    let mut progress = Progress::new(every, &mut on_progress);
+    let p = &mut progress;
+    let written = match format {
+        #[cfg(feature = "csv")]
+        types::SupportedFileFormat::Csv => CsvParser::dump_with_progress(writer, transactions, p),
+        #[cfg(feature = "bin")]
+        types::SupportedFileFormat::Bin => BinParser::dump_with_progress(writer, transactions, p),
+        #[cfg(feature = "text")]
+        types::SupportedFileFormat::Text => TextParser::dump_with_progress(writer, transactions, p),
+        types::SupportedFileFormat::Toml => TomlParser::dump_with_progress(writer, transactions, p),
+        types::SupportedFileFormat::Xml => XmlParser::dump_with_progress(writer, transactions, p),
+        types::SupportedFileFormat::JsonLines => {
+            JsonLinesParser::dump_with_progress(writer, transactions, p)
+        }
+        types::SupportedFileFormat::TextLine => {
+            TextLineParser::dump_with_progress(writer, transactions, p)
+        }
+        #[cfg(feature = "prost")]
+        types::SupportedFileFormat::Protobuf => {
+            crate::protobuf_format::ProtobufParser::dump_with_progress(writer, transactions, p)
+        }
+    }?;
+    progress.finish();
+    Ok(written)
+}
+
+/// Конвертирует транзакции из одного формата в другой.
+///
+/// Потоковые парсеры пока не реализованы, поэтому транзакции целиком читаются в память
+/// и затем записываются в `writer`.
+///
+/// ## Аргументы
+///
+/// * `reader` - Поток с исходными данными в формате `in_format`.
+/// * `in_format` - Формат исходных данных.
+/// * `writer` - Поток, куда будет записан результат.
+/// * `out_format` - Целевой формат.
+///
+/// ## Возвращаемое значение
+///
+/// Возвращает количество сконвертированных транзакций или ошибку [`error::ConvertError`].
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{convert, types::SupportedFileFormat};
+///
+/// let data = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+///                1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding""##;
+/// let mut output = Vec::new();
+///
+/// let converted = convert(
+///     &mut data.as_bytes(),
+///     SupportedFileFormat::Csv,
+///     &mut output,
+///     SupportedFileFormat::Text,
+/// )
+/// .expect("Ошибка конвертации");
+/// assert_eq!(converted, 1);
+/// ```
+pub fn convert(
+    reader: &mut impl io::Read,
+    in_format: types::SupportedFileFormat,
+    writer: &mut impl io::Write,
+    out_format: types::SupportedFileFormat,
+) -> Result<usize, error::ConvertError> {
+    let transactions = parse(reader, in_format)?;
+    Ok(dump(writer, out_format, &transactions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_returns_written_count() {
+        let txs = vec![
+            types::TransactionBuilder::new().id(1).build(),
+            types::TransactionBuilder::new().id(2).build(),
+        ];
+        let formats = [
+            #[cfg(feature = "text")]
+            types::SupportedFileFormat::Text,
+            #[cfg(feature = "csv")]
+            types::SupportedFileFormat::Csv,
+            #[cfg(feature = "bin")]
+            types::SupportedFileFormat::Bin,
+            types::SupportedFileFormat::Toml,
+            types::SupportedFileFormat::Xml,
+            types::SupportedFileFormat::JsonLines,
+            types::SupportedFileFormat::TextLine,
+            #[cfg(feature = "prost")]
+            types::SupportedFileFormat::Protobuf,
+        ];
+
+        for format in formats {
+            let mut buffer = Vec::new();
+            assert_eq!(dump(&mut buffer, format, &txs).unwrap(), txs.len());
+
+            let mut buffer = Vec::new();
+            assert_eq!(dump(&mut buffer, format, &[]).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv", feature = "bin"))]
+    fn test_convert_csv_bin_csv_roundtrip() {
+        let mut csv = Vec::new();
+        let txs = vec![
+            types::TransactionBuilder::new().id(1).amount(10).build(),
+            types::TransactionBuilder::new()
+                .id(2)
+                .description("Invoice, \"#2\"")
+                .build(),
+        ];
+        dump(&mut csv, types::SupportedFileFormat::Csv, &txs).unwrap();
+
+        let mut bin = Vec::new();
+        let converted = convert(
+            &mut csv.as_slice(),
+            types::SupportedFileFormat::Csv,
+            &mut bin,
+            types::SupportedFileFormat::Bin,
+        )
+        .unwrap();
+        assert_eq!(converted, 2);
+
+        let mut back = Vec::new();
+        convert(
+            &mut bin.as_slice(),
+            types::SupportedFileFormat::Bin,
+            &mut back,
+            types::SupportedFileFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(back, csv);
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv", feature = "text"))]
+    fn test_parse_with_warnings() {
+        use validation::Warning;
+
+        let data = "TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                    AMOUNT: 100\nTIMESTAMP: 2000\nSTATUS: SUCCESS\n\n\
+                    TX_ID: 2\nTX_TYPE: FEE\nFROM_USER_ID: 501\nTO_USER_ID: 0\n\
+                    AMOUNT: 0\nTIMESTAMP: 1000\nSTATUS: SUCCESS\n\n\
+                    TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                    AMOUNT: 100\nTIMESTAMP: 3000\nSTATUS: SUCCESS\n";
+        let (txs, warnings) =
+            parse_with_warnings(&mut data.as_bytes(), types::SupportedFileFormat::Text).unwrap();
+        assert_eq!(txs.len(), 3);
+        assert_eq!(
+            warnings,
+            [
+                Warning::NonMonotonicTimestamp {
+                    id: 2,
+                    timestamp: 1000,
+                    previous: 2000
+                },
+                Warning::ZeroAmount { id: 2 },
+                Warning::DuplicateId { id: 1 },
+            ]
+        );
+
+        let mut csv = Vec::new();
+        dump(&mut csv, types::SupportedFileFormat::Csv, &txs[..1]).unwrap();
+        let (_, warnings) =
+            parse_with_warnings(&mut csv.as_slice(), types::SupportedFileFormat::Csv).unwrap();
+        assert!(warnings.is_empty());
+
+        // ошибки разбора по-прежнему возвращаются как ошибки
+        assert!(
+            parse_with_warnings(
+                &mut "TX_ID: x\n".as_bytes(),
+                types::SupportedFileFormat::Text
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bin")]
+    fn test_progress_reports_final_count() {
+        let txs: Vec<_> = (0..10)
+            .map(|id| types::TransactionBuilder::new().id(id).build())
+            .collect();
+
+        for format in types::SupportedFileFormat::ALL.iter().copied() {
+            let mut buffer = Vec::new();
+            let mut dumped = Vec::new();
+            dump_with_progress(&mut buffer, format, &txs, 4, |n| dumped.push(n)).unwrap();
+            assert_eq!(dumped.last(), Some(&txs.len()), "{}", format);
+
+            let mut parsed = Vec::new();
+            let back =
+                parse_with_progress(&mut buffer.as_slice(), format, 4, |n| parsed.push(n)).unwrap();
+            assert_eq!(back.len(), txs.len());
+            assert_eq!(parsed.last(), Some(&txs.len()), "{}", format);
+        }
+
+        // Потоковые форматы сообщают о прогрессе по ходу работы.
+        let mut buffer = Vec::new();
+        let mut dumped = Vec::new();
+        dump_with_progress(&mut buffer, types::SupportedFileFormat::Bin, &txs, 4, |n| {
+            dumped.push(n)
+        })
+        .unwrap();
+        assert_eq!(dumped, [4, 8, 10]);
+
+        let mut parsed = Vec::new();
+        parse_with_progress(
+            &mut buffer.as_slice(),
+            types::SupportedFileFormat::Bin,
+            0,
+            |n| parsed.push(n),
+        )
+        .unwrap();
+        assert_eq!(parsed, (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv", feature = "text"))]
+    fn test_crlf_line_endings() {
+        let txs = vec![
+            types::TransactionBuilder::new()
+                .id(1)
+                .description("a")
+                .build(),
+            types::TransactionBuilder::new()
+                .id(2)
+                .description("b")
+                .build(),
+        ];
+        let options = DumpOptions {
+            line_ending: LineEnding::Crlf,
+            ..DumpOptions::default()
+        };
+
+        for format in [
+            types::SupportedFileFormat::Csv,
+            types::SupportedFileFormat::Text,
+        ] {
+            let mut buffer = Vec::new();
+            dump_with(&mut buffer, format, &txs, &options).unwrap();
+            let output = String::from_utf8(buffer).unwrap();
+
+            assert!(output.ends_with("\r\n"), "{}", format);
+            assert_eq!(
+                output.matches('\n').count(),
+                output.matches("\r\n").count(),
+                "{}: bare LF in {:?}",
+                format,
+                output
+            );
+            assert_eq!(parse(&mut output.as_bytes(), format).unwrap(), txs);
+
+            let mut lf = Vec::new();
+            dump_with(&mut lf, format, &txs, &DumpOptions::default()).unwrap();
+            assert_eq!(String::from_utf8(lf).unwrap(), output.replace("\r\n", "\n"));
+        }
+
+        let mut buffer = Vec::new();
+        dump_with(&mut buffer, types::SupportedFileFormat::Csv, &txs, &options).unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("DESCRIPTION\r\n1,DEPOSIT,0,0,0,0,PENDING,\"a\"\r\n2,")
+        );
+    }
+
+    // Считает вызовы write у нижележащего потока.
+    struct CountingWriter {
+        writes: usize,
+    }
+
+    impl io::Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_text_formats_are_buffered() {
+        let txs: Vec<_> = (0..100)
+            .map(|id| types::TransactionBuilder::new().id(id).build())
+            .collect();
+        let formats = [
+            #[cfg(feature = "text")]
+            types::SupportedFileFormat::Text,
+            #[cfg(feature = "csv")]
+            types::SupportedFileFormat::Csv,
+            types::SupportedFileFormat::Toml,
+            types::SupportedFileFormat::Xml,
+            types::SupportedFileFormat::JsonLines,
+            types::SupportedFileFormat::TextLine,
+        ];
+
+        for format in formats {
+            let mut writer = CountingWriter { writes: 0 };
+            dump(&mut writer, format, &txs).unwrap();
+
+            assert!(
+                writer.writes < txs.len(),
+                "{} made {} writes",
+                format,
+                writer.writes
+            );
+        }
     }
 }
@@ -0,0 +1,236 @@
+use crate::error::{self, DumpError, ParseError};
+use crate::parser;
+use crate::types::{Transaction, TxStatus, TxType};
+use prost::Message;
+use std::io;
+
+// Значения перечислений совпадают с дискриминантами, которые использует BIN формат.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum ProtoTxType {
+    Deposit = 0,
+    Transfer = 1,
+    Withdrawal = 2,
+    Fee = 3,
+    Reversal = 4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum ProtoTxStatus {
+    Success = 0,
+    Failure = 1,
+    Pending = 2,
+}
+
+// Сообщение `Transaction`, схема описана в doc/YPBankProtobufFormat_ru.md.
+#[derive(Clone, PartialEq, Message)]
+struct ProtoTransaction {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    #[prost(enumeration = "ProtoTxType", tag = "2")]
+    tx_type: i32,
+    #[prost(uint64, tag = "3")]
+    from_user_id: u64,
+    #[prost(uint64, tag = "4")]
+    to_user_id: u64,
+    #[prost(uint64, tag = "5")]
+    amount: u64,
+    #[prost(uint64, tag = "6")]
+    timestamp: u64,
+    #[prost(enumeration = "ProtoTxStatus", tag = "7")]
+    status: i32,
+    #[prost(string, tag = "8")]
+    description: String,
+}
+
+impl From<&Transaction> for ProtoTransaction {
+    fn from(tx: &Transaction) -> Self {
+        let tx_type = match tx.r#type {
+            TxType::Deposit => ProtoTxType::Deposit,
+            TxType::Transfer => ProtoTxType::Transfer,
+            TxType::Withdrawal => ProtoTxType::Withdrawal,
+            TxType::Fee => ProtoTxType::Fee,
+            TxType::Reversal => ProtoTxType::Reversal,
+        };
+        let status = match tx.status {
+            TxStatus::Success => ProtoTxStatus::Success,
+            TxStatus::Failure => ProtoTxStatus::Failure,
+            TxStatus::Pending => ProtoTxStatus::Pending,
+        };
+        Self {
+            id: tx.id,
+            tx_type: tx_type as i32,
+            from_user_id: tx.from_user,
+            to_user_id: tx.to_user,
+            amount: tx.amount,
+            timestamp: tx.timestamp,
+            status: status as i32,
+            description: tx.description.clone(),
+        }
+    }
+}
+
+impl TryFrom<ProtoTransaction> for Transaction {
+    type Error = ParseError;
+
+    fn try_from(msg: ProtoTransaction) -> Result<Self, Self::Error> {
+        let r#type = match ProtoTxType::try_from(msg.tx_type) {
+            Ok(ProtoTxType::Deposit) => TxType::Deposit,
+            Ok(ProtoTxType::Transfer) => TxType::Transfer,
+            Ok(ProtoTxType::Withdrawal) => TxType::Withdrawal,
+            Ok(ProtoTxType::Fee) => TxType::Fee,
+            Ok(ProtoTxType::Reversal) => TxType::Reversal,
+            Err(_) => {
+                return Err(ParseError::InvalidFormat(format!(
+                    "unexpected TxType {}",
+                    msg.tx_type
+                )));
+            }
+        };
+        let status = match ProtoTxStatus::try_from(msg.status) {
+            Ok(ProtoTxStatus::Success) => TxStatus::Success,
+            Ok(ProtoTxStatus::Failure) => TxStatus::Failure,
+            Ok(ProtoTxStatus::Pending) => TxStatus::Pending,
+            Err(_) => {
+                return Err(ParseError::InvalidFormat(format!(
+                    "unexpected TxStatus {}",
+                    msg.status
+                )));
+            }
+        };
+        Ok(Transaction {
+            id: msg.id,
+            r#type,
+            from_user: msg.from_user_id,
+            to_user: msg.to_user_id,
+            amount: msg.amount,
+            timestamp: msg.timestamp,
+            status,
+            description: msg.description,
+        })
+    }
+}
+
+/// Читает транзакции из потока сообщений Protocol Buffers.
+///
+/// Каждое сообщение `Transaction` предварено своей длиной в формате varint.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Сообщение не удалось декодировать или в нём неизвестное значение перечисления.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+fn parse_from_protobuf(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut buf = data.as_slice();
+    let mut result = Vec::new();
+    while !buf.is_empty() {
+        let msg = ProtoTransaction::decode_length_delimited(&mut buf)
+            .map_err(|err| ParseError::InvalidFormat(err.to_string()))?;
+        result.push(Transaction::try_from(msg)?);
+    }
+    Ok(result)
+}
+
+/// Сериализует транзакции в поток сообщений Protocol Buffers с префиксом длины.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+fn dump_as_protobuf(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+) -> Result<usize, DumpError> {
+    let mut buf = Vec::new();
+    for tx in transactions {
+        buf.clear();
+        ProtoTransaction::from(tx)
+            .encode_length_delimited(&mut buf)
+            .map_err(|_| DumpError::InternalError)?;
+        writer.write_all(&buf)?;
+    }
+    Ok(transactions.len())
+}
+
+pub(crate) struct ProtobufParser;
+
+impl parser::Parser for ProtobufParser {
+    fn parse(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_protobuf(reader)
+    }
+
+    fn dump(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+    ) -> Result<usize, error::DumpError> {
+        dump_as_protobuf(writer, transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "csv")]
+    use crate::types::SupportedFileFormat;
+
+    #[cfg(feature = "csv")]
+    const CSV: &str = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+        1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+        1002,TRANSFER,501,502,15000,1672534800000,FAILURE,"Payment for services, invoice #123"
+        1003,WITHDRAWAL,502,0,1000,1672538400000,PENDING,"ATM withdrawal"
+        1004,FEE,502,0,10,1672538400001,SUCCESS,""
+        1005,REVERSAL,0,502,1000,1672538400002,SUCCESS,"Возврат""##;
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_roundtrip() {
+        let txs = parser::parse(&mut CSV.as_bytes(), SupportedFileFormat::Csv).unwrap();
+        let mut buffer = Vec::new();
+
+        assert_eq!(dump_as_protobuf(&mut buffer, &txs).unwrap(), txs.len());
+        let got = parse_from_protobuf(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_messages_match_csv_fields() {
+        let txs = parser::parse(&mut CSV.as_bytes(), SupportedFileFormat::Csv).unwrap();
+        let mut buffer = Vec::new();
+        dump_as_protobuf(&mut buffer, &txs).unwrap();
+
+        let mut buf = buffer.as_slice();
+        let first = ProtoTransaction::decode_length_delimited(&mut buf).unwrap();
+        let second = ProtoTransaction::decode_length_delimited(&mut buf).unwrap();
+
+        assert_eq!(first.id, 1001);
+        assert_eq!(first.tx_type, 0);
+        assert_eq!(first.to_user_id, 501);
+        assert_eq!(first.amount, 50000);
+        assert_eq!(first.timestamp, 1672531200000);
+        assert_eq!(first.status, 0);
+        assert_eq!(first.description, "Initial account funding");
+        assert_eq!(second.tx_type, i32::from(u8::from(TxType::Transfer)));
+        assert_eq!(second.status, i32::from(u8::from(TxStatus::Failure)));
+        assert_eq!(second.from_user_id, 501);
+    }
+
+    #[test]
+    fn test_unknown_enum_value() {
+        let msg = ProtoTransaction {
+            tx_type: 42,
+            ..ProtoTransaction::default()
+        };
+        let buffer = msg.encode_length_delimited_to_vec();
+
+        let got = parse_from_protobuf(&mut buffer.as_slice());
+
+        assert!(matches!(got, Err(ParseError::InvalidFormat(_))));
+    }
+}
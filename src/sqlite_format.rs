@@ -0,0 +1,165 @@
+use crate::error::{DumpError, ParseError};
+use crate::types::{Transaction, TxStatus, TxType};
+use std::path::Path;
+
+const CREATE_TABLE: &str = "CREATE TABLE transactions (
+    id INTEGER NOT NULL,
+    tx_type TEXT NOT NULL,
+    from_user_id INTEGER NOT NULL,
+    to_user_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    description TEXT NOT NULL
+)";
+
+impl From<rusqlite::Error> for ParseError {
+    fn from(err: rusqlite::Error) -> Self {
+        ParseError::IOError(err.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for DumpError {
+    fn from(_: rusqlite::Error) -> Self {
+        DumpError::OutputError
+    }
+}
+
+/// Записывает транзакции в базу SQLite по пути `path`. Доступна с фичей `rusqlite`.
+///
+/// Создаёт таблицу `transactions` с колонками `id`, `tx_type`, `from_user_id`, `to_user_id`,
+/// `amount`, `timestamp`, `status` и `description`; тип и статус хранятся строками
+/// (`DEPOSIT`, `SUCCESS`, ...), остальные числовые поля — как `INTEGER`. Существующая
+/// таблица с тем же именем заменяется. Все записи вставляются в одной SQL-транзакции.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError::OutputError`], если базу не удалось открыть или записать,
+/// а также если числовое поле больше `i64::MAX` и не помещается в `INTEGER` SQLite.
+/// В случае ошибки база остаётся без изменений.
+pub fn dump_as_sqlite(path: &Path, transactions: &[Transaction]) -> Result<usize, DumpError> {
+    let mut conn = rusqlite::Connection::open(path)?;
+    let tx = conn.transaction()?;
+    tx.execute("DROP TABLE IF EXISTS transactions", [])?;
+    tx.execute(CREATE_TABLE, [])?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO transactions (id, tx_type, from_user_id, to_user_id, amount, \
+             timestamp, status, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for t in transactions {
+            insert.execute(rusqlite::params![
+                t.id,
+                t.r#type.to_string(),
+                t.from_user,
+                t.to_user,
+                t.amount,
+                t.timestamp,
+                t.status.to_string(),
+                t.description,
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(transactions.len())
+}
+
+/// Читает транзакции из таблицы `transactions` базы SQLite в порядке вставки (по `rowid`).
+/// Доступна с фичей `rusqlite`.
+///
+/// Ожидает схему, которую создаёт [`dump_as_sqlite`].
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Базу не удалось открыть или в ней нет таблицы `transactions` ([`ParseError::IOError`]).
+/// * Значение поля некорректно: отрицательное число или неизвестный тип/статус.
+pub fn parse_from_sqlite(path: &Path) -> Result<Vec<Transaction>, ParseError> {
+    let conn =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut select = conn.prepare(
+        "SELECT id, tx_type, from_user_id, to_user_id, amount, timestamp, status, description \
+         FROM transactions ORDER BY rowid",
+    )?;
+    let mut rows = select.query([])?;
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let number = |index: usize| {
+            let value: i64 = row.get(index)?;
+            u64::try_from(value).map_err(|_| {
+                ParseError::InvalidFormat(format!("negative value {} in column {}", value, index))
+            })
+        };
+        result.push(Transaction {
+            id: number(0)?,
+            r#type: row.get::<_, String>(1)?.parse::<TxType>()?,
+            from_user: number(2)?,
+            to_user: number(3)?,
+            amount: number(4)?,
+            timestamp: number(5)?,
+            status: row.get::<_, String>(6)?.parse::<TxStatus>()?,
+            description: row.get(7)?,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ypbank_{}_{}.sqlite", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let txs = vec![
+            TransactionBuilder::new()
+                .id(1001)
+                .to_user(501)
+                .amount(50000)
+                .timestamp(1672531200000)
+                .status(TxStatus::Success)
+                .description("Initial account funding")
+                .build(),
+            TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Transfer)
+                .from_user(501)
+                .to_user(502)
+                .amount(15000)
+                .status(TxStatus::Failure)
+                .description("Payment, 'quoted' \"text\"\nВозврат")
+                .build(),
+            TransactionBuilder::new().id(1).build(),
+        ];
+        let path = temp_db("roundtrip");
+
+        assert_eq!(dump_as_sqlite(&path, &txs).unwrap(), txs.len());
+        // Повторный экспорт заменяет таблицу, а не дописывает в неё.
+        dump_as_sqlite(&path, &txs).unwrap();
+        let got = parse_from_sqlite(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(got.unwrap(), txs);
+    }
+
+    #[test]
+    fn test_value_out_of_range() {
+        let path = temp_db("overflow");
+        let txs = [TransactionBuilder::new().amount(u64::MAX).build()];
+
+        let got = dump_as_sqlite(&path, &txs);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(got, Err(DumpError::OutputError)));
+        assert!(matches!(
+            parse_from_sqlite(&temp_db("missing")),
+            Err(ParseError::IOError(_))
+        ));
+    }
+}
@@ -1,27 +1,61 @@
-use crate::error::{self, DumpError, ParseError};
-use crate::types::{Transaction, TxStatus, TxType};
-use crate::{parser, utils};
-use core::fmt;
+use crate::error::{self, DumpError, ParseError, TextBlockError};
+use crate::parser::{self, DumpOptions};
+use crate::types::{FIELD_NAMES, Transaction, TxStatus, TxType};
+use crate::utils;
 use std::collections::HashMap;
-use std::{
-    io::{self, BufRead},
-    str::FromStr,
-};
+use std::io::{self, Write};
 
 trait Validator {
     fn is_valid(&self) -> bool;
 }
 
-static REQUIRED_FIELDS: &[&str] = &[
-    "TX_ID",
-    "TX_TYPE",
-    "FROM_USER_ID",
-    "TO_USER_ID",
-    "AMOUNT",
-    "TIMESTAMP",
-    "STATUS",
-    "DESCRIPTION",
-];
+// Разделитель имени поля и значения по умолчанию.
+const DEFAULT_SEPARATOR: &str = ": ";
+
+/// Параметры текстового формата.
+///
+/// По умолчанию имя поля и значение разделяются `": "`, как в описании формата.
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    /// Разделитель между именем поля и значением, например `" = "`. Не должен быть пустым.
+    ///
+    /// При записи вставляется как есть. При чтении строка делится по первому вхождению
+    /// разделителя без пробелов по краям, а имя и значение обрезаются, поэтому с `": "`
+    /// читается и `TX_ID:1`, а описание, содержащее разделитель, не ломает разбор.
+    pub separator: String,
+    /// Максимальная ширина значения `DESCRIPTION` (вместе с кавычками) при записи.
+    ///
+    /// Более длинное значение переносится: строка заканчивается `\`, а продолжение
+    /// пишется на следующей строке после префикса `|`. Escape-последовательности вида
+    /// `\n` не разрываются. При чтении строки продолжения склеиваются всегда, независимо
+    /// от этого параметра. `None` (по умолчанию) — без переносов.
+    pub wrap_description: Option<usize>,
+    /// Принимать `TX_TYPE` и `STATUS` только в верхнем регистре. По умолчанию
+    /// регистр не важен: `deposit` и `Deposit` читаются как `DEPOSIT`.
+    pub strict_case: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            separator: DEFAULT_SEPARATOR.to_string(),
+            wrap_description: None,
+            strict_case: false,
+        }
+    }
+}
+
+// Префикс строки продолжения перенесённого значения.
+const CONTINUATION_PREFIX: &str = "|";
+
+// Поля, которые могут отсутствовать в блоке. Пропущенное описание считается пустым.
+static OPTIONAL_FIELDS: &[&str] = &["DESCRIPTION"];
+
+fn required_fields() -> impl Iterator<Item = &'static &'static str> {
+    FIELD_NAMES
+        .iter()
+        .filter(|field| !OPTIONAL_FIELDS.contains(field))
+}
 
 struct TxWrapper {
     parsed_fields: HashMap<String, String>,
@@ -34,14 +68,17 @@ impl TxWrapper {
         }
     }
 
-    fn from_tx(tx: &Transaction) -> Self {
+    fn from_tx(tx: &Transaction, options: &DumpOptions) -> Self {
         let mut fields = HashMap::<String, String>::with_capacity(8);
         fields.insert("TX_ID".to_string(), tx.id.to_string());
         fields.insert("TX_TYPE".to_string(), tx.r#type.to_string());
         fields.insert("FROM_USER_ID".to_string(), tx.from_user.to_string());
         fields.insert("TO_USER_ID".to_string(), tx.to_user.to_string());
         fields.insert("AMOUNT".to_string(), tx.amount.to_string());
-        fields.insert("TIMESTAMP".to_string(), tx.timestamp.to_string());
+        fields.insert(
+            "TIMESTAMP".to_string(),
+            utils::format_timestamp(tx.timestamp, options),
+        );
         fields.insert("STATUS".to_string(), tx.status.to_string());
         fields.insert("DESCRIPTION".to_string(), tx.description.clone());
 
@@ -62,15 +99,15 @@ impl TxWrapper {
         Ok(())
     }
 
-    fn build(&self) -> Result<Transaction, ParseError> {
+    fn build(&self, strict_case: bool) -> Result<Transaction, ParseError> {
         let id: u64 = self.parsed_fields["TX_ID"].parse()?;
-        let r#type: TxType = self.parsed_fields["TX_TYPE"].parse()?;
+        let r#type = TxType::parse_with_case(&self.parsed_fields["TX_TYPE"], strict_case)?;
         let from_user: u64 = self.parsed_fields["FROM_USER_ID"].parse()?;
         let to_user: u64 = self.parsed_fields["TO_USER_ID"].parse()?;
         let amount: u64 = self.parsed_fields["AMOUNT"].parse()?;
-        let timestamp: u64 = self.parsed_fields["TIMESTAMP"].parse()?;
-        let status: TxStatus = self.parsed_fields["STATUS"].parse()?;
-        let description = utils::parse_quoted_field(&self.parsed_fields["DESCRIPTION"]);
+        let timestamp = utils::parse_timestamp(&self.parsed_fields["TIMESTAMP"])?;
+        let status = TxStatus::parse_with_case(&self.parsed_fields["STATUS"], strict_case)?;
+        let description = self.description();
 
         Ok(Transaction {
             id,
@@ -83,18 +120,138 @@ impl TxWrapper {
             description,
         })
     }
+
+    // Завершает блок: пустой блок (лишние пустые строки) пропускается, а блок без
+    // обязательных полей — ошибка, где бы в файле он ни находился.
+    fn finish(&self, strict_case: bool) -> Result<Option<Transaction>, ParseError> {
+        if self.parsed_fields.is_empty() {
+            return Ok(None);
+        }
+        if !self.is_valid() {
+            let missing: Vec<&str> = required_fields()
+                .filter(|field| !self.parsed_fields.contains_key(**field))
+                .copied()
+                .collect();
+            return Err(ParseError::InvalidFormat(format!(
+                "incomplete record, missing fields: {}",
+                missing.join(", ")
+            )));
+        }
+        self.build(strict_case).map(Some)
+    }
+
+    fn description(&self) -> String {
+        self.parsed_fields
+            .get("DESCRIPTION")
+            .map(|value| unescape_description(&utils::parse_quoted_field(value)))
+            .unwrap_or_default()
+    }
+
+    fn block_error(block: usize, field: &str, value: &str, reason: String) -> TextBlockError {
+        TextBlockError {
+            block,
+            field: Some(field.to_string()),
+            value: value.to_string(),
+            reason,
+        }
+    }
+
+    fn check_u64(&self, block: usize, name: &str) -> Result<u64, TextBlockError> {
+        let value = &self.parsed_fields[name];
+        value.parse().map_err(|err: std::num::ParseIntError| {
+            Self::block_error(block, name, value, err.to_string())
+        })
+    }
+
+    fn check_timestamp(&self, block: usize) -> Result<u64, TextBlockError> {
+        let value = &self.parsed_fields["TIMESTAMP"];
+        utils::parse_timestamp(value).map_err(|err| {
+            let reason = match err {
+                ParseError::InvalidFormat(msg) => msg,
+                other => format!("{:?}", other),
+            };
+            Self::block_error(block, "TIMESTAMP", value, reason)
+        })
+    }
+
+    fn check_enum<T>(
+        &self,
+        block: usize,
+        name: &str,
+        parse: impl FnOnce(&str) -> Result<T, ParseError>,
+    ) -> Result<T, TextBlockError> {
+        let value = &self.parsed_fields[name];
+        parse(value).map_err(|err| {
+            let reason = match err {
+                ParseError::InvalidFormat(msg) => msg,
+                other => format!("{:?}", other),
+            };
+            Self::block_error(block, name, value, reason)
+        })
+    }
+
+    // Проверяет значения полей блока по отдельности, сообщая, какое поле не прошло проверку.
+    fn validate(&self, block: usize, strict_case: bool) -> Result<Transaction, TextBlockError> {
+        if let Some(missing) =
+            required_fields().find(|field| !self.parsed_fields.contains_key(**field))
+        {
+            return Err(Self::block_error(
+                block,
+                missing,
+                "",
+                "missing field".to_string(),
+            ));
+        }
+
+        Ok(Transaction {
+            id: self.check_u64(block, "TX_ID")?,
+            r#type: self.check_enum(block, "TX_TYPE", |value| {
+                TxType::parse_with_case(value, strict_case)
+            })?,
+            from_user: self.check_u64(block, "FROM_USER_ID")?,
+            to_user: self.check_u64(block, "TO_USER_ID")?,
+            amount: self.check_u64(block, "AMOUNT")?,
+            timestamp: self.check_timestamp(block)?,
+            status: self.check_enum(block, "STATUS", |value| {
+                TxStatus::parse_with_case(value, strict_case)
+            })?,
+            description: self.description(),
+        })
+    }
 }
 
-fn dump_txw_as_text(txw: &TxWrapper, writer: &mut impl io::Write) -> Result<(), error::DumpError> {
-    REQUIRED_FIELDS.iter().try_for_each(|s| {
+// Имена полей дополняются пробелами до ширины `name_width`; при нулевой ширине
+// получается обычный вид `ИМЯ: значение`. Описание переносится по ширине `wrap`.
+// Каждая строка заканчивается `eol`.
+fn dump_txw_as_text(
+    txw: &TxWrapper,
+    writer: &mut impl io::Write,
+    separator: &str,
+    name_width: usize,
+    wrap: Option<usize>,
+    eol: &str,
+) -> Result<(), error::DumpError> {
+    FIELD_NAMES.iter().try_for_each(|s| {
         let Some(val) = txw.parsed_fields.get(*s) else {
             return Err(DumpError::InternalError);
         };
         if *s == "DESCRIPTION" {
-            writeln!(writer, "{}: {}", s, utils::wrap_with_quotes(val))?;
+            let value = utils::wrap_with_quotes(&escape_description(val));
+            let parts = match wrap {
+                Some(width) => wrap_value(&value, width),
+                None => vec![value],
+            };
+            write!(writer, "{:<name_width$}{}", s, separator)?;
+            for (index, part) in parts.iter().enumerate() {
+                if index > 0 {
+                    write!(writer, "\\{}  {}", eol, CONTINUATION_PREFIX)?;
+                }
+                write!(writer, "{}", part)?;
+            }
+            write!(writer, "{}", eol)?;
             Ok(())
         } else {
-            writeln!(writer, "{}: {}", s, val)?;
+            write!(writer, "{:<name_width$}{}{}{}", s, separator, val, eol)?;
             Ok(())
         }
     })?;
@@ -103,68 +260,260 @@ fn dump_txw_as_text(txw: &TxWrapper, writer: &mut impl io::Write) -> Result<(),
 
 impl Validator for TxWrapper {
     fn is_valid(&self) -> bool {
-        REQUIRED_FIELDS
-            .iter()
-            .all(|required_field| self.parsed_fields.contains_key(*required_field))
+        required_fields().all(|required_field| self.parsed_fields.contains_key(*required_field))
     }
 }
 
-impl FromStr for TxType {
-    type Err = ParseError;
+// Делит строку `ИМЯ<разделитель>значение` по первому вхождению разделителя
+// (без пробелов по его краям, если он состоит не только из них).
+fn split_field<'a>(line: &'a str, separator: &str) -> Option<(&'a str, &'a str)> {
+    let trimmed = separator.trim();
+    let separator = if trimmed.is_empty() {
+        separator
+    } else {
+        trimmed
+    };
+    line.split_once(separator)
+        .map(|(name, value)| (name.trim(), value.trim()))
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "DEPOSIT" => Ok(TxType::Deposit),
-            "TRANSFER" => Ok(TxType::Transfer),
-            "WITHDRAWAL" => Ok(TxType::Withdrawal),
-            _ => Err(ParseError::InvalidFormat("unknown tx type".to_string())),
+// Делит экранированное значение на части не длиннее `width` символов, не разрывая
+// escape-последовательности `\x`, поэтому часть может быть длиннее при `width < 2`.
+fn wrap_value(value: &str, width: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let mut unit = String::from(c);
+        if c == '\\'
+            && let Some(next) = chars.next()
+        {
+            unit.push(next);
+        }
+        let unit_len = unit.chars().count();
+        if current_len + unit_len > width && !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+            current_len = 0;
         }
+        current.push_str(&unit);
+        current_len += unit_len;
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
     }
+    parts
 }
 
-impl FromStr for TxStatus {
-    type Err = ParseError;
+// Склеивает перенесённые значения (см. `TextOptions::wrap_description`): строка,
+// которая заканчивается нечётным числом `\`, продолжается следующей строкой, если та
+// начинается с `|`. Последний `\` и префикс отбрасываются, пробелы вокруг места
+// переноса сохраняются. Иначе строка возвращается как есть.
+fn join_continuations<I: Iterator<Item = io::Result<String>>>(
+    lines: I,
+) -> impl Iterator<Item = io::Result<String>> {
+    let mut lines = lines.peekable();
+    std::iter::from_fn(move || {
+        let mut line = match lines.next()? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err)),
+        };
+        loop {
+            let end = line.trim_end().len();
+            let backslashes = line[..end].chars().rev().take_while(|c| *c == '\\').count();
+            if backslashes % 2 == 0 {
+                break;
+            }
+            let Some(Ok(next)) = lines.peek() else {
+                break;
+            };
+            let Some(rest) = next.trim_start().strip_prefix(CONTINUATION_PREFIX) else {
+                break;
+            };
+            let rest = rest.trim_end().to_string();
+            line.truncate(end - 1);
+            line.push_str(&rest);
+            lines.next();
+        }
+        Some(Ok(line))
+    })
+}
+
+// Экранирует в описании символы, ломающие построчный формат: `\`, `:` и переводы строк.
+fn escape_description(description: &str) -> String {
+    let mut result = String::with_capacity(description.len());
+    for c in description.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            ':' => result.push_str("\\:"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "SUCCESS" => Ok(TxStatus::Success),
-            "FAILURE" => Ok(TxStatus::Failure),
-            "PENDING" => Ok(TxStatus::Pending),
-            _ => Err(ParseError::InvalidFormat("unknown tx status".to_string())),
+// Обратное преобразование к escape_description. Неизвестные последовательности
+// оставляются как есть, чтобы не ломать описания со случайными обратными слешами.
+fn unescape_description(description: &str) -> String {
+    let mut result = String::with_capacity(description.len());
+    let mut chars = description.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
         }
+        match chars.peek() {
+            Some('\\') => result.push('\\'),
+            Some(':') => result.push(':'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            _ => {
+                result.push(c);
+                continue;
+            }
+        }
+        chars.next();
     }
+    result
 }
 
 fn parse_lines<I: Iterator<Item = io::Result<String>>>(
     lines: I,
+    options: &TextOptions,
+    progress: &mut parser::Progress,
 ) -> Result<Vec<Transaction>, ParseError> {
     let mut result: Vec<Transaction> = Vec::new();
     let mut current_tx = TxWrapper::new();
     for line in lines {
         let l = line?.trim().to_string();
+        if l.starts_with('#') {
+            continue;
+        }
         if l.is_empty() {
-            if !current_tx.is_valid() {
-                current_tx = TxWrapper::new();
-                continue;
+            let block = std::mem::replace(&mut current_tx, TxWrapper::new());
+            if let Some(tx) = block.finish(options.strict_case)? {
+                result.push(tx);
+                progress.tick();
             }
-            result.push(current_tx.build()?);
             continue;
         }
-        let parts: Vec<&str> = l.split(':').map(|s| s.trim()).collect();
-        if parts.len() != 2 {
+        let Some((name, value)) = split_field(&l, &options.separator) else {
             return Err(ParseError::InvalidFormat(
                 "invalid field format".to_string(),
             ));
-        }
-        current_tx.apply_field(parts[0], parts[1])?;
+        };
+        current_tx.apply_field(name, value)?;
     }
 
-    if current_tx.is_valid() {
-        result.push(current_tx.build()?);
+    if let Some(tx) = current_tx.finish(options.strict_case)? {
+        result.push(tx);
+        progress.tick();
     }
     Ok(result)
 }
 
+// Состояние текущего блока в режиме сбора ошибок.
+struct CollectState {
+    result: Vec<Transaction>,
+    errors: Vec<TextBlockError>,
+    block: usize,
+    current_tx: TxWrapper,
+    current_error: Option<TextBlockError>,
+    in_block: bool,
+}
+
+impl CollectState {
+    fn apply_line(&mut self, line: &str) {
+        self.in_block = true;
+        if self.current_error.is_some() {
+            return;
+        }
+        let Some((name, value)) = split_field(line, DEFAULT_SEPARATOR) else {
+            self.current_error = Some(TextBlockError {
+                block: self.block,
+                field: None,
+                value: line.to_string(),
+                reason: "invalid field format".to_string(),
+            });
+            return;
+        };
+        if self.current_tx.apply_field(name, value).is_err() {
+            self.current_error = Some(TxWrapper::block_error(
+                self.block,
+                name,
+                value,
+                "duplicate field".to_string(),
+            ));
+        }
+    }
+
+    fn finish_block(&mut self) {
+        if !self.in_block {
+            return;
+        }
+        let current_tx = std::mem::replace(&mut self.current_tx, TxWrapper::new());
+        let outcome = match self.current_error.take() {
+            Some(err) => Err(err),
+            // режим сбора ошибок читает с параметрами по умолчанию, регистр не важен
+            None => current_tx.validate(self.block, false),
+        };
+        match outcome {
+            Ok(tx) => self.result.push(tx),
+            Err(err) => self.errors.push(err),
+        }
+        self.block += 1;
+        self.in_block = false;
+    }
+}
+
+fn parse_lines_collect<I: Iterator<Item = io::Result<String>>>(
+    lines: I,
+) -> Result<(Vec<Transaction>, Vec<TextBlockError>), ParseError> {
+    let mut state = CollectState {
+        result: Vec::new(),
+        errors: Vec::new(),
+        block: 0,
+        current_tx: TxWrapper::new(),
+        current_error: None,
+        in_block: false,
+    };
+    for line in lines {
+        let l = line?.trim().to_string();
+        if l.starts_with('#') {
+            continue;
+        }
+        if l.is_empty() {
+            state.finish_block();
+        } else {
+            state.apply_line(&l);
+        }
+    }
+    state.finish_block();
+    Ok((state.result, state.errors))
+}
+
+/// Читает транзакции из текстового формата, не прерываясь на ошибочных блоках.
+///
+/// Каждый блок проверяется отдельно: если значение какого-либо поля не удаётся разобрать,
+/// поле отсутствует или встречается дважды, блок пропускается, а в список ошибок
+/// добавляется [`TextBlockError`] с именем поля, его значением и причиной.
+///
+/// # Аргументы
+///
+/// * `reader` - Источник данных, реализующий [`std::io::Read`].
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`] только при ошибке ввода-вывода при чтении из `reader`.
+pub fn parse_from_text_collect(
+    reader: &mut impl io::Read,
+) -> Result<(Vec<Transaction>, Vec<TextBlockError>), ParseError> {
+    let lines = join_continuations(utils::lines_without_bom(reader));
+    parse_lines_collect(lines)
+}
+
 /// Читает и парсит транзакции из текстового формата.
 ///
 /// # Аргументы
@@ -179,28 +528,20 @@ fn parse_lines<I: Iterator<Item = io::Result<String>>>(
 /// * Формат данных некорректен.
 /// * Возникла ошибка ввода-вывода при чтении из `reader`.
 fn parse_from_text(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
-    let lines = io::BufReader::new(reader).lines();
-    parse_lines(lines)
+    parse_from_text_with(reader, &TextOptions::default())
 }
 
-impl fmt::Display for TxType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Deposit => write!(f, "DEPOSIT"),
-            Self::Transfer => write!(f, "TRANSFER"),
-            Self::Withdrawal => write!(f, "WITHDRAWAL"),
-        }
-    }
-}
-
-impl fmt::Display for TxStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Success => write!(f, "SUCCESS"),
-            Self::Failure => write!(f, "FAILURE"),
-            Self::Pending => write!(f, "PENDING"),
-        }
-    }
+/// Читает транзакции из текстового формата с заданными параметрами (см. [`TextOptions`]).
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`] в тех же случаях, что и [`crate::parse`] для текстового формата.
+pub fn parse_from_text_with(
+    reader: &mut impl io::Read,
+    options: &TextOptions,
+) -> Result<Vec<Transaction>, ParseError> {
+    let lines = join_continuations(utils::lines_without_bom(reader));
+    parse_lines(lines, options, &mut parser::Progress::none())
 }
 
 /// Сериализует список транзакций в текстовый формат, записывая результат в `writer`.
@@ -211,6 +552,8 @@ impl fmt::Display for TxStatus {
 ///   Должен реализовывать трейт [`std::io::Write`].
 /// * `transactions` - Слайс транзакций для записи.
 ///
+/// Возвращает количество записанных транзакций.
+///
 /// # Ошибки
 ///
 /// Возвращает [`DumpError`], если:
@@ -218,16 +561,97 @@ impl fmt::Display for TxStatus {
 fn dump_as_text(
     writer: &mut impl io::Write,
     transactions: &[Transaction],
-) -> Result<(), DumpError> {
+) -> Result<usize, DumpError> {
+    dump_as_text_with(writer, transactions, &TextOptions::default())
+}
+
+/// Сериализует транзакции в текстовый формат с заданными параметрами (см. [`TextOptions`]).
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_text_with(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    options: &TextOptions,
+) -> Result<usize, DumpError> {
+    dump_as_text_with_options(
+        writer,
+        transactions,
+        options,
+        &DumpOptions::default(),
+        &mut parser::Progress::none(),
+    )
+}
+
+pub(crate) fn dump_as_text_with_options(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    text_options: &TextOptions,
+    options: &DumpOptions,
+    progress: &mut parser::Progress,
+) -> Result<usize, DumpError> {
+    // Каждое поле пишется отдельным write!, поэтому без буфера это была бы
+    // отдельная запись в поток на каждую строку.
+    let eol = options.line_ending.as_str();
+    let mut writer = io::BufWriter::new(writer);
+    utils::write_header_comment(&mut writer, options)?;
     let mut iter = transactions.iter().peekable();
     while let Some(tx) = iter.next() {
-        let txw = TxWrapper::from_tx(tx);
-        dump_txw_as_text(&txw, writer)?;
+        let txw = TxWrapper::from_tx(tx, options);
+        dump_txw_as_text(
+            &txw,
+            &mut writer,
+            &text_options.separator,
+            0,
+            text_options.wrap_description,
+            eol,
+        )?;
         if iter.peek().is_some() {
-            writeln!(writer)?;
+            write!(writer, "{}", eol)?;
         }
+        progress.tick();
     }
-    Ok(())
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+/// Сериализует транзакции в текстовый формат для чтения человеком: имена полей
+/// выравниваются по ширине самого длинного, так что двоеточия стоят в одном столбце.
+///
+/// Если `rule` включён, записи разделяются строкой `---` вместо пустой строки.
+/// Такой вывод уже не читается парсером текстового формата; без `rule` он остаётся
+/// корректным текстовым форматом, так как пробелы вокруг имени поля игнорируются.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_text_pretty(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    rule: bool,
+) -> Result<usize, DumpError> {
+    let name_width = FIELD_NAMES
+        .iter()
+        .map(|field| field.len())
+        .max()
+        .unwrap_or(0);
+    let options = DumpOptions::default();
+    let mut writer = io::BufWriter::new(writer);
+    let mut iter = transactions.iter().peekable();
+    while let Some(tx) = iter.next() {
+        let txw = TxWrapper::from_tx(tx, &options);
+        dump_txw_as_text(&txw, &mut writer, DEFAULT_SEPARATOR, name_width, None, "\n")?;
+        if iter.peek().is_some() {
+            writeln!(writer, "{}", if rule { "---" } else { "" })?;
+        }
+    }
+    writer.flush()?;
+    Ok(transactions.len())
 }
 
 pub(crate) struct TextParser;
@@ -240,14 +664,205 @@ impl parser::Parser for TextParser {
     fn dump(
         writer: &mut impl io::Write,
         transactions: &[Transaction],
-    ) -> Result<(), error::DumpError> {
+    ) -> Result<usize, error::DumpError> {
         dump_as_text(writer, transactions)
     }
+
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut parser::Progress,
+    ) -> Result<Vec<Transaction>, error::ParseError> {
+        let lines = join_continuations(utils::lines_without_bom(reader));
+        parse_lines(lines, &TextOptions::default(), progress)
+    }
+
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+        progress: &mut parser::Progress,
+    ) -> Result<usize, error::DumpError> {
+        dump_as_text_with_options(
+            writer,
+            transactions,
+            &TextOptions::default(),
+            &DumpOptions::default(),
+            progress,
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::fee_and_reversal;
+
+    #[test]
+    fn test_custom_separator_roundtrip() {
+        let options = TextOptions {
+            separator: " = ".to_string(),
+            ..TextOptions::default()
+        };
+        let txs = vec![
+            crate::types::TransactionBuilder::new()
+                .id(1)
+                .description("x = y, a=b")
+                .build(),
+            crate::types::TransactionBuilder::new().id(2).build(),
+        ];
+        let mut buffer = Vec::new();
+
+        dump_as_text_with(&mut buffer, &txs, &options).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("TX_ID = 1\nTX_TYPE = DEPOSIT\n"));
+        assert!(output.contains("DESCRIPTION = \"x = y, a=b\"\n"));
+
+        assert_eq!(
+            parse_from_text_with(&mut output.as_bytes(), &options).unwrap(),
+            txs
+        );
+        // без пробелов вокруг разделителя строка тоже читается
+        let compact = output.replace(" = ", "=");
+        assert_eq!(
+            parse_from_text_with(&mut compact.as_bytes(), &options).unwrap()[1],
+            txs[1]
+        );
+        assert!(parse_from_text(&mut output.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_strict_case() {
+        let input = "TX_ID: 1\nTX_TYPE: deposit\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                     AMOUNT: 100\nTIMESTAMP: 1672531200000\nSTATUS: Success\n";
+        let strict = TextOptions {
+            strict_case: true,
+            ..TextOptions::default()
+        };
+
+        let lenient = parse_from_text(&mut input.as_bytes()).unwrap();
+        assert_eq!(lenient[0].r#type, TxType::Deposit);
+        assert_eq!(lenient[0].status, TxStatus::Success);
+        assert!(matches!(
+            parse_from_text_with(&mut input.as_bytes(), &strict),
+            Err(ParseError::InvalidFormat(_))
+        ));
+
+        let upper = input
+            .replace("deposit", "DEPOSIT")
+            .replace("Success", "SUCCESS");
+        assert_eq!(
+            parse_from_text_with(&mut upper.as_bytes(), &strict).unwrap(),
+            lenient
+        );
+    }
+
+    #[test]
+    fn test_wrap_description_roundtrip() {
+        let options = TextOptions {
+            wrap_description: Some(20),
+            ..TextOptions::default()
+        };
+        let description = "Payment for services:  invoice #123\nback\\slash   ";
+        let txs = vec![
+            crate::types::TransactionBuilder::new()
+                .id(1)
+                .description(description)
+                .build(),
+            crate::types::TransactionBuilder::new()
+                .id(2)
+                .description("short")
+                .build(),
+        ];
+        let mut buffer = Vec::new();
+
+        dump_as_text_with(&mut buffer, &txs, &options).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(
+            r#"DESCRIPTION: "Payment for service\
+  |s\:  invoice #123\nb\
+  |ack\\slash   "
+"#
+        ));
+        assert!(output.contains("DESCRIPTION: \"short\"\n"));
+        for line in output.lines() {
+            let value = line
+                .strip_prefix("DESCRIPTION: ")
+                .or_else(|| line.strip_prefix("  |"))
+                .unwrap_or("");
+            assert!(
+                value.trim_end_matches('\\').chars().count() <= 20,
+                "{}",
+                line
+            );
+        }
+
+        let parsed = parse_from_text(&mut output.as_bytes()).unwrap();
+        assert_eq!(parsed[0].description, description);
+        assert_eq!(parsed, txs);
+    }
+
+    #[test]
+    fn test_trailing_backslash_without_continuation() {
+        // Описание без кавычек, заканчивающееся `\`, не склеивается со следующим полем.
+        let input = "DESCRIPTION: C:\\temp\\\nTX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\n\
+                     TO_USER_ID: 1\nAMOUNT: 1\nTIMESTAMP: 1\nSTATUS: SUCCESS\n";
+        let txs = parse_from_text(&mut input.as_bytes()).unwrap();
+        assert_eq!(txs[0].id, 1);
+        assert_eq!(txs[0].description, "C:\\temp\\");
+    }
+
+    #[test]
+    fn test_incomplete_trailing_block() {
+        let complete = "TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 2\n\
+                        AMOUNT: 10\nTIMESTAMP: 1633036800000\nSTATUS: SUCCESS\n\
+                        DESCRIPTION: \"x\"\n";
+        let partial = "TX_ID: 2\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 2\n\
+                       AMOUNT: 10\nTIMESTAMP: 1633036800000\nDESCRIPTION: \"y\"";
+
+        let input = format!("{}\n{}", complete, partial);
+        assert!(matches!(
+            parse_from_text(&mut input.as_bytes()),
+            Err(ParseError::InvalidFormat(msg)) if msg.ends_with("missing fields: STATUS")
+        ));
+
+        // неполный блок в середине файла тоже ошибка, а не пропущенная запись
+        let input = format!("{}\n\n{}", partial, complete);
+        assert!(matches!(
+            parse_from_text(&mut input.as_bytes()),
+            Err(ParseError::InvalidFormat(msg)) if msg.ends_with("missing fields: STATUS")
+        ));
+
+        let input = format!("{}\n\n", complete);
+        assert_eq!(parse_from_text(&mut input.as_bytes()).unwrap().len(), 1);
+        assert_eq!(parse_from_text(&mut complete.as_bytes()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_dump_pretty_aligns_colons() {
+        let txs = [
+            crate::types::TransactionBuilder::new()
+                .id(1)
+                .description("a: b")
+                .build(),
+            crate::types::TransactionBuilder::new().id(2).build(),
+        ];
+        let mut buffer = Vec::new();
+        assert_eq!(dump_as_text_pretty(&mut buffer, &txs, true).unwrap(), 2);
+        let output = String::from_utf8(buffer).unwrap();
+
+        let records: Vec<&str> = output.split("---\n").collect();
+        assert_eq!(records.len(), 2);
+        for record in records {
+            let columns: Vec<usize> = record.lines().map(|l| l.find(':').unwrap()).collect();
+            assert_eq!(columns.len(), FIELD_NAMES.len());
+            assert!(columns.iter().all(|&c| c == "FROM_USER_ID".len()));
+        }
+        assert!(output.contains("TX_ID       : 1\n"));
+
+        // без разделителя вывод читается обычным парсером
+        let mut buffer = Vec::new();
+        dump_as_text_pretty(&mut buffer, &txs, false).unwrap();
+        assert_eq!(parse_from_text(&mut buffer.as_slice()).unwrap(), txs);
+    }
 
     #[test]
     fn test_parse_one_valid_transaction() {
@@ -336,4 +951,204 @@ mod tests {
 
         assert!(got.is_err());
     }
+
+    #[test]
+    fn test_fee_and_reversal_roundtrip() {
+        let txs = fee_and_reversal();
+        let mut buffer = Vec::new();
+
+        dump_as_text(&mut buffer, &txs).unwrap();
+        let got = parse_from_text(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_collect_errors_skips_invalid_block() {
+        let input = r##"TX_ID: 1001
+TX_TYPE: DEPOSIT
+FROM_USER_ID: 0
+TO_USER_ID: 501
+AMOUNT: 50000
+TIMESTAMP: 1672531200000
+STATUS: SUCCESS
+DESCRIPTION: "Initial account funding"
+
+TX_ID: 1002
+TX_TYPE: TRANSFER
+FROM_USER_ID: 501
+TO_USER_ID: 502
+AMOUNT: lots
+TIMESTAMP: 1672534800000
+STATUS: FAILURE
+DESCRIPTION: "Payment"
+"##;
+
+        let (txs, errors) = parse_from_text_collect(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].id, 1001);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block, 1);
+        assert_eq!(errors[0].field.as_deref(), Some("AMOUNT"));
+        assert_eq!(errors[0].value, "lots");
+        assert!(!errors[0].reason.is_empty());
+    }
+
+    fn roundtrip_description(description: &str) -> String {
+        let tx = crate::types::TransactionBuilder::new()
+            .description(description)
+            .build();
+        let mut buffer = Vec::new();
+
+        dump_as_text(&mut buffer, &[tx.clone(), tx]).unwrap();
+        let got = parse_from_text(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got.len(), 2);
+        got[0].description.clone()
+    }
+
+    #[test]
+    fn test_description_with_colon_and_newline() {
+        let description = "Payment: urgent\nline2";
+
+        assert_eq!(roundtrip_description(description), description);
+        assert_eq!(roundtrip_description("a\n\nb\r\n"), "a\n\nb\r\n");
+    }
+
+    #[test]
+    fn test_description_with_backslashes() {
+        let description = r"C:\path\n is not a newline \";
+
+        assert_eq!(roundtrip_description(description), description);
+        assert_eq!(escape_description("a:b\\c\nd"), r"a\:b\\c\nd");
+    }
+
+    #[test]
+    fn test_numeric_timestamp_roundtrip() {
+        let tx = crate::types::TransactionBuilder::new()
+            .timestamp(1672531200123)
+            .build();
+        let mut buffer = Vec::new();
+
+        dump_as_text_with(
+            &mut buffer,
+            std::slice::from_ref(&tx),
+            &TextOptions::default(),
+        )
+        .unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer).contains("TIMESTAMP: 1672531200123\n"));
+        assert_eq!(parse_from_text(&mut buffer.as_slice()).unwrap(), vec![tx]);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_iso_timestamp_roundtrip() {
+        let tx = crate::types::TransactionBuilder::new()
+            .timestamp(1672531200123)
+            .build();
+        let options = DumpOptions {
+            human_timestamps: true,
+            ..DumpOptions::default()
+        };
+        let mut buffer = Vec::new();
+
+        dump_as_text_with_options(
+            &mut buffer,
+            std::slice::from_ref(&tx),
+            &TextOptions::default(),
+            &options,
+            &mut parser::Progress::none(),
+        )
+        .unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer).contains("TIMESTAMP: 2023-01-01T00:00:00.123Z\n"));
+        assert_eq!(parse_from_text(&mut buffer.as_slice()).unwrap(), vec![tx]);
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let clean = "TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                     AMOUNT: 50000\nTIMESTAMP: 1672531200000\nSTATUS: SUCCESS\n\
+                     DESCRIPTION: \"Initial funding\"\n\nTX_ID: 1002\nTX_TYPE: FEE\n\
+                     FROM_USER_ID: 501\nTO_USER_ID: 0\nAMOUNT: 10\nTIMESTAMP: 1672531200001\n\
+                     STATUS: PENDING\nDESCRIPTION: \"Fee\"\n";
+        let windows = format!("\u{feff}{}", clean.replace('\n', "\r\n"));
+
+        let expected = parse_from_text(&mut clean.as_bytes()).unwrap();
+        let got = parse_from_text(&mut windows.as_bytes()).unwrap();
+
+        assert_eq!(expected.len(), 2);
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_missing_description_defaults_to_empty() {
+        let input = "TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                     AMOUNT: 50000\nTIMESTAMP: 1672531200000\nSTATUS: SUCCESS\n";
+
+        let got = parse_from_text(&mut input.as_bytes()).unwrap();
+        let (collected, errors) = parse_from_text_collect(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].description, "");
+        assert_eq!(collected, got);
+        assert!(errors.is_empty());
+
+        let mut buffer = Vec::new();
+        dump_as_text(&mut buffer, &got).unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("DESCRIPTION: \"\"\n")
+        );
+    }
+
+    #[test]
+    fn test_comment_lines() {
+        let input = "# exported today\n\
+                     \x20  # second comment\n\
+                     TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                     # inside a block\n\
+                     AMOUNT: 50000\nTIMESTAMP: 1672531200000\nSTATUS: SUCCESS\n\
+                     DESCRIPTION: \"# not a comment\"\n";
+
+        let got = parse_from_text(&mut input.as_bytes()).unwrap();
+        let (collected, errors) = parse_from_text_collect(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].amount, 50000);
+        assert_eq!(got[0].description, "# not a comment");
+        assert_eq!(collected, got);
+        assert!(errors.is_empty());
+
+        let options = DumpOptions {
+            header_comment: Some("YPBank export".to_string()),
+            ..DumpOptions::default()
+        };
+        let mut buffer = Vec::new();
+        dump_as_text_with_options(
+            &mut buffer,
+            &got,
+            &TextOptions::default(),
+            &options,
+            &mut parser::Progress::none(),
+        )
+        .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("# YPBank export\nTX_ID: 1001\n"));
+        assert_eq!(parse_from_text(&mut output.as_bytes()).unwrap(), got);
+    }
+
+    #[test]
+    fn test_missing_required_field_is_skipped() {
+        let input = "TX_ID: 1001\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 501\n\
+                     TIMESTAMP: 1672531200000\nSTATUS: SUCCESS\nDESCRIPTION: \"no amount\"\n";
+
+        let (_, errors) = parse_from_text_collect(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("AMOUNT"));
+    }
 }
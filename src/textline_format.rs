@@ -0,0 +1,319 @@
+use crate::error::{self, DumpError, ParseError};
+use crate::parser;
+use crate::types::{FIELD_NAMES, Transaction, TxStatus, TxType};
+use crate::utils;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+// Заключает значение в двойные кавычки в стиле shell: `\` и `"` экранируются обратной
+// косой чертой. Переводы строк записываются как `\n` и `\r`, чтобы запись не выходила
+// за пределы одной строки.
+fn quote(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Делит строку на пары `ИМЯ=значение`, разделённые пробельными символами. Значение
+// может быть записано как есть (до ближайшего пробела) или в двойных кавычках.
+fn split_pairs(line: &str) -> Result<Vec<(String, String)>, ParseError> {
+    let mut pairs = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(pairs);
+        }
+
+        let mut name = String::new();
+        loop {
+            match chars.next() {
+                Some('=') => break,
+                Some(c) if !c.is_whitespace() => name.push(c),
+                _ => {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "expected NAME=value, got '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => value.push('\n'),
+                        Some('r') => value.push('\r'),
+                        Some(c @ ('\\' | '"')) => value.push(c),
+                        _ => {
+                            return Err(ParseError::InvalidFormat(format!(
+                                "invalid escape in {}",
+                                name
+                            )));
+                        }
+                    },
+                    Some(c) => value.push(c),
+                    None => {
+                        return Err(ParseError::InvalidFormat(format!(
+                            "unclosed quotes in {}",
+                            name
+                        )));
+                    }
+                }
+            }
+            if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                return Err(ParseError::InvalidFormat(format!(
+                    "unexpected character after closing quote in {}",
+                    name
+                )));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+        pairs.push((name, value));
+    }
+}
+
+fn parse_line(line: &str) -> Result<Transaction, ParseError> {
+    let mut fields = HashMap::with_capacity(FIELD_NAMES.len());
+    for (name, value) in split_pairs(line)? {
+        if !FIELD_NAMES.contains(&name.as_str()) {
+            return Err(ParseError::InvalidFormat(format!("unknown field {}", name)));
+        }
+        if fields.contains_key(&name) {
+            return Err(ParseError::InvalidFormat(format!(
+                "duplicate field {}",
+                name
+            )));
+        }
+        fields.insert(name, value);
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("missing field {}", name)))
+    };
+    let number = |name: &str| {
+        let value = field(name)?;
+        value
+            .parse::<u64>()
+            .map_err(|_| ParseError::InvalidFormat(format!("{}: invalid number '{}'", name, value)))
+    };
+
+    Ok(Transaction {
+        id: number("TX_ID")?,
+        r#type: field("TX_TYPE")?.parse::<TxType>()?,
+        from_user: number("FROM_USER_ID")?,
+        to_user: number("TO_USER_ID")?,
+        amount: number("AMOUNT")?,
+        timestamp: utils::parse_timestamp(field("TIMESTAMP")?)?,
+        status: field("STATUS")?.parse::<TxStatus>()?,
+        // Описание, как и в многострочном текстовом формате, необязательно.
+        description: fields.get("DESCRIPTION").cloned().unwrap_or_default(),
+    })
+}
+
+/// Читает транзакции из однострочного текстового формата: по одной транзакции на строку
+/// вида `TX_ID=1001 TX_TYPE=DEPOSIT ... DESCRIPTION="..."`.
+///
+/// Пустые строки пропускаются. Значение поля записывается как есть или в двойных
+/// кавычках; внутри кавычек `\"`, `\\`, `\n` и `\r` обозначают кавычку, обратную косую
+/// черту и переводы строк. Порядок полей не важен, `DESCRIPTION` можно опустить.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Строка не разбивается на пары `ИМЯ=значение`, поле неизвестно, повторяется
+///   или отсутствует, либо значение некорректно ([`ParseError::InvalidFormat`] с номером строки).
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+pub fn parse_from_text_oneline(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
+    parse_from_text_oneline_impl(reader, &mut parser::Progress::none())
+}
+
+fn parse_from_text_oneline_impl(
+    reader: &mut impl io::Read,
+    progress: &mut parser::Progress,
+) -> Result<Vec<Transaction>, ParseError> {
+    let mut result = Vec::new();
+    for (index, line) in utils::lines_without_bom(reader).enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx = parse_line(&line).map_err(|err| match err {
+            ParseError::InvalidFormat(msg) => {
+                ParseError::InvalidFormat(format!("line {}: {}", index + 1, msg))
+            }
+            err => err,
+        })?;
+        result.push(tx);
+        progress.tick();
+    }
+    Ok(result)
+}
+
+/// Записывает транзакции в однострочный текстовый формат (см. [`parse_from_text_oneline`]).
+/// Описание всегда заключается в кавычки, остальные поля записываются как есть.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+pub fn dump_as_text_oneline(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+) -> Result<usize, DumpError> {
+    dump_as_text_oneline_impl(writer, transactions, &mut parser::Progress::none())
+}
+
+fn dump_as_text_oneline_impl(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+    progress: &mut parser::Progress,
+) -> Result<usize, DumpError> {
+    let mut writer = io::BufWriter::new(writer);
+    for tx in transactions {
+        writeln!(
+            writer,
+            "TX_ID={} TX_TYPE={} FROM_USER_ID={} TO_USER_ID={} AMOUNT={} TIMESTAMP={} \
+             STATUS={} DESCRIPTION={}",
+            tx.id,
+            tx.r#type,
+            tx.from_user,
+            tx.to_user,
+            tx.amount,
+            tx.timestamp,
+            tx.status,
+            quote(&tx.description)
+        )?;
+        progress.tick();
+    }
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+pub(crate) struct TextLineParser;
+
+impl parser::Parser for TextLineParser {
+    fn parse(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_text_oneline(reader)
+    }
+
+    fn dump(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+    ) -> Result<usize, error::DumpError> {
+        dump_as_text_oneline(writer, transactions)
+    }
+
+    fn parse_with_progress(
+        reader: &mut impl io::Read,
+        progress: &mut parser::Progress,
+    ) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_text_oneline_impl(reader, progress)
+    }
+
+    fn dump_with_progress(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+        progress: &mut parser::Progress,
+    ) -> Result<usize, error::DumpError> {
+        dump_as_text_oneline_impl(writer, transactions, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    #[test]
+    fn test_roundtrip() {
+        let txs = vec![
+            TransactionBuilder::new()
+                .id(1001)
+                .to_user(501)
+                .amount(50000)
+                .timestamp(1672531200000)
+                .status(TxStatus::Success)
+                .description("Initial account funding")
+                .build(),
+            TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Transfer)
+                .from_user(501)
+                .to_user(502)
+                .status(TxStatus::Failure)
+                .description("Say \"hi\" to C:\\temp\nnext   line")
+                .build(),
+            TransactionBuilder::new().id(1003).build(),
+        ];
+        let mut buffer = Vec::new();
+
+        assert_eq!(dump_as_text_oneline(&mut buffer, &txs).unwrap(), txs.len());
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output.lines().count(), txs.len());
+        assert!(output.starts_with(
+            "TX_ID=1001 TX_TYPE=DEPOSIT FROM_USER_ID=0 TO_USER_ID=501 AMOUNT=50000 \
+             TIMESTAMP=1672531200000 STATUS=SUCCESS DESCRIPTION=\"Initial account funding\"\n"
+        ));
+
+        assert_eq!(
+            parse_from_text_oneline(&mut output.as_bytes()).unwrap(),
+            txs
+        );
+    }
+
+    #[test]
+    fn test_parse_field_order_and_errors() {
+        let input = "DESCRIPTION=\"a b\" STATUS=PENDING TIMESTAMP=5 AMOUNT=1 \
+                     TO_USER_ID=2 FROM_USER_ID=3 TX_TYPE=FEE TX_ID=4\n\n";
+        let txs = parse_from_text_oneline(&mut input.as_bytes()).unwrap();
+        assert_eq!(txs[0].description, "a b");
+        assert_eq!(txs[0].r#type, TxType::Fee);
+
+        let cases = [
+            ("TX_ID=1 TX_ID=2", "line 1: duplicate field TX_ID"),
+            ("TX_ID=1 FOO=2", "line 1: unknown field FOO"),
+            ("TX_ID", "line 1: expected NAME=value, got 'TX_ID'"),
+            (
+                "DESCRIPTION=\"open",
+                "line 1: unclosed quotes in DESCRIPTION",
+            ),
+            (
+                "DESCRIPTION=\"a\"b",
+                "line 1: unexpected character after closing quote in DESCRIPTION",
+            ),
+            ("TX_ID=x", "line 1: TX_ID: invalid number 'x'"),
+            ("TX_ID=1", "line 1: missing field TX_TYPE"),
+        ];
+        for (input, expected) in cases {
+            assert!(
+                matches!(
+                    parse_from_text_oneline(&mut input.as_bytes()),
+                    Err(ParseError::InvalidFormat(ref msg)) if msg == expected
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use crate::error::{self, DumpError, ParseError};
+use crate::parser;
+use crate::types::{FIELD_NAMES, Transaction, TxStatus, TxType};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+const SECTION_PREFIX: &str = "[transaction.";
+
+// Экранирует строку по правилам базовых строк TOML и оборачивает её в кавычки.
+// Управляющие символы без короткой формы записываются как `\uXXXX`: TOML запрещает
+// их в строках в исходном виде.
+fn escape_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Разбирает значение в кавычках, раскрывая escape-последовательности.
+fn unescape_string(value: &str) -> Result<String, ParseError> {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .filter(|_| value.len() >= 2)
+    else {
+        return Err(ParseError::InvalidFormat(format!(
+            "expected quoted string, got {}",
+            value
+        )));
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('"') => result.push('"'),
+                Some('\\') => result.push('\\'),
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some(kind @ ('u' | 'U')) => {
+                    let len = if kind == 'u' { 4 } else { 8 };
+                    let hex: String = chars.by_ref().take(len).collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .filter(|_| hex.len() == len)
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| {
+                            ParseError::InvalidFormat(format!(
+                                "invalid unicode escape in {}",
+                                value
+                            ))
+                        })?;
+                    result.push(c);
+                }
+                _ => {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "invalid escape sequence in {}",
+                        value
+                    )));
+                }
+            },
+            '"' => {
+                return Err(ParseError::InvalidFormat(format!(
+                    "unescaped quote in {}",
+                    value
+                )));
+            }
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+// Поля одной таблицы `[transaction.N]` в том виде, в каком они записаны в файле.
+struct Section {
+    fields: HashMap<String, String>,
+}
+
+impl Section {
+    fn new() -> Self {
+        Self {
+            fields: HashMap::with_capacity(FIELD_NAMES.len()),
+        }
+    }
+
+    fn apply_field(&mut self, name: &str, value: &str) -> Result<(), ParseError> {
+        if !FIELD_NAMES.contains(&name) {
+            return Err(ParseError::InvalidFormat(format!("unknown field {}", name)));
+        }
+        if self.fields.contains_key(name) {
+            return Err(ParseError::InvalidFormat(format!(
+                "duplicate field {}",
+                name
+            )));
+        }
+        self.fields.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<&str, ParseError> {
+        self.fields
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("missing field {}", name)))
+    }
+
+    fn build(&self) -> Result<Transaction, ParseError> {
+        Ok(Transaction {
+            id: self.get("TX_ID")?.parse()?,
+            r#type: unescape_string(self.get("TX_TYPE")?)?.parse::<TxType>()?,
+            from_user: self.get("FROM_USER_ID")?.parse()?,
+            to_user: self.get("TO_USER_ID")?.parse()?,
+            amount: self.get("AMOUNT")?.parse()?,
+            timestamp: self.get("TIMESTAMP")?.parse()?,
+            status: unescape_string(self.get("STATUS")?)?.parse::<TxStatus>()?,
+            description: unescape_string(self.get("DESCRIPTION")?)?,
+        })
+    }
+}
+
+// Разбирает заголовок таблицы и проверяет, что индексы идут подряд с нуля.
+fn parse_section_header(line: &str, expected: usize) -> Result<(), ParseError> {
+    let index = line
+        .strip_prefix(SECTION_PREFIX)
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| ParseError::InvalidFormat(format!("invalid section header {}", line)))?;
+    let index: usize = index.trim().parse()?;
+    if index != expected {
+        return Err(ParseError::InvalidFormat(format!(
+            "expected section transaction.{}, got transaction.{}",
+            expected, index
+        )));
+    }
+    Ok(())
+}
+
+fn parse_lines<I: Iterator<Item = io::Result<String>>>(
+    lines: I,
+) -> Result<Vec<Transaction>, ParseError> {
+    let mut result: Vec<Transaction> = Vec::new();
+    let mut current: Option<Section> = None;
+    for line in lines {
+        let line = line?;
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') {
+            continue;
+        }
+        if l.starts_with('[') {
+            if let Some(section) = current.take() {
+                result.push(section.build()?);
+            }
+            parse_section_header(l, result.len())?;
+            current = Some(Section::new());
+            continue;
+        }
+        let Some(section) = current.as_mut() else {
+            return Err(ParseError::InvalidFormat(
+                "field outside of transaction section".to_string(),
+            ));
+        };
+        let Some((name, value)) = l.split_once('=') else {
+            return Err(ParseError::InvalidFormat(
+                "invalid field format".to_string(),
+            ));
+        };
+        section.apply_field(name.trim(), value.trim())?;
+    }
+
+    if let Some(section) = current {
+        result.push(section.build()?);
+    }
+    Ok(result)
+}
+
+/// Читает и парсит транзакции из TOML формата.
+///
+/// Каждая транзакция записана отдельной таблицей `[transaction.N]`, где `N` — порядковый
+/// номер транзакции, начиная с нуля.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Формат данных некорректен или индексы таблиц идут не подряд.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+fn parse_from_toml(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
+    let lines = io::BufReader::new(reader).lines();
+    parse_lines(lines)
+}
+
+/// Сериализует список транзакций в TOML формат, по одной таблице на транзакцию.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если произошла ошибка ввода-вывода при записи во `writer`.
+fn dump_as_toml(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+) -> Result<usize, DumpError> {
+    let mut writer = io::BufWriter::new(writer);
+    for (index, tx) in transactions.iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{}{}]", SECTION_PREFIX, index)?;
+        writeln!(writer, "TX_ID = {}", tx.id)?;
+        writeln!(writer, "TX_TYPE = \"{}\"", tx.r#type)?;
+        writeln!(writer, "FROM_USER_ID = {}", tx.from_user)?;
+        writeln!(writer, "TO_USER_ID = {}", tx.to_user)?;
+        writeln!(writer, "AMOUNT = {}", tx.amount)?;
+        writeln!(writer, "TIMESTAMP = {}", tx.timestamp)?;
+        writeln!(writer, "STATUS = \"{}\"", tx.status)?;
+        writeln!(writer, "DESCRIPTION = {}", escape_string(&tx.description))?;
+    }
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+pub(crate) struct TomlParser;
+
+impl parser::Parser for TomlParser {
+    fn parse(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_toml(reader)
+    }
+
+    fn dump(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+    ) -> Result<usize, error::DumpError> {
+        dump_as_toml(writer, transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Transaction> {
+        vec![
+            Transaction {
+                id: 1001,
+                r#type: TxType::Deposit,
+                from_user: 0,
+                to_user: 501,
+                amount: 50000,
+                timestamp: 1672531200000,
+                status: TxStatus::Success,
+                description: "Initial account funding".to_string(),
+            },
+            Transaction {
+                id: 1002,
+                r#type: TxType::Transfer,
+                from_user: 501,
+                to_user: 502,
+                amount: 15000,
+                timestamp: 1672534800000,
+                status: TxStatus::Failure,
+                description: "Invoice \"#123\"\nsecond line\\tail".to_string(),
+            },
+            Transaction {
+                id: 1003,
+                r#type: TxType::Withdrawal,
+                from_user: 502,
+                to_user: 0,
+                amount: 1000,
+                timestamp: 1672538400000,
+                status: TxStatus::Pending,
+                description: String::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let txs = sample();
+        let mut buffer = Vec::new();
+
+        dump_as_toml(&mut buffer, &txs).unwrap();
+        let got = parse_from_toml(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(got, txs);
+    }
+
+    #[test]
+    fn test_dump_escapes_description() {
+        let mut buffer = Vec::new();
+        dump_as_toml(&mut buffer, &sample()[1..2]).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+
+        assert!(dumped.contains(r##"DESCRIPTION = "Invoice \"#123\"\nsecond line\\tail""##));
+        assert_eq!(dumped.lines().count(), 9);
+    }
+
+    #[test]
+    fn test_control_characters() {
+        let mut txs = sample()[..1].to_vec();
+        txs[0].description = "bell\u{7}del\u{7f}".to_string();
+        let mut buffer = Vec::new();
+
+        dump_as_toml(&mut buffer, &txs).unwrap();
+        let dumped = String::from_utf8(buffer.clone()).unwrap();
+
+        assert!(dumped.contains(r#"DESCRIPTION = "bell\u0007del\u007f""#));
+        assert_eq!(parse_from_toml(&mut buffer.as_slice()).unwrap(), txs);
+
+        let upper = dumped.replace(r"\u0007", r"\U00000007");
+        assert_eq!(parse_from_toml(&mut upper.as_bytes()).unwrap(), txs);
+
+        let invalid = dumped.replace(r"\u0007", r"\u07zz");
+        assert!(parse_from_toml(&mut invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_section_indices_are_contiguous() {
+        let mut buffer = Vec::new();
+        dump_as_toml(&mut buffer, &sample()).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+
+        let headers: Vec<&str> = dumped.lines().filter(|l| l.starts_with('[')).collect();
+        assert_eq!(
+            headers,
+            vec!["[transaction.0]", "[transaction.1]", "[transaction.2]"]
+        );
+
+        let gap = dumped.replace("[transaction.1]", "[transaction.5]");
+        assert!(matches!(
+            parse_from_toml(&mut gap.as_bytes()),
+            Err(ParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let input = "[transaction.0]\nTX_ID = 1\nTX_TYPE = \"DEPOSIT\"\n";
+
+        let got = parse_from_toml(&mut input.as_bytes());
+
+        assert!(matches!(got, Err(ParseError::InvalidFormat(_))));
+    }
+}
@@ -3,11 +3,20 @@
 //! Определяет основные структуры и перечисления, используемые для представления
 //! транзакций в системе.
 
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{DumpError, ParseError, UnknownFormatError};
+use crate::utils;
+
 type TxId = u64;
 type UserId = u64;
 
 /// Тип транзакции.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Порядок ([`Ord`]) совпадает с порядком объявления вариантов: `Deposit < Transfer < ... < Reversal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TxType {
     /// Пополнение счета.
     Deposit,
@@ -15,10 +24,16 @@ pub enum TxType {
     Transfer,
     /// Снятие средств со счета.
     Withdrawal,
+    /// Комиссия банка.
+    Fee,
+    /// Отмена (сторнирование) ранее проведённой операции.
+    Reversal,
 }
 
 /// Статус обработки транзакции.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Порядок ([`Ord`]) совпадает с порядком объявления вариантов: `Success < Failure < Pending`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TxStatus {
     /// Операция выполнена успешно.
     Success,
@@ -28,10 +43,201 @@ pub enum TxStatus {
     Pending,
 }
 
+/// Числовой код типа транзакции, используемый в бинарном формате.
+///
+/// ```rust
+/// use ypbank_parser::types::TxType;
+///
+/// assert_eq!(u8::from(TxType::Withdrawal), 2);
+/// assert_eq!(TxType::try_from(2u8).unwrap(), TxType::Withdrawal);
+/// ```
+impl From<TxType> for u8 {
+    fn from(value: TxType) -> Self {
+        match value {
+            TxType::Deposit => 0,
+            TxType::Transfer => 1,
+            TxType::Withdrawal => 2,
+            TxType::Fee => 3,
+            TxType::Reversal => 4,
+        }
+    }
+}
+
+/// Восстанавливает тип транзакции по числовому коду (см. `From<TxType> for u8`).
+impl TryFrom<u8> for TxType {
+    type Error = ParseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TxType::Deposit),
+            1 => Ok(TxType::Transfer),
+            2 => Ok(TxType::Withdrawal),
+            3 => Ok(TxType::Fee),
+            4 => Ok(TxType::Reversal),
+            _ => Err(ParseError::InvalidFormat(format!(
+                "unknown tx type code {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Числовой код статуса транзакции, используемый в бинарном формате.
+impl From<TxStatus> for u8 {
+    fn from(value: TxStatus) -> Self {
+        match value {
+            TxStatus::Success => 0,
+            TxStatus::Failure => 1,
+            TxStatus::Pending => 2,
+        }
+    }
+}
+
+/// Восстанавливает статус транзакции по числовому коду (см. `From<TxStatus> for u8`).
+impl TryFrom<u8> for TxStatus {
+    type Error = ParseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TxStatus::Success),
+            1 => Ok(TxStatus::Failure),
+            2 => Ok(TxStatus::Pending),
+            _ => Err(ParseError::InvalidFormat(format!(
+                "unknown tx status code {}",
+                value
+            ))),
+        }
+    }
+}
+
+// Регистр не важен: `deposit` и `Deposit` читаются как `DEPOSIT`.
+// Строгая проверка регистра — в `TxType::parse_with_case`.
+impl FromStr for TxType {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "DEPOSIT" => Ok(TxType::Deposit),
+            "TRANSFER" => Ok(TxType::Transfer),
+            "WITHDRAWAL" => Ok(TxType::Withdrawal),
+            "FEE" => Ok(TxType::Fee),
+            "REVERSAL" => Ok(TxType::Reversal),
+            _ => Err(ParseError::InvalidFormat("unknown tx type".to_string())),
+        }
+    }
+}
+
+// Регистр не важен, как и для `TxType`.
+impl FromStr for TxStatus {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "SUCCESS" => Ok(TxStatus::Success),
+            "FAILURE" => Ok(TxStatus::Failure),
+            "PENDING" => Ok(TxStatus::Pending),
+            _ => Err(ParseError::InvalidFormat("unknown tx status".to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TxType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "DEPOSIT"),
+            Self::Transfer => write!(f, "TRANSFER"),
+            Self::Withdrawal => write!(f, "WITHDRAWAL"),
+            Self::Fee => write!(f, "FEE"),
+            Self::Reversal => write!(f, "REVERSAL"),
+        }
+    }
+}
+
+impl fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Success => write!(f, "SUCCESS"),
+            Self::Failure => write!(f, "FAILURE"),
+            Self::Pending => write!(f, "PENDING"),
+        }
+    }
+}
+
+// Проверяет, что значение записано в каноническом (верхнем) регистре.
+fn check_case(s: &str, what: &str) -> Result<(), ParseError> {
+    if s == s.to_ascii_uppercase() {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidFormat(format!(
+            "non-canonical case in {} '{}'",
+            what, s
+        )))
+    }
+}
+
+impl TxType {
+    /// Все типы транзакций в порядке их числовых кодов (см. `From<TxType> for u8`).
+    ///
+    /// ```rust
+    /// use ypbank_parser::types::TxType;
+    ///
+    /// assert_eq!(TxType::all().first(), Some(&TxType::Deposit));
+    /// assert!(TxType::all().contains(&TxType::Reversal));
+    /// ```
+    pub fn all() -> &'static [TxType] {
+        &[
+            TxType::Deposit,
+            TxType::Transfer,
+            TxType::Withdrawal,
+            TxType::Fee,
+            TxType::Reversal,
+        ]
+    }
+
+    /// Разбирает тип транзакции. Если `strict_case` выключен, регистр не важен
+    /// (так же работает [`FromStr`]); в строгом режиме принимается только верхний регистр.
+    ///
+    /// ```rust
+    /// use ypbank_parser::types::TxType;
+    ///
+    /// assert_eq!(TxType::parse_with_case("Deposit", false).unwrap(), TxType::Deposit);
+    /// assert!(TxType::parse_with_case("Deposit", true).is_err());
+    /// ```
+    pub fn parse_with_case(s: &str, strict_case: bool) -> Result<Self, ParseError> {
+        if strict_case {
+            check_case(s, "tx type")?;
+        }
+        s.parse()
+    }
+}
+
+impl TxStatus {
+    /// Все статусы транзакций в порядке их числовых кодов (см. `From<TxStatus> for u8`).
+    pub fn all() -> &'static [TxStatus] {
+        &[TxStatus::Success, TxStatus::Failure, TxStatus::Pending]
+    }
+
+    /// Разбирает статус транзакции; `strict_case` работает так же, как в
+    /// [`TxType::parse_with_case`].
+    pub fn parse_with_case(s: &str, strict_case: bool) -> Result<Self, ParseError> {
+        if strict_case {
+            check_case(s, "tx status")?;
+        }
+        s.parse()
+    }
+}
+
 /// Основная структура, представляющая транзакцию.
 ///
 /// Содержит полную информацию о платеже, включая участников, сумму и статус.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Транзакции упорядочены хронологически: сначала по `timestamp`, затем по `id`,
+/// а при их совпадении — по остальным полям, чтобы порядок был полным.
+///
+/// [`Hash`] и [`Eq`] учитывают все поля, включая описание, поэтому транзакции можно
+/// дедуплицировать через [`std::collections::HashSet`]: совпадающие только по `id`
+/// транзакции считаются разными.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Transaction {
     /// Уникальный идентификатор транзакции.
     pub id: TxId,
@@ -51,13 +257,1030 @@ pub struct Transaction {
     pub description: String,
 }
 
+/// Имена полей транзакции в каноническом порядке. Так называются колонки заголовка CSV
+/// и поля текстовых форматов; в этом же порядке поля записываются при дампе.
+///
+/// ```rust
+/// use ypbank_parser::FIELD_NAMES;
+///
+/// assert_eq!(FIELD_NAMES[0], "TX_ID");
+/// assert_eq!(FIELD_NAMES.len(), 8);
+/// ```
+pub const FIELD_NAMES: [&str; 8] = [
+    "TX_ID",
+    "TX_TYPE",
+    "FROM_USER_ID",
+    "TO_USER_ID",
+    "AMOUNT",
+    "TIMESTAMP",
+    "STATUS",
+    "DESCRIPTION",
+];
+
+/// Построитель [`Transaction`] с цепочкой сеттеров.
+///
+/// Незаданные поля заполняются значениями по умолчанию: тип [`TxType::Deposit`],
+/// статус [`TxStatus::Pending`], нулевые идентификаторы, сумма и время,
+/// пустое описание.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{TransactionBuilder, types::TxStatus};
+///
+/// let tx = TransactionBuilder::new().id(1001).amount(500).build();
+/// assert_eq!(tx.status, TxStatus::Pending);
+/// assert!(tx.description.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    tx: Transaction,
+}
+
+impl Default for TransactionBuilder {
+    fn default() -> Self {
+        Self {
+            tx: Transaction {
+                id: 0,
+                r#type: TxType::Deposit,
+                from_user: 0,
+                to_user: 0,
+                amount: 0,
+                timestamp: 0,
+                status: TxStatus::Pending,
+                description: String::new(),
+            },
+        }
+    }
+}
+
+impl TransactionBuilder {
+    /// Создаёт построитель со значениями по умолчанию.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Устанавливает идентификатор транзакции.
+    pub fn id(mut self, id: TxId) -> Self {
+        self.tx.id = id;
+        self
+    }
+
+    /// Устанавливает тип транзакции.
+    pub fn tx_type(mut self, r#type: TxType) -> Self {
+        self.tx.r#type = r#type;
+        self
+    }
+
+    /// Устанавливает отправителя.
+    pub fn from_user(mut self, user: UserId) -> Self {
+        self.tx.from_user = user;
+        self
+    }
+
+    /// Устанавливает получателя.
+    pub fn to_user(mut self, user: UserId) -> Self {
+        self.tx.to_user = user;
+        self
+    }
+
+    /// Устанавливает сумму в минимальных единицах валюты.
+    pub fn amount(mut self, amount: u64) -> Self {
+        self.tx.amount = amount;
+        self
+    }
+
+    /// Устанавливает время создания транзакции.
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.tx.timestamp = timestamp;
+        self
+    }
+
+    /// Устанавливает статус транзакции.
+    pub fn status(mut self, status: TxStatus) -> Self {
+        self.tx.status = status;
+        self
+    }
+
+    /// Устанавливает описание транзакции.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.tx.description = description.into();
+        self
+    }
+
+    /// Возвращает построенную транзакцию.
+    pub fn build(self) -> Transaction {
+        self.tx
+    }
+}
+
+/// Набор транзакций с методами загрузки и сохранения в поддерживаемых форматах.
+///
+/// Обёртка над `Vec<Transaction>`, делегирующая чтение и запись функциям
+/// [`crate::parse`] и [`crate::dump`]. Благодаря [`Deref`](std::ops::Deref) к набору
+/// применимы методы срезов.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::{TransactionBatch, TransactionBuilder, types::SupportedFileFormat};
+///
+/// let mut batch = TransactionBatch::new();
+/// batch.push(TransactionBuilder::new().id(1).amount(100).build());
+///
+/// let mut buffer = Vec::new();
+/// batch.save(&mut buffer, SupportedFileFormat::Csv).expect("Ошибка записи");
+/// let loaded = TransactionBatch::load(&mut buffer.as_slice(), SupportedFileFormat::Csv)
+///     .expect("Ошибка парсинга");
+/// assert_eq!(loaded, batch);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionBatch {
+    transactions: Vec<Transaction>,
+}
+
+impl TransactionBatch {
+    /// Создаёт пустой набор.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Читает набор из `reader` в формате `format` (см. [`crate::parse`]).
+    pub fn load(
+        reader: &mut impl std::io::Read,
+        format: SupportedFileFormat,
+    ) -> Result<Self, ParseError> {
+        crate::parse(reader, format).map(Self::from)
+    }
+
+    /// Записывает набор во `writer` в формате `format` (см. [`crate::dump`]).
+    ///
+    /// Возвращает количество записанных транзакций.
+    pub fn save(
+        &self,
+        writer: &mut impl std::io::Write,
+        format: SupportedFileFormat,
+    ) -> Result<usize, DumpError> {
+        crate::dump(writer, format, &self.transactions)
+    }
+
+    /// Количество транзакций в наборе.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Возвращает `true`, если набор пуст.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Итератор по транзакциям в порядке добавления.
+    pub fn iter(&self) -> std::slice::Iter<'_, Transaction> {
+        self.transactions.iter()
+    }
+
+    /// Добавляет транзакцию в конец набора.
+    pub fn push(&mut self, tx: Transaction) {
+        self.transactions.push(tx);
+    }
+
+    /// Возвращает транзакции набора.
+    pub fn into_inner(self) -> Vec<Transaction> {
+        self.transactions
+    }
+}
+
+impl From<Vec<Transaction>> for TransactionBatch {
+    fn from(transactions: Vec<Transaction>) -> Self {
+        Self { transactions }
+    }
+}
+
+impl std::ops::Deref for TransactionBatch {
+    type Target = [Transaction];
+
+    fn deref(&self) -> &Self::Target {
+        &self.transactions
+    }
+}
+
+impl<'a> IntoIterator for &'a TransactionBatch {
+    type Item = &'a Transaction;
+    type IntoIter = std::slice::Iter<'a, Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Transaction {
+    /// Возвращает изменение баланса пользователя `user` в результате транзакции:
+    /// сумму со знаком плюс, если он получатель, со знаком минус, если отправитель,
+    /// и ноль, если он не участвует (или переводит сам себе).
+    ///
+    /// Статус транзакции не учитывается.
+    ///
+    /// # Пример
+    ///
+    /// ```rust
+    /// use ypbank_parser::TransactionBuilder;
+    /// use ypbank_parser::types::TxType;
+    ///
+    /// let tx = TransactionBuilder::new()
+    ///     .tx_type(TxType::Transfer)
+    ///     .from_user(501)
+    ///     .to_user(502)
+    ///     .amount(100)
+    ///     .build();
+    /// assert_eq!(tx.signed_amount_for(501), -100);
+    /// assert_eq!(tx.signed_amount_for(502), 100);
+    /// ```
+    pub fn signed_amount_for(&self, user: UserId) -> i128 {
+        let amount = i128::from(self.amount);
+        let received = if self.to_user == user { amount } else { 0 };
+        let sent = if self.from_user == user { amount } else { 0 };
+        received - sent
+    }
+
+    /// Делит сумму, хранящуюся в минимальных единицах валюты, на целую и дробную части.
+    /// `minor_units` — число минимальных единиц в одной основной (`100` для центов).
+    /// Только для отображения, сама сумма не меняется; строку даёт [`format_amount`].
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `minor_units` равен нулю.
+    ///
+    /// ```rust
+    /// use ypbank_parser::TransactionBuilder;
+    ///
+    /// let tx = TransactionBuilder::new().amount(50_025).build();
+    /// assert_eq!(tx.amount_major(100), (500, 25));
+    /// assert_eq!(tx.amount_major(1), (50_025, 0));
+    /// ```
+    pub fn amount_major(&self, minor_units: u32) -> (u64, u64) {
+        split_amount(self.amount, minor_units)
+    }
+
+    /// Разбирает одну строку данных CSV без заголовка. Колонки должны идти в порядке
+    /// стандартного заголовка (`TX_ID,TX_TYPE,...,DESCRIPTION`), диалект — по умолчанию
+    /// (см. [`crate::CsvOptions`]).
+    ///
+    /// ```rust
+    /// use ypbank_parser::types::Transaction;
+    ///
+    /// let tx = Transaction::from_csv_row(r#"1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Top up""#)
+    ///     .unwrap();
+    /// assert_eq!(tx.amount, 50000);
+    /// ```
+    ///
+    /// # Ошибки
+    ///
+    /// Возвращает [`ParseError::InvalidFormat`], если число полей не равно восьми
+    /// или значение поля некорректно.
+    #[cfg(feature = "csv")]
+    pub fn from_csv_row(row: &str) -> Result<Transaction, ParseError> {
+        crate::csv_format::parse_csv_row(row)
+    }
+
+    /// Сравнивает транзакции, не учитывая поля из `ignore`.
+    /// С пустым `ignore` результат совпадает с `==`.
+    ///
+    /// ```rust
+    /// use ypbank_parser::TransactionBuilder;
+    /// use ypbank_parser::types::TxField;
+    ///
+    /// let a = TransactionBuilder::new().id(1).description("a").build();
+    /// let b = TransactionBuilder::new().id(1).description("b").build();
+    /// assert!(a.eq_ignoring(&b, &[TxField::Description]));
+    /// assert!(!a.eq_ignoring(&b, &[]));
+    /// ```
+    pub fn eq_ignoring(&self, other: &Transaction, ignore: &[TxField]) -> bool {
+        TxField::ALL
+            .iter()
+            .filter(|field| !ignore.contains(field))
+            .all(|field| field.eq_in(self, other))
+    }
+}
+
+/// Поле транзакции. Используется для выборочного сравнения
+/// (см. [`Transaction::eq_ignoring`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxField {
+    /// [`Transaction::id`].
+    Id,
+    /// [`Transaction::r#type`].
+    Type,
+    /// [`Transaction::from_user`].
+    FromUser,
+    /// [`Transaction::to_user`].
+    ToUser,
+    /// [`Transaction::amount`].
+    Amount,
+    /// [`Transaction::timestamp`].
+    Timestamp,
+    /// [`Transaction::status`].
+    Status,
+    /// [`Transaction::description`].
+    Description,
+}
+
+impl TxField {
+    /// Все поля в порядке объявления в [`Transaction`].
+    pub const ALL: [TxField; 8] = [
+        TxField::Id,
+        TxField::Type,
+        TxField::FromUser,
+        TxField::ToUser,
+        TxField::Amount,
+        TxField::Timestamp,
+        TxField::Status,
+        TxField::Description,
+    ];
+
+    /// Проверяет, совпадает ли это поле у двух транзакций.
+    pub fn eq_in(&self, a: &Transaction, b: &Transaction) -> bool {
+        match self {
+            TxField::Id => a.id == b.id,
+            TxField::Type => a.r#type == b.r#type,
+            TxField::FromUser => a.from_user == b.from_user,
+            TxField::ToUser => a.to_user == b.to_user,
+            TxField::Amount => a.amount == b.amount,
+            TxField::Timestamp => a.timestamp == b.timestamp,
+            TxField::Status => a.status == b.status,
+            TxField::Description => a.description == b.description,
+        }
+    }
+}
+
+/// Выводит имя поля в нижнем регистре: `id`, `type`, `from_user`, ...
+impl fmt::Display for TxField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TxField::Id => "id",
+            TxField::Type => "type",
+            TxField::FromUser => "from_user",
+            TxField::ToUser => "to_user",
+            TxField::Amount => "amount",
+            TxField::Timestamp => "timestamp",
+            TxField::Status => "status",
+            TxField::Description => "description",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Разбирает имя поля без учёта регистра; в составных именах допускаются
+/// и `_`, и `-` (`from_user` или `from-user`).
+impl FromStr for TxField {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_ascii_lowercase().replace('-', "_");
+        TxField::ALL
+            .into_iter()
+            .find(|field| field.to_string() == name)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("unknown field '{}'", s)))
+    }
+}
+
+/// Форматирует сумму в минимальных единицах валюты как число в основных единицах:
+/// `50000` при `minor_units = 100` даёт `"500.00"`.
+///
+/// Дробная часть дополняется нулями до числа знаков в `minor_units - 1` (две цифры
+/// для `100`, три для `1000`); при `minor_units = 1` дробной части нет.
+///
+/// # Паника
+///
+/// Паникует, если `minor_units` равен нулю.
+///
+/// ```rust
+/// use ypbank_parser::types::format_amount;
+///
+/// assert_eq!(format_amount(50_000, 100), "500.00");
+/// assert_eq!(format_amount(1_005, 1000), "1.005");
+/// assert_eq!(format_amount(42, 1), "42");
+/// ```
+pub fn format_amount(amount: u64, minor_units: u32) -> String {
+    let (major, minor) = split_amount(amount, minor_units);
+    if minor_units == 1 {
+        return major.to_string();
+    }
+    let width = (minor_units - 1).to_string().len();
+    format!("{}.{:0width$}", major, minor)
+}
+
+fn split_amount(amount: u64, minor_units: u32) -> (u64, u64) {
+    assert!(minor_units > 0, "minor_units must be positive");
+    let minor_units = u64::from(minor_units);
+    (amount / minor_units, amount % minor_units)
+}
+
+/// Разбирает строку вида `ref=123;cat=food` на пары ключ-значение.
+///
+/// Сегменты разделяются символом `separator`, ключ от значения отделяется первым `=`.
+/// Пробелы вокруг ключей и значений отбрасываются. Сегменты без `=` или с пустым
+/// ключом пропускаются; при повторе ключа побеждает последнее значение.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::types::parse_description_tags;
+///
+/// let tags = parse_description_tags("ref=123;cat=food;garbage", ';');
+/// assert_eq!(tags.len(), 2);
+/// assert_eq!(tags["ref"], "123");
+/// ```
+pub fn parse_description_tags(description: &str, separator: char) -> HashMap<String, String> {
+    description
+        .split(separator)
+        .filter_map(|segment| segment.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Хронологический порядок: по `timestamp`, затем по `id`, затем по остальным полям.
+impl Ord for Transaction {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then(self.id.cmp(&other.id))
+            .then(self.r#type.cmp(&other.r#type))
+            .then(self.from_user.cmp(&other.from_user))
+            .then(self.to_user.cmp(&other.to_user))
+            .then(self.amount.cmp(&other.amount))
+            .then(self.status.cmp(&other.status))
+            .then_with(|| self.description.cmp(&other.description))
+    }
+}
+
+impl PartialOrd for Transaction {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Выводит транзакцию в читаемом многострочном виде: каждое поле на отдельной строке
+/// в формате `ИМЯ_ПОЛЯ: значение` (имена полей совпадают с текстовым форматом).
+///
+/// Сумма выводится с разделителями разрядов, поэтому результат предназначен для
+/// отладки и отображения, а не для последующего парсинга.
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "TX_ID: {}", self.id)?;
+        writeln!(f, "TX_TYPE: {}", self.r#type)?;
+        writeln!(f, "FROM_USER_ID: {}", self.from_user)?;
+        writeln!(f, "TO_USER_ID: {}", self.to_user)?;
+        writeln!(f, "AMOUNT: {}", utils::group_thousands(self.amount))?;
+        writeln!(f, "TIMESTAMP: {}", self.timestamp)?;
+        writeln!(f, "STATUS: {}", self.status)?;
+        write!(f, "DESCRIPTION: \"{}\"", self.description)
+    }
+}
+
 /// Поддерживаемые форматы файлов для импорта/экспорта транзакций.
+///
+/// Форматы Text, CSV и BIN включаются фичами `text`, `csv` и `bin` (все включены
+/// по умолчанию); без соответствующей фичи вариант отсутствует, а его имя не разбирается.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SupportedFileFormat {
-    /// Текстовый формат (см. [описание](doc/YPBankTextFormat_ru.md)).
+    /// Текстовый формат (см. [описание](doc/YPBankTextFormat_ru.md)). Доступен с фичей `text`.
+    #[cfg(feature = "text")]
     Text,
-    /// CSV формат (см. [описание](doc/YPBankCsvFormat_ru.md)).
+    /// CSV формат (см. [описание](doc/YPBankCsvFormat_ru.md)). Доступен с фичей `csv`.
+    #[cfg(feature = "csv")]
     Csv,
-    /// Бинарный формат (см. [описание](doc/YPBankBinFormat_ru.md)).
+    /// Бинарный формат (см. [описание](doc/YPBankBinFormat_ru.md)). Доступен с фичей `bin`.
+    #[cfg(feature = "bin")]
     Bin,
+    /// TOML формат с таблицей на каждую транзакцию (см. [описание](doc/YPBankTomlFormat_ru.md)).
+    Toml,
+    /// XML формат с элементом `<transaction>` на каждую транзакцию
+    /// (см. [описание](doc/YPBankXmlFormat_ru.md)).
+    Xml,
+    /// JSON Lines: по одному JSON-объекту на строку
+    /// (см. [описание](doc/YPBankJsonLinesFormat_ru.md)).
+    JsonLines,
+    /// Однострочный текстовый формат: транзакция на строку в виде пар `ИМЯ=значение`
+    /// (см. [описание](doc/YPBankTextLineFormat_ru.md)).
+    TextLine,
+    /// Поток сообщений Protocol Buffers с префиксом длины
+    /// (см. [описание](doc/YPBankProtobufFormat_ru.md)). Доступен с фичей `prost`.
+    #[cfg(feature = "prost")]
+    Protobuf,
+}
+
+impl SupportedFileFormat {
+    /// Все поддерживаемые форматы в порядке объявления.
+    pub const ALL: &[SupportedFileFormat] = &[
+        #[cfg(feature = "text")]
+        Self::Text,
+        #[cfg(feature = "csv")]
+        Self::Csv,
+        #[cfg(feature = "bin")]
+        Self::Bin,
+        Self::Toml,
+        Self::Xml,
+        Self::JsonLines,
+        Self::TextLine,
+        #[cfg(feature = "prost")]
+        Self::Protobuf,
+    ];
+
+    /// Краткое описание формата в одну строку.
+    pub fn description(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "text")]
+            Self::Text => "текстовый формат: блоки `ПОЛЕ: значение`, разделённые пустой строкой",
+            #[cfg(feature = "csv")]
+            Self::Csv => "CSV с заголовком, по одной транзакции на строку",
+            #[cfg(feature = "bin")]
+            Self::Bin => "бинарный формат с заголовком записи YPBN",
+            Self::Toml => "TOML с таблицей [transaction.N] на каждую транзакцию",
+            Self::Xml => "XML с элементом <transaction> на каждую транзакцию",
+            Self::JsonLines => "JSON Lines: по одному JSON-объекту на строку",
+            Self::TextLine => "текстовый формат в одну строку: пары ИМЯ=значение",
+            #[cfg(feature = "prost")]
+            Self::Protobuf => "поток сообщений Protocol Buffers с префиксом длины",
+        }
+    }
+}
+
+/// Разбирает имя формата без учёта регистра: `text`, `csv`, `bin` (с одноимёнными фичами),
+/// `toml`, `xml`, `jsonl` (или `ndjson`), `textline` (или `oneline`) или `protobuf`
+/// (с фичей `prost`).
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::types::SupportedFileFormat;
+///
+/// assert_eq!("CSV".parse::<SupportedFileFormat>().unwrap(), SupportedFileFormat::Csv);
+/// assert!("xlsx".parse::<SupportedFileFormat>().is_err());
+/// ```
+impl FromStr for SupportedFileFormat {
+    type Err = UnknownFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "text")]
+            "text" => Ok(Self::Text),
+            #[cfg(feature = "csv")]
+            "csv" => Ok(Self::Csv),
+            #[cfg(feature = "bin")]
+            "bin" => Ok(Self::Bin),
+            "toml" => Ok(Self::Toml),
+            "xml" => Ok(Self::Xml),
+            "jsonl" | "ndjson" => Ok(Self::JsonLines),
+            "textline" | "oneline" => Ok(Self::TextLine),
+            #[cfg(feature = "prost")]
+            "protobuf" => Ok(Self::Protobuf),
+            _ => Err(UnknownFormatError {
+                name: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Выводит каноническое имя формата в нижнем регистре.
+impl fmt::Display for SupportedFileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "text")]
+            Self::Text => write!(f, "text"),
+            #[cfg(feature = "csv")]
+            Self::Csv => write!(f, "csv"),
+            #[cfg(feature = "bin")]
+            Self::Bin => write!(f, "bin"),
+            Self::Toml => write!(f, "toml"),
+            Self::Xml => write!(f, "xml"),
+            Self::JsonLines => write!(f, "jsonl"),
+            Self::TextLine => write!(f, "textline"),
+            #[cfg(feature = "prost")]
+            Self::Protobuf => write!(f, "protobuf"),
+        }
+    }
+}
+
+// Транзакции типов FEE и REVERSAL для тестов форматов BIN, CSV и Text.
+#[cfg(all(test, any(feature = "bin", feature = "csv", feature = "text")))]
+pub(crate) fn fee_and_reversal() -> Vec<Transaction> {
+    vec![
+        TransactionBuilder::new()
+            .id(2001)
+            .tx_type(TxType::Fee)
+            .from_user(501)
+            .to_user(0)
+            .amount(150)
+            .timestamp(1672531200000)
+            .status(TxStatus::Success)
+            .description("Monthly fee")
+            .build(),
+        TransactionBuilder::new()
+            .id(2002)
+            .tx_type(TxType::Reversal)
+            .from_user(0)
+            .to_user(501)
+            .amount(150)
+            .timestamp(1672534800000)
+            .status(TxStatus::Pending)
+            .description("Fee reversal")
+            .build(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_major() {
+        let tx = |amount| TransactionBuilder::new().amount(amount).build();
+
+        assert_eq!(tx(50000).amount_major(100), (500, 0));
+        assert_eq!(format_amount(50000, 100), "500.00");
+        assert_eq!(tx(15007).amount_major(100), (150, 7));
+        assert_eq!(format_amount(15007, 100), "150.07");
+        assert_eq!(format_amount(99, 100), "0.99");
+        assert_eq!(format_amount(0, 100), "0.00");
+
+        assert_eq!(tx(1234).amount_major(1), (1234, 0));
+        assert_eq!(format_amount(1234, 1), "1234");
+
+        assert_eq!(format_amount(1005, 1000), "1.005");
+        assert_eq!(format_amount(u64::MAX, 100), "184467440737095516.15");
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv", feature = "text"))]
+    fn test_formats_use_field_names() {
+        let txs = [TransactionBuilder::new().id(1).description("x").build()];
+
+        let mut csv = Vec::new();
+        crate::dump(&mut csv, SupportedFileFormat::Csv, &txs).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().next().unwrap(), FIELD_NAMES.join(","));
+
+        let mut text = Vec::new();
+        crate::dump(&mut text, SupportedFileFormat::Text, &txs).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        let names: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.split_once(':').map(|(name, _)| name))
+            .collect();
+        assert_eq!(names, FIELD_NAMES);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_from_csv_row() {
+        let tx = Transaction::from_csv_row(
+            "1002,TRANSFER,501,502,15000,1672534800000,FAILURE,\"Payment, invoice #123, \"\"A\"\"\"",
+        )
+        .unwrap();
+        assert_eq!(
+            tx,
+            TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Transfer)
+                .from_user(501)
+                .to_user(502)
+                .amount(15000)
+                .timestamp(1672534800000)
+                .status(TxStatus::Failure)
+                .description("Payment, invoice #123, \"A\"")
+                .build()
+        );
+
+        assert!(matches!(
+            Transaction::from_csv_row("1002,TRANSFER,501,502,15000,1672534800000,FAILURE"),
+            Err(ParseError::InvalidFormat(msg)) if msg == "invalid fields count: 7"
+        ));
+        // заголовок — не строка данных
+        assert!(
+            Transaction::from_csv_row(
+                "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_eq_ignoring() {
+        let a = TransactionBuilder::new()
+            .id(1)
+            .amount(100)
+            .description("first")
+            .build();
+        let mut b = a.clone();
+        b.description = "second".to_string();
+
+        assert!(a.eq_ignoring(&b, &[TxField::Description]));
+        assert!(!a.eq_ignoring(&b, &[TxField::Amount]));
+        // без игнорируемых полей — то же, что PartialEq
+        assert_eq!(a.eq_ignoring(&b, &[]), a == b);
+        assert!(a.eq_ignoring(&a.clone(), &[]));
+
+        b.amount = 200;
+        assert!(!a.eq_ignoring(&b, &[TxField::Description]));
+        assert!(a.eq_ignoring(&b, &[TxField::Description, TxField::Amount]));
+
+        assert_eq!("From-User".parse::<TxField>().unwrap(), TxField::FromUser);
+        assert_eq!(TxField::FromUser.to_string(), "from_user");
+        assert!("bogus".parse::<TxField>().is_err());
+    }
+
+    #[test]
+    fn test_parse_enum_case_insensitive() {
+        for s in ["deposit", "Deposit", "DEPOSIT"] {
+            assert_eq!(s.parse::<TxType>().unwrap(), TxType::Deposit);
+        }
+        assert_eq!("Success".parse::<TxStatus>().unwrap(), TxStatus::Success);
+        assert_eq!(TxType::Deposit.to_string(), "DEPOSIT");
+        assert_eq!(TxStatus::Pending.to_string(), "PENDING");
+    }
+
+    #[test]
+    fn test_parse_enum_strict_case() {
+        assert_eq!(
+            TxType::parse_with_case("DEPOSIT", true).unwrap(),
+            TxType::Deposit
+        );
+        assert!(matches!(
+            TxType::parse_with_case("deposit", true),
+            Err(ParseError::InvalidFormat(msg)) if msg == "non-canonical case in tx type 'deposit'"
+        ));
+        assert!(TxStatus::parse_with_case("success", true).is_err());
+        assert!(TxType::parse_with_case("BOGUS", true).is_err());
+    }
+
+    #[test]
+    fn test_display_transaction() {
+        let tx = Transaction {
+            id: 1001,
+            r#type: TxType::Deposit,
+            from_user: 0,
+            to_user: 501,
+            amount: 1234567,
+            timestamp: 1672531200000,
+            status: TxStatus::Success,
+            description: "Initial account funding".to_string(),
+        };
+
+        let got = tx.to_string();
+
+        assert!(got.contains("TX_TYPE: DEPOSIT"));
+        assert!(got.contains("AMOUNT: 1,234,567"));
+        assert!(got.ends_with("DESCRIPTION: \"Initial account funding\""));
+        assert_eq!(got.lines().count(), 8);
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(utils::group_thousands(0), "0");
+        assert_eq!(utils::group_thousands(999), "999");
+        assert_eq!(utils::group_thousands(1000), "1,000");
+        assert_eq!(
+            utils::group_thousands(u64::MAX),
+            "18,446,744,073,709,551,615"
+        );
+    }
+
+    #[test]
+    fn test_parse_description_tags() {
+        let tags = parse_description_tags("ref=123;cat=food", ';');
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["ref"], "123");
+        assert_eq!(tags["cat"], "food");
+    }
+
+    #[test]
+    fn test_parse_description_tags_skips_malformed() {
+        let tags = parse_description_tags("Payment | ref = 7 | =x | note=a=b |", '|');
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["ref"], "7");
+        assert_eq!(tags["note"], "a=b");
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let tx = TransactionBuilder::new().id(1001).amount(50000).build();
+
+        assert_eq!(
+            tx,
+            Transaction {
+                id: 1001,
+                r#type: TxType::Deposit,
+                from_user: 0,
+                to_user: 0,
+                amount: 50000,
+                timestamp: 0,
+                status: TxStatus::Pending,
+                description: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_builder_all_fields() {
+        let tx = TransactionBuilder::new()
+            .id(1002)
+            .tx_type(TxType::Transfer)
+            .from_user(501)
+            .to_user(502)
+            .amount(15000)
+            .timestamp(1672534800000)
+            .status(TxStatus::Failure)
+            .description("Payment")
+            .build();
+
+        assert_eq!(tx.r#type, TxType::Transfer);
+        assert_eq!((tx.from_user, tx.to_user), (501, 502));
+        assert_eq!(tx.timestamp, 1672534800000);
+        assert_eq!(tx.status, TxStatus::Failure);
+        assert_eq!(tx.description, "Payment");
+    }
+
+    #[test]
+    fn test_parse_file_format() {
+        let formats = [
+            #[cfg(feature = "text")]
+            ("text", SupportedFileFormat::Text),
+            #[cfg(feature = "csv")]
+            ("Csv", SupportedFileFormat::Csv),
+            #[cfg(feature = "bin")]
+            ("BIN", SupportedFileFormat::Bin),
+            ("toml", SupportedFileFormat::Toml),
+            ("XML", SupportedFileFormat::Xml),
+            ("jsonl", SupportedFileFormat::JsonLines),
+            ("TextLine", SupportedFileFormat::TextLine),
+            #[cfg(feature = "prost")]
+            ("Protobuf", SupportedFileFormat::Protobuf),
+        ];
+        for (name, format) in formats {
+            assert_eq!(name.parse::<SupportedFileFormat>().unwrap(), format);
+            assert_eq!(format.to_string(), name.to_ascii_lowercase());
+        }
+
+        assert_eq!(SupportedFileFormat::ALL.len(), formats.len());
+        for format in SupportedFileFormat::ALL {
+            assert_eq!(
+                format.to_string().parse::<SupportedFileFormat>().unwrap(),
+                *format
+            );
+            assert!(!format.description().is_empty());
+        }
+
+        let err = "xlsx".parse::<SupportedFileFormat>().unwrap_err();
+        assert_eq!(err.name, "xlsx");
+        assert!(err.to_string().contains("xlsx"));
+        // в сообщении перечислены только форматы, включённые фичами
+        let message = err.to_string();
+        let listed: Vec<&str> = message.rsplit_once(": ").unwrap().1.split(", ").collect();
+        assert_eq!(listed.len(), SupportedFileFormat::ALL.len());
+        assert_eq!(listed.contains(&"csv"), cfg!(feature = "csv"));
+        assert_eq!(listed.contains(&"bin"), cfg!(feature = "bin"));
+        assert_eq!(listed.contains(&"text"), cfg!(feature = "text"));
+        assert!(listed.contains(&"toml"));
+        assert!("".parse::<SupportedFileFormat>().is_err());
+    }
+
+    #[test]
+    fn test_sort_chronologically() {
+        let tx = |id, timestamp| {
+            TransactionBuilder::new()
+                .id(id)
+                .timestamp(timestamp)
+                .build()
+        };
+        let mut txs = [tx(3, 300), tx(2, 100), tx(5, 200), tx(1, 100), tx(4, 200)];
+
+        txs.sort();
+
+        let order: Vec<(u64, u64)> = txs.iter().map(|tx| (tx.timestamp, tx.id)).collect();
+        assert_eq!(
+            order,
+            vec![(100, 1), (100, 2), (200, 4), (200, 5), (300, 3)]
+        );
+    }
+
+    #[test]
+    fn test_hash_set_dedup() {
+        let tx = |id, description: &str| {
+            TransactionBuilder::new()
+                .id(id)
+                .description(description)
+                .build()
+        };
+        let set: std::collections::HashSet<Transaction> = [
+            tx(1, "a"),
+            tx(2, "b"),
+            tx(1, "a"),
+            // тот же id, другое описание
+            tx(1, "c"),
+            tx(2, "b"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&tx(1, "c")));
+    }
+
+    #[test]
+    fn test_all_variants() {
+        let type_codes: std::collections::HashSet<u8> =
+            TxType::all().iter().map(|t| u8::from(*t)).collect();
+        assert_eq!(TxType::all().len(), 5);
+        assert_eq!(type_codes.len(), TxType::all().len());
+
+        let status_codes: std::collections::HashSet<u8> =
+            TxStatus::all().iter().map(|s| u8::from(*s)).collect();
+        assert_eq!(TxStatus::all().len(), 3);
+        assert_eq!(status_codes.len(), TxStatus::all().len());
+    }
+
+    #[test]
+    fn test_u8_codes_roundtrip() {
+        for (code, &tx_type) in TxType::all().iter().enumerate() {
+            assert_eq!(u8::from(tx_type), code as u8);
+            assert_eq!(TxType::try_from(code as u8).unwrap(), tx_type);
+        }
+
+        for (code, &status) in TxStatus::all().iter().enumerate() {
+            assert_eq!(u8::from(status), code as u8);
+            assert_eq!(TxStatus::try_from(code as u8).unwrap(), status);
+        }
+
+        assert!(matches!(
+            TxType::try_from(5),
+            Err(ParseError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            TxStatus::try_from(3),
+            Err(ParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_signed_amount_for() {
+        let deposit = TransactionBuilder::new()
+            .tx_type(TxType::Deposit)
+            .to_user(501)
+            .amount(u64::MAX)
+            .build();
+        let withdrawal = TransactionBuilder::new()
+            .tx_type(TxType::Withdrawal)
+            .from_user(501)
+            .amount(u64::MAX)
+            .build();
+        let transfer = TransactionBuilder::new()
+            .tx_type(TxType::Transfer)
+            .from_user(501)
+            .to_user(502)
+            .amount(15000)
+            .build();
+
+        assert_eq!(deposit.signed_amount_for(501), i128::from(u64::MAX));
+        assert_eq!(withdrawal.signed_amount_for(501), -i128::from(u64::MAX));
+        assert_eq!(transfer.signed_amount_for(501), -15000);
+        assert_eq!(transfer.signed_amount_for(502), 15000);
+        assert_eq!(transfer.signed_amount_for(777), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv", feature = "bin"))]
+    fn test_batch_load_push_save() {
+        let csv = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+            1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+            1002,TRANSFER,501,502,15000,1672534800000,FAILURE,"Payment"
+            "##;
+        let mut batch =
+            TransactionBatch::load(&mut csv.as_bytes(), SupportedFileFormat::Csv).unwrap();
+        assert_eq!(batch.len(), 2);
+
+        batch.push(TransactionBuilder::new().id(1003).amount(10).build());
+        assert_eq!(
+            batch.iter().map(|tx| tx.id).collect::<Vec<_>>(),
+            [1001, 1002, 1003]
+        );
+        assert_eq!(batch.last().unwrap().amount, 10);
+        assert_eq!(
+            batch.first().unwrap().description,
+            "Initial account funding"
+        );
+
+        let mut buffer = Vec::new();
+        assert_eq!(
+            batch.save(&mut buffer, SupportedFileFormat::Bin).unwrap(),
+            3
+        );
+        let loaded =
+            TransactionBatch::load(&mut buffer.as_slice(), SupportedFileFormat::Bin).unwrap();
+        assert_eq!(loaded, batch);
+        assert_eq!(loaded.into_inner().len(), 3);
+    }
 }
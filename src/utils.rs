@@ -1,3 +1,24 @@
+use crate::error::ParseError;
+#[cfg(any(feature = "csv", feature = "text"))]
+use crate::parser::DumpOptions;
+use std::io::{self, BufRead};
+
+/// Построчно читает поток, убирая UTF-8 BOM в начале первой строки.
+///
+/// Окончания строк `\n` и `\r\n` отбрасываются (см. [`BufRead::lines`]).
+pub(crate) fn lines_without_bom(reader: impl io::Read) -> impl Iterator<Item = io::Result<String>> {
+    io::BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.map(|l| match l.strip_prefix('\u{feff}') {
+                Some(rest) if index == 0 => rest.to_string(),
+                _ => l,
+            })
+        })
+}
+
+#[cfg(feature = "text")]
 pub(crate) fn parse_quoted_field(s: &str) -> String {
     let s = s.trim();
     if s.starts_with('"') && s.ends_with('"') && s.len() >= 2 {
@@ -7,6 +28,75 @@ pub(crate) fn parse_quoted_field(s: &str) -> String {
     }
 }
 
+#[cfg(feature = "text")]
 pub(crate) fn wrap_with_quotes(s: &str) -> String {
     format!("\"{}\"", s)
 }
+
+/// Форматирует число, разделяя группы разрядов запятыми: `1234567` -> `1,234,567`.
+pub(crate) fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Разбирает временную метку: число миллисекунд с начала эпохи или, при включённой
+/// фиче `chrono`, строку в формате ISO-8601 (RFC 3339).
+pub(crate) fn parse_timestamp(s: &str) -> Result<u64, ParseError> {
+    match s.parse::<u64>() {
+        Ok(value) => Ok(value),
+        #[cfg(feature = "chrono")]
+        Err(err) => match chrono::DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => u64::try_from(dt.timestamp_millis()).map_err(|_| {
+                ParseError::InvalidFormat(format!("timestamp before unix epoch: {}", s))
+            }),
+            Err(_) => Err(err.into()),
+        },
+        #[cfg(not(feature = "chrono"))]
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Записывает [`DumpOptions::header_comment`], если он задан: каждую строку комментария
+/// с префиксом `#` и концом строки из `options`.
+#[cfg(any(feature = "csv", feature = "text"))]
+pub(crate) fn write_header_comment(
+    writer: &mut impl io::Write,
+    options: &DumpOptions,
+) -> io::Result<()> {
+    let Some(comment) = &options.header_comment else {
+        return Ok(());
+    };
+    let eol = options.line_ending.as_str();
+    for line in comment.lines() {
+        if line.is_empty() {
+            write!(writer, "#{}", eol)?;
+        } else {
+            write!(writer, "# {}{}", line, eol)?;
+        }
+    }
+    Ok(())
+}
+
+/// Форматирует временную метку для текстовых форматов согласно [`DumpOptions`].
+#[cfg(any(feature = "csv", feature = "text"))]
+pub(crate) fn format_timestamp(timestamp: u64, options: &DumpOptions) -> String {
+    #[cfg(feature = "chrono")]
+    if options.human_timestamps {
+        let dt = i64::try_from(timestamp)
+            .ok()
+            .and_then(chrono::DateTime::from_timestamp_millis);
+        if let Some(dt) = dt {
+            return dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        }
+    }
+    #[cfg(not(feature = "chrono"))]
+    let _ = options;
+    timestamp.to_string()
+}
@@ -0,0 +1,317 @@
+//! Модуль проверки качества данных.
+//!
+//! В отличие от парсеров, которые проверяют каждую запись по отдельности,
+//! функции этого модуля анализируют уже разобранный набор транзакций целиком
+//! и возвращают все найденные проблемы, а не только первую.
+
+use crate::error::ParseError;
+use crate::types::{Transaction, TxType};
+use std::collections::HashSet;
+
+/// Проблема с участниками перевода ([`TxType::Transfer`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferIssue {
+    /// Отправитель и получатель совпадают.
+    SelfTransfer {
+        /// Идентификатор транзакции.
+        id: u64,
+        /// Идентификатор пользователя, переводящего самому себе.
+        user: u64,
+    },
+    /// Отправителем перевода указан нулевой (системный) счёт.
+    MissingSender {
+        /// Идентификатор транзакции.
+        id: u64,
+    },
+    /// Получателем перевода указан нулевой (системный) счёт.
+    MissingRecipient {
+        /// Идентификатор транзакции.
+        id: u64,
+    },
+}
+
+/// Проверяет, что у всех переводов указаны оба участника и они различны.
+///
+/// Нулевой счёт допустим только для пополнений и снятий, поэтому для перевода
+/// он считается отсутствующим участником. Транзакции других типов не проверяются.
+///
+/// # Ошибки
+///
+/// Возвращает список всех найденных проблем в порядке следования транзакций.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::TransactionBuilder;
+/// use ypbank_parser::types::TxType;
+/// use ypbank_parser::validation::{validate_transfer_users, TransferIssue};
+///
+/// let txs = vec![
+///     TransactionBuilder::new().id(1).tx_type(TxType::Transfer).from_user(5).to_user(5).build(),
+/// ];
+/// assert_eq!(
+///     validate_transfer_users(&txs),
+///     Err(vec![TransferIssue::SelfTransfer { id: 1, user: 5 }])
+/// );
+/// ```
+pub fn validate_transfer_users(transactions: &[Transaction]) -> Result<(), Vec<TransferIssue>> {
+    let mut issues = Vec::new();
+    for tx in transactions
+        .iter()
+        .filter(|tx| tx.r#type == TxType::Transfer)
+    {
+        if tx.from_user == 0 {
+            issues.push(TransferIssue::MissingSender { id: tx.id });
+        }
+        if tx.to_user == 0 {
+            issues.push(TransferIssue::MissingRecipient { id: tx.id });
+        }
+        if tx.from_user != 0 && tx.from_user == tx.to_user {
+            issues.push(TransferIssue::SelfTransfer {
+                id: tx.id,
+                user: tx.from_user,
+            });
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Проверяет, что идентификаторы транзакций в наборе не повторяются.
+///
+/// Парсеры сами по себе дубликаты не отслеживают, поэтому проверку нужно
+/// вызывать явно после разбора.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError::DuplicateId`] для первого идентификатора,
+/// встретившегося повторно.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::TransactionBuilder;
+/// use ypbank_parser::error::ParseError;
+/// use ypbank_parser::validation::check_unique_ids;
+///
+/// let txs = vec![
+///     TransactionBuilder::new().id(1001).build(),
+///     TransactionBuilder::new().id(1001).build(),
+/// ];
+/// assert!(matches!(check_unique_ids(&txs), Err(ParseError::DuplicateId { id: 1001 })));
+/// ```
+pub fn check_unique_ids(transactions: &[Transaction]) -> Result<(), ParseError> {
+    let mut seen = HashSet::with_capacity(transactions.len());
+    match transactions.iter().find(|tx| !seen.insert(tx.id)) {
+        Some(tx) => Err(ParseError::DuplicateId { id: tx.id }),
+        None => Ok(()),
+    }
+}
+
+/// Подозрительная, но допустимая особенность набора транзакций (см. [`collect_warnings`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// Идентификатор уже встречался в одной из предыдущих транзакций.
+    DuplicateId {
+        /// Повторившийся идентификатор.
+        id: u64,
+    },
+    /// Временная метка меньше, чем у предыдущей транзакции.
+    NonMonotonicTimestamp {
+        /// Идентификатор транзакции.
+        id: u64,
+        /// Временная метка транзакции.
+        timestamp: u64,
+        /// Временная метка предыдущей транзакции.
+        previous: u64,
+    },
+    /// Сумма транзакции равна нулю.
+    ZeroAmount {
+        /// Идентификатор транзакции.
+        id: u64,
+    },
+}
+
+/// Находит в наборе подозрительные, но не фатальные особенности: повторные
+/// идентификаторы, убывание временных меток и нулевые суммы.
+///
+/// Предупреждения возвращаются в порядке следования транзакций; у одной транзакции
+/// их может быть несколько. Повтор идентификатора отмечается у каждого вхождения,
+/// кроме первого.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::TransactionBuilder;
+/// use ypbank_parser::validation::{collect_warnings, Warning};
+///
+/// let txs = vec![
+///     TransactionBuilder::new().id(1).amount(100).build(),
+///     TransactionBuilder::new().id(2).build(),
+/// ];
+/// assert_eq!(collect_warnings(&txs), [Warning::ZeroAmount { id: 2 }]);
+/// ```
+pub fn collect_warnings(transactions: &[Transaction]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::with_capacity(transactions.len());
+    let mut previous: Option<u64> = None;
+    for tx in transactions {
+        if !seen.insert(tx.id) {
+            warnings.push(Warning::DuplicateId { id: tx.id });
+        }
+        if let Some(previous) = previous
+            && tx.timestamp < previous
+        {
+            warnings.push(Warning::NonMonotonicTimestamp {
+                id: tx.id,
+                timestamp: tx.timestamp,
+                previous,
+            });
+        }
+        if tx.amount == 0 {
+            warnings.push(Warning::ZeroAmount { id: tx.id });
+        }
+        previous = Some(tx.timestamp);
+    }
+    warnings
+}
+
+/// Верхняя граница временной метки по умолчанию для [`check_timestamps`]:
+/// 2100-01-01 00:00:00 UTC в миллисекундах.
+pub const DEFAULT_MAX_TIMESTAMP: u64 = 4_102_444_800_000;
+
+/// Проверяет, что временные метки правдоподобны: не равны нулю и не превышают
+/// `max_timestamp` (включительно). В качестве границы обычно берут [`DEFAULT_MAX_TIMESTAMP`].
+///
+/// Парсеры принимают любую метку, поэтому проверку нужно вызывать явно после разбора:
+/// она помогает заметить испорченные выгрузки с нулевыми или слишком далёкими датами.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError::ImplausibleTimestamp`] для первой неправдоподобной метки.
+///
+/// # Пример
+///
+/// ```rust
+/// use ypbank_parser::TransactionBuilder;
+/// use ypbank_parser::error::ParseError;
+/// use ypbank_parser::validation::{check_timestamps, DEFAULT_MAX_TIMESTAMP};
+///
+/// let txs = vec![TransactionBuilder::new().timestamp(0).build()];
+/// assert!(matches!(
+///     check_timestamps(&txs, DEFAULT_MAX_TIMESTAMP),
+///     Err(ParseError::ImplausibleTimestamp { value: 0 })
+/// ));
+/// ```
+pub fn check_timestamps(
+    transactions: &[Transaction],
+    max_timestamp: u64,
+) -> Result<(), ParseError> {
+    match transactions
+        .iter()
+        .find(|tx| tx.timestamp == 0 || tx.timestamp > max_timestamp)
+    {
+        Some(tx) => Err(ParseError::ImplausibleTimestamp {
+            value: tx.timestamp,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    fn transfer(id: u64, from_user: u64, to_user: u64) -> Transaction {
+        TransactionBuilder::new()
+            .id(id)
+            .tx_type(TxType::Transfer)
+            .from_user(from_user)
+            .to_user(to_user)
+            .amount(100)
+            .build()
+    }
+
+    #[test]
+    fn test_valid_transfers() {
+        let txs = vec![
+            transfer(1, 501, 502),
+            TransactionBuilder::new().id(2).to_user(501).build(),
+        ];
+
+        assert_eq!(validate_transfer_users(&txs), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_all_issues() {
+        let txs = vec![
+            transfer(1, 501, 502),
+            transfer(2, 501, 501),
+            transfer(3, 0, 502),
+            transfer(4, 502, 501),
+            transfer(5, 0, 0),
+            // пополнение с нулевым отправителем - норма
+            TransactionBuilder::new().id(6).to_user(501).build(),
+        ];
+
+        let got = validate_transfer_users(&txs);
+
+        assert_eq!(
+            got,
+            Err(vec![
+                TransferIssue::SelfTransfer { id: 2, user: 501 },
+                TransferIssue::MissingSender { id: 3 },
+                TransferIssue::MissingSender { id: 5 },
+                TransferIssue::MissingRecipient { id: 5 },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unique_ids() {
+        let txs = vec![transfer(1001, 501, 502), transfer(1002, 502, 501)];
+
+        assert!(check_unique_ids(&txs).is_ok());
+        assert!(check_unique_ids(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_id() {
+        let txs = vec![
+            transfer(1001, 501, 502),
+            transfer(1002, 502, 501),
+            transfer(1001, 501, 503),
+        ];
+
+        assert!(matches!(
+            check_unique_ids(&txs),
+            Err(ParseError::DuplicateId { id: 1001 })
+        ));
+    }
+
+    #[test]
+    fn test_check_timestamps() {
+        let tx = |timestamp| TransactionBuilder::new().timestamp(timestamp).build();
+
+        assert!(check_timestamps(&[tx(1672531200000)], DEFAULT_MAX_TIMESTAMP).is_ok());
+        assert!(check_timestamps(&[tx(DEFAULT_MAX_TIMESTAMP)], DEFAULT_MAX_TIMESTAMP).is_ok());
+        assert!(matches!(
+            check_timestamps(&[tx(1672531200000), tx(0)], DEFAULT_MAX_TIMESTAMP),
+            Err(ParseError::ImplausibleTimestamp { value: 0 })
+        ));
+        // 9999-12-31 — заведомо испорченное значение
+        assert!(matches!(
+            check_timestamps(&[tx(253402214400000)], DEFAULT_MAX_TIMESTAMP),
+            Err(ParseError::ImplausibleTimestamp {
+                value: 253402214400000
+            })
+        ));
+        // граница настраивается
+        assert!(check_timestamps(&[tx(1672531200000)], 1_000).is_err());
+    }
+}
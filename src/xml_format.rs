@@ -0,0 +1,345 @@
+use crate::error::{self, DumpError, ParseError};
+use crate::parser;
+use crate::types::{FIELD_NAMES, Transaction, TxStatus, TxType};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const ROOT: &str = "transactions";
+const ELEMENT: &str = "transaction";
+
+// Заменяет специальные символы XML на сущности. `\r` записывается ссылкой `&#13;`,
+// иначе парсер XML заменит `\r\n` на `\n`. Управляющие символы, запрещённые в XML 1.0,
+// нельзя записать даже ссылкой, поэтому для них возвращается ошибка.
+fn escape_text(s: &str) -> Result<String, DumpError> {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\r' => result.push_str("&#13;"),
+            '\t' | '\n' => result.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {
+                return Err(DumpError::InvalidXmlChar { ch: c });
+            }
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+// Раскрывает именованные и числовые сущности XML.
+fn unescape_text(s: &str) -> Result<String, ParseError> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find(';') else {
+            return Err(ParseError::InvalidFormat(format!(
+                "unterminated entity in {}",
+                s
+            )));
+        };
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or_else(|| ParseError::InvalidFormat(format!("unknown entity &{};", entity)))?,
+        };
+        result.push(c);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Последовательно разбирает документ, храня текущую позицию во входной строке.
+struct XmlReader<'a> {
+    input: &'a str,
+}
+
+impl<'a> XmlReader<'a> {
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    // Пропускает объявление `<?xml ... ?>` и комментарии `<!-- ... -->`.
+    fn skip_prolog(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.skip_whitespace();
+            let terminator = if self.input.starts_with("<?") {
+                "?>"
+            } else if self.input.starts_with("<!--") {
+                "-->"
+            } else {
+                return Ok(());
+            };
+            let Some(end) = self.input.find(terminator) else {
+                return Err(ParseError::InvalidFormat(
+                    "unterminated declaration".to_string(),
+                ));
+            };
+            self.input = &self.input[end + terminator.len()..];
+        }
+    }
+
+    fn try_tag(&mut self, tag: &str) -> bool {
+        self.skip_whitespace();
+        match self.input.strip_prefix(tag) {
+            Some(rest) => {
+                self.input = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect_tag(&mut self, tag: &str) -> Result<(), ParseError> {
+        if self.try_tag(tag) {
+            Ok(())
+        } else {
+            Err(ParseError::InvalidFormat(format!("expected {}", tag)))
+        }
+    }
+
+    // Читает открывающий тег поля и возвращает его имя.
+    fn read_open_tag(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_whitespace();
+        let name = self
+            .input
+            .strip_prefix('<')
+            .and_then(|rest| rest.split_once('>'))
+            .map(|(name, _)| name)
+            .filter(|name| !name.starts_with('/'))
+            .ok_or_else(|| ParseError::InvalidFormat("expected opening tag".to_string()))?;
+        self.input = &self.input[name.len() + 2..];
+        Ok(name)
+    }
+
+    // Читает текст до следующего тега без изменений (пробелы сохраняются).
+    fn read_text(&mut self) -> &'a str {
+        let end = self.input.find('<').unwrap_or(self.input.len());
+        let (text, rest) = self.input.split_at(end);
+        self.input = rest;
+        text
+    }
+}
+
+fn read_transaction(reader: &mut XmlReader) -> Result<Transaction, ParseError> {
+    let mut fields = HashMap::<&str, String>::with_capacity(FIELD_NAMES.len());
+    let close = format!("</{}>", ELEMENT);
+    while !reader.try_tag(&close) {
+        let name = reader.read_open_tag()?;
+        if !FIELD_NAMES.contains(&name) {
+            return Err(ParseError::InvalidFormat(format!("unknown field {}", name)));
+        }
+        let value = unescape_text(reader.read_text())?;
+        reader.expect_tag(&format!("</{}>", name))?;
+        if fields.insert(name, value).is_some() {
+            return Err(ParseError::InvalidFormat(format!(
+                "duplicate field {}",
+                name
+            )));
+        }
+    }
+
+    let get = |name: &str| {
+        fields
+            .get(name)
+            .ok_or_else(|| ParseError::InvalidFormat(format!("missing field {}", name)))
+    };
+    Ok(Transaction {
+        id: get("TX_ID")?.trim().parse()?,
+        r#type: get("TX_TYPE")?.trim().parse::<TxType>()?,
+        from_user: get("FROM_USER_ID")?.trim().parse()?,
+        to_user: get("TO_USER_ID")?.trim().parse()?,
+        amount: get("AMOUNT")?.trim().parse()?,
+        timestamp: get("TIMESTAMP")?.trim().parse()?,
+        status: get("STATUS")?.trim().parse::<TxStatus>()?,
+        description: get("DESCRIPTION")?.clone(),
+    })
+}
+
+/// Читает и парсит транзакции из XML формата.
+///
+/// Документ содержит корневой элемент `<transactions>` с дочерними `<transaction>`,
+/// поля которых записаны вложенными элементами. Пробелы между элементами игнорируются.
+///
+/// # Ошибки
+///
+/// Возвращает [`ParseError`], если:
+/// * Документ не соответствует ожидаемой структуре или значение поля некорректно.
+/// * Возникла ошибка ввода-вывода при чтении из `reader`.
+fn parse_from_xml(reader: &mut impl io::Read) -> Result<Vec<Transaction>, ParseError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut reader = XmlReader { input: &input };
+    reader.skip_prolog()?;
+    reader.expect_tag(&format!("<{}>", ROOT))?;
+    let mut result = Vec::new();
+    let close = format!("</{}>", ROOT);
+    let open = format!("<{}>", ELEMENT);
+    while !reader.try_tag(&close) {
+        reader.expect_tag(&open)?;
+        result.push(read_transaction(&mut reader)?);
+    }
+    reader.skip_whitespace();
+    if !reader.input.is_empty() {
+        return Err(ParseError::InvalidFormat(
+            "unexpected data after root element".to_string(),
+        ));
+    }
+    Ok(result)
+}
+
+/// Сериализует список транзакций в XML формат.
+///
+/// Возвращает количество записанных транзакций.
+///
+/// # Ошибки
+///
+/// Возвращает [`DumpError`], если:
+/// * Описание содержит символ, запрещённый в XML 1.0 ([`DumpError::InvalidXmlChar`]).
+/// * Произошла ошибка ввода-вывода при записи во `writer`.
+fn dump_as_xml(
+    writer: &mut impl io::Write,
+    transactions: &[Transaction],
+) -> Result<usize, DumpError> {
+    let mut writer = io::BufWriter::new(writer);
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<{}>", ROOT)?;
+    for tx in transactions {
+        let values = [
+            tx.id.to_string(),
+            tx.r#type.to_string(),
+            tx.from_user.to_string(),
+            tx.to_user.to_string(),
+            tx.amount.to_string(),
+            tx.timestamp.to_string(),
+            tx.status.to_string(),
+            escape_text(&tx.description)?,
+        ];
+        writeln!(writer, "  <{}>", ELEMENT)?;
+        for (name, value) in FIELD_NAMES.iter().zip(values) {
+            writeln!(writer, "    <{name}>{value}</{name}>")?;
+        }
+        writeln!(writer, "  </{}>", ELEMENT)?;
+    }
+    writeln!(writer, "</{}>", ROOT)?;
+    writer.flush()?;
+    Ok(transactions.len())
+}
+
+pub(crate) struct XmlParser;
+
+impl parser::Parser for XmlParser {
+    fn parse(reader: &mut impl io::Read) -> Result<Vec<Transaction>, error::ParseError> {
+        parse_from_xml(reader)
+    }
+
+    fn dump(
+        writer: &mut impl io::Write,
+        transactions: &[Transaction],
+    ) -> Result<usize, error::DumpError> {
+        dump_as_xml(writer, transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionBuilder;
+
+    #[test]
+    fn test_roundtrip_with_special_characters() {
+        let txs = vec![
+            TransactionBuilder::new()
+                .id(1001)
+                .to_user(501)
+                .amount(50000)
+                .description("a < b & \"c\" > 'd'")
+                .build(),
+            TransactionBuilder::new()
+                .id(1002)
+                .tx_type(TxType::Transfer)
+                .description("  two\nlines  ")
+                .build(),
+        ];
+        let mut buffer = Vec::new();
+
+        dump_as_xml(&mut buffer, &txs).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+
+        assert!(dumped.contains(
+            "<DESCRIPTION>a &lt; b &amp; &quot;c&quot; &gt; &apos;d&apos;</DESCRIPTION>"
+        ));
+        assert_eq!(parse_from_xml(&mut dumped.as_bytes()).unwrap(), txs);
+    }
+
+    #[test]
+    fn test_control_characters() {
+        let crlf = [TransactionBuilder::new().description("a\r\nb\tc").build()];
+        let mut buffer = Vec::new();
+
+        dump_as_xml(&mut buffer, &crlf).unwrap();
+        let dumped = String::from_utf8(buffer).unwrap();
+        assert!(dumped.contains("<DESCRIPTION>a&#13;\nb\tc</DESCRIPTION>"));
+        assert_eq!(parse_from_xml(&mut dumped.as_bytes()).unwrap(), crlf);
+
+        for ch in ['\u{0}', '\u{1}', '\u{1f}', '\u{ffff}'] {
+            let txs = [TransactionBuilder::new()
+                .description(format!("x{}y", ch))
+                .build()];
+            assert!(matches!(
+                dump_as_xml(&mut Vec::new(), &txs),
+                Err(DumpError::InvalidXmlChar { ch: c }) if c == ch
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_tolerates_whitespace() {
+        let input = "<transactions><transaction>\n\
+                     <TX_ID> 7 </TX_ID><TX_TYPE>FEE</TX_TYPE>\t<FROM_USER_ID>1</FROM_USER_ID>\
+                     <TO_USER_ID>0</TO_USER_ID><AMOUNT>10</AMOUNT><TIMESTAMP>5</TIMESTAMP>\
+                     <STATUS>SUCCESS</STATUS><DESCRIPTION>x &#38; &#x3C;</DESCRIPTION>\n\
+                     </transaction>  </transactions>\n";
+
+        let got = parse_from_xml(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].id, 7);
+        assert_eq!(got[0].r#type, TxType::Fee);
+        assert_eq!(got[0].description, "x & <");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing = "<transactions><transaction><TX_ID>1</TX_ID></transaction></transactions>";
+        let unclosed = "<transactions><transaction><TX_ID>1</TX_ID>";
+
+        for input in [missing, unclosed, "<root></root>"] {
+            assert!(matches!(
+                parse_from_xml(&mut input.as_bytes()),
+                Err(ParseError::InvalidFormat(_))
+            ));
+        }
+        assert!(
+            parse_from_xml(&mut "<transactions></transactions>".as_bytes())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
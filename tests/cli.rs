@@ -0,0 +1,597 @@
+//! Сквозные тесты бинарников: разбор аргументов, вывод и коды завершения
+//! (1 - ошибка разбора, 2 - ошибка записи, 3 - ошибка использования, 4 - ошибка ввода-вывода).
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+const CSV: &str = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+1002,TRANSFER,501,502,15000,1672534800000,FAILURE,"Payment for services, invoice #123"
+"##;
+
+// Создаёт во временном каталоге файл `name` с содержимым `content`.
+fn fixture(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_converter_csv_to_text() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TX_ID: 1001"))
+        .stdout(predicate::str::contains("TX_TYPE: TRANSFER"))
+        .stderr(predicate::str::contains("Записано транзакций: 2"));
+}
+
+#[test]
+fn test_converter_roundtrip_through_bin() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    let bin = cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "bin"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let bin_path = dir.path().join("out.bin");
+    fs::write(&bin_path, bin).unwrap();
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&bin_path)
+        .args(["--input-format", "bin", "--output-format", "csv"])
+        .assert()
+        .success()
+        .stdout(CSV);
+}
+
+#[test]
+fn test_converter_writes_output_file() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+    // существующий файл перезаписывается
+    let bin_path = fixture(&dir, "out.bin", "stale content that is longer than nothing");
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "bin"])
+        .arg("--output-file")
+        .arg(&bin_path)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("Записано транзакций: 2"));
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&bin_path)
+        .args(["--input-format", "bin", "--output-format", "csv"])
+        .assert()
+        .success()
+        .stdout(CSV);
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn test_converter_compresses_gz_output_file() {
+    use std::io::Read;
+
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+    let gz_path = dir.path().join("out.csv.gz");
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "csv"])
+        .arg("--output-file")
+        .arg(&gz_path)
+        .assert()
+        .success()
+        .stdout("");
+
+    let mut decompressed = String::new();
+    flate2::read::GzDecoder::new(fs::File::open(&gz_path).unwrap())
+        .read_to_string(&mut decompressed)
+        .unwrap();
+    assert_eq!(decompressed, CSV);
+}
+
+#[test]
+fn test_converter_usage_error_keeps_output_file() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+    let existing = fixture(&dir, "existing.bin", "previous output");
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args([
+            "--input-format",
+            "csv",
+            "--output-format",
+            "csv",
+            "--pretty",
+        ])
+        .arg("--output-file")
+        .arg(&existing)
+        .assert()
+        .code(3);
+
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "previous output");
+}
+
+#[test]
+fn test_converter_output_file_not_creatable_exits_4() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .arg("--output-file")
+        .arg(dir.path().join("missing").join("out.txt"))
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("невозможно создать файл"));
+}
+
+#[test]
+fn test_converter_reads_stdin() {
+    cargo_bin_cmd!("ypbank_converter")
+        .args([
+            "--input-file",
+            "-",
+            "--input-format",
+            "csv",
+            "--output-format",
+            "text",
+        ])
+        .write_stdin(CSV)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TX_ID: 1001"))
+        .stdout(predicate::str::contains(
+            "DESCRIPTION: \"Payment for services, invoice #123\"",
+        ));
+}
+
+#[test]
+fn test_converter_parse_error_exits_1() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(
+        &dir,
+        "broken.csv",
+        "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+         1001,DEPOSIT,0,501,lots,1672531200000,SUCCESS,\"broken\"\n",
+    );
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("broken.csv"));
+}
+
+// Запись в /dev/full всегда завершается ошибкой ENOSPC.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_converter_dump_error_exits_2() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    // `assert_cmd::Command` перехватывает stdout, поэтому запускаем процесс напрямую.
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_ypbank_converter"))
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--output-format", "csv"])
+        .stdout(fs::File::create("/dev/full").unwrap())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn test_converter_bad_format_exits_3() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "xlsx", "--output-format", "csv"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("xlsx"));
+}
+
+#[test]
+fn test_converter_missing_file_exits_4() {
+    let dir = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(dir.path().join("missing.csv"))
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("missing.csv"));
+}
+
+// Каталог нельзя прочитать как файл: открытие проходит, а чтение - нет.
+#[test]
+fn test_converter_unreadable_input_exits_4() {
+    let dir = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(dir.path())
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_converter_check_reports_count() {
+    let dir = TempDir::new().unwrap();
+    let input = fixture(&dir, "in.csv", CSV);
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "csv", "--check"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("OK: 2 transactions\n");
+}
+
+#[test]
+fn test_converter_check_rejects_corrupted_file() {
+    let dir = TempDir::new().unwrap();
+    let mut bin = fs::read("example_data/transactions.bin").unwrap();
+    bin.truncate(bin.len() - 3);
+    let input = dir.path().join("broken.bin");
+    fs::write(&input, bin).unwrap();
+
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&input)
+        .args(["--input-format", "bin", "--check"])
+        .assert()
+        .failure()
+        .stdout("");
+}
+
+#[test]
+fn test_converter_help_exits_0() {
+    cargo_bin_cmd!("ypbank_converter")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--input-format"));
+}
+
+#[test]
+fn test_comparer_identical_files() {
+    let dir = TempDir::new().unwrap();
+    let csv = fixture(&dir, "a.csv", CSV);
+    let text = cargo_bin_cmd!("ypbank_converter")
+        .arg("--input-file")
+        .arg(&csv)
+        .args(["--input-format", "csv", "--output-format", "text"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let text = fixture(&dir, "a.txt", &String::from_utf8(text).unwrap());
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&csv)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&text)
+        .args(["--format2", "text"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("идентичны"));
+}
+
+#[test]
+fn test_comparer_reports_mismatch() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let second = fixture(&dir, "b.csv", &CSV.replace("15000", "15001"));
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&second)
+        .args(["--format2", "csv"])
+        .assert()
+        .code(5)
+        .stdout(predicate::str::contains("amount: 15000 != 15001"));
+}
+
+#[test]
+fn test_comparer_exit_code_reflects_result() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let same = fixture(&dir, "b.csv", CSV);
+    let header_and_one = CSV.lines().take(2).collect::<Vec<_>>().join("\n");
+    let shorter = fixture(&dir, "c.csv", &header_and_one);
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&same)
+        .args(["--format2", "csv"])
+        .assert()
+        .code(0);
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&shorter)
+        .args(["--format2", "csv"])
+        .assert()
+        .code(5)
+        .stdout(predicate::str::contains("отсутствует во втором файле"));
+}
+
+#[test]
+fn test_comparer_count_only_equal() {
+    let dir = TempDir::new().unwrap();
+    let csv = fixture(&dir, "a.csv", CSV);
+    // содержимое отличается, но количество совпадает
+    let changed = fixture(&dir, "b.csv", &CSV.replace("15000", "15001"));
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&csv)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&changed)
+        .args(["--format2", "csv", "--count-only"])
+        .assert()
+        .success()
+        .stdout("Количество транзакций совпадает: 2\n");
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .args([
+            "--file1",
+            "example_data/transactions.bin",
+            "--format1",
+            "bin",
+            "--file2",
+            "example_data/transactions.csv",
+            "--format2",
+            "csv",
+            "--count-only",
+        ])
+        .assert()
+        .success()
+        .stdout("Количество транзакций совпадает: 3\n");
+}
+
+#[test]
+fn test_comparer_count_only_differs() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let header_and_one = CSV.lines().take(2).collect::<Vec<_>>().join("\n");
+    let second = fixture(&dir, "b.csv", &header_and_one);
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&second)
+        .args(["--format2", "csv", "--count-only"])
+        .assert()
+        .code(5)
+        .stdout("Количество транзакций не совпадает: 2 в file1, 1 в file2\n");
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&second)
+        .args(["--format2", "csv", "--count-only", "--fingerprint"])
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_comparer_count_only_exit_code() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let same_count = fixture(&dir, "b.csv", &CSV.replace("15000", "15001"));
+    let header_and_one = CSV.lines().take(2).collect::<Vec<_>>().join("\n");
+    let fewer = fixture(&dir, "c.csv", &header_and_one);
+
+    for (second, code) in [(&same_count, 0), (&fewer, 5)] {
+        cargo_bin_cmd!("ypbank_comparer")
+            .arg("--file1")
+            .arg(&first)
+            .args(["--format1", "csv", "--file2"])
+            .arg(second)
+            .args(["--format2", "csv", "--count-only"])
+            .assert()
+            .code(code);
+    }
+}
+
+#[test]
+fn test_comparer_reads_one_file_from_stdin() {
+    cargo_bin_cmd!("ypbank_comparer")
+        .args([
+            "--file1",
+            "-",
+            "--format1",
+            "csv",
+            "--file2",
+            "example_data/transactions.csv",
+            "--format2",
+            "csv",
+        ])
+        .write_stdin(fs::read_to_string("example_data/transactions.csv").unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("идентичны"));
+}
+
+#[test]
+fn test_comparer_rejects_two_stdin_inputs() {
+    cargo_bin_cmd!("ypbank_comparer")
+        .args([
+            "--file1",
+            "-",
+            "--format1",
+            "csv",
+            "--file2",
+            "-",
+            "--format2",
+            "csv",
+        ])
+        .write_stdin(CSV)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_comparer_parse_error_exits_1() {
+    let dir = TempDir::new().unwrap();
+    let good = fixture(&dir, "a.csv", CSV);
+    let bad = fixture(&dir, "b.csv", "not a csv header\n");
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&good)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&bad)
+        .args(["--format2", "csv"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_comparer_missing_argument_exits_3() {
+    cargo_bin_cmd!("ypbank_comparer")
+        .args(["--file1", "a.csv", "--format1", "csv"])
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_comparer_missing_file_exits_4() {
+    let dir = TempDir::new().unwrap();
+    let good = fixture(&dir, "a.csv", CSV);
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&good)
+        .args(["--format1", "csv", "--file2"])
+        .arg(dir.path().join("missing.csv"))
+        .args(["--format2", "csv"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_comparer_dump_diffs_not_creatable_exits_4() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let second = fixture(&dir, "b.csv", &CSV.replace("15000", "15001"));
+    let target = dir.path().join("missing").join("diffs.csv");
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&second)
+        .args(["--format2", "csv", "--dump-diffs", "csv"])
+        .arg(&target)
+        .assert()
+        .code(4)
+        .stdout("")
+        .stderr(predicate::str::contains("невозможно создать файл"));
+}
+
+#[test]
+fn test_comparer_dump_diffs_bad_format_exits_3() {
+    let dir = TempDir::new().unwrap();
+    let first = fixture(&dir, "a.csv", CSV);
+    let second = fixture(&dir, "b.csv", &CSV.replace("15000", "15001"));
+    let target = dir.path().join("diffs.out");
+
+    cargo_bin_cmd!("ypbank_comparer")
+        .arg("--file1")
+        .arg(&first)
+        .args(["--format1", "csv", "--file2"])
+        .arg(&second)
+        .args(["--format2", "csv", "--dump-diffs", "yaml"])
+        .arg(&target)
+        .assert()
+        .code(3)
+        .stdout("")
+        .stderr(predicate::str::contains("неизвестный формат yaml"));
+    assert!(!target.exists());
+}
+
+#[test]
+fn test_stats_verify_bin() {
+    cargo_bin_cmd!("ypbank_stats")
+        .args([
+            "--input-file",
+            "example_data/transactions.bin",
+            "--input-format",
+            "bin",
+            "--verify",
+        ])
+        .assert()
+        .success()
+        .stdout("OK: 3 records\n");
+}
+
+#[test]
+fn test_stats_exit_codes() {
+    cargo_bin_cmd!("ypbank_stats")
+        .args([
+            "--input-file",
+            "example_data/missing.csv",
+            "--input-format",
+            "csv",
+        ])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("невозможно открыть файл"));
+
+    cargo_bin_cmd!("ypbank_stats")
+        .args(["--input-format", "csv"])
+        .assert()
+        .code(3);
+}
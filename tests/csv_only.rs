@@ -0,0 +1,42 @@
+//! Проверка сборки с одним форматом: `cargo test --no-default-features --features csv --test csv_only`.
+//! Тест работает и с фичами по умолчанию, но смысл имеет именно в урезанной сборке.
+#![cfg(feature = "csv")]
+
+use ypbank_parser::types::SupportedFileFormat;
+use ypbank_parser::{dump, parse, parse_from_csv_with};
+
+const CSV: &str = r##"TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
+1001,DEPOSIT,0,501,50000,1672531200000,SUCCESS,"Initial account funding"
+1002,TRANSFER,501,502,15000,1672534800000,FAILURE,"Payment for services, invoice #123"
+"##;
+
+#[test]
+fn test_csv_roundtrip() {
+    let txs = parse(&mut CSV.as_bytes(), SupportedFileFormat::Csv).unwrap();
+    assert_eq!(txs.len(), 2);
+    assert_eq!(txs[1].description, "Payment for services, invoice #123");
+    assert_eq!(
+        parse_from_csv_with(&mut CSV.as_bytes(), &Default::default()).unwrap(),
+        txs
+    );
+
+    let mut buffer = Vec::new();
+    dump(&mut buffer, SupportedFileFormat::Csv, &txs).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), CSV);
+}
+
+#[test]
+fn test_disabled_formats_are_unknown() {
+    assert_eq!(
+        "csv".parse::<SupportedFileFormat>().unwrap(),
+        SupportedFileFormat::Csv
+    );
+    assert_eq!(
+        "bin".parse::<SupportedFileFormat>().is_ok(),
+        cfg!(feature = "bin")
+    );
+    assert_eq!(
+        "text".parse::<SupportedFileFormat>().is_ok(),
+        cfg!(feature = "text")
+    );
+}
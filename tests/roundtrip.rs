@@ -0,0 +1,23 @@
+//! Property-based проверка: для любого набора транзакций и любого формата
+//! `parse(dump(txs)) == txs`. Запуск: `cargo test --features proptest --test roundtrip`.
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use ypbank_parser::types::{SupportedFileFormat, Transaction};
+use ypbank_parser::{dump, parse};
+
+proptest! {
+    #[test]
+    fn test_roundtrip_all_formats(
+        txs in proptest::collection::vec(any::<Transaction>(), 0..8)
+    ) {
+        for &format in SupportedFileFormat::ALL {
+            let mut buffer = Vec::new();
+            prop_assert_eq!(dump(&mut buffer, format, &txs).unwrap(), txs.len());
+
+            let parsed = parse(&mut buffer.as_slice(), format);
+            prop_assert!(parsed.is_ok(), "{}: {:?}", format, parsed);
+            prop_assert_eq!(parsed.unwrap(), txs.clone(), "format {}", format);
+        }
+    }
+}